@@ -0,0 +1,68 @@
+use sf_api::{
+    gamestate::GameState,
+    response::Response,
+    simulate::calibration::{CalibrationReport, RecordedFight},
+    transport::read_cassette,
+};
+
+/// Replays the arena fights of a cassette, that has been recorded with a
+/// `RecordingTransport` on a real server, in the simulator and prints where
+/// the simulator disagrees with the server. Look at every opponent with
+/// `ViewPlayer` right before fighting them, so that their equipment is known.
+/// There is no default cassette, because the mock server just lets the higher
+/// level win, so its fights say nothing about the simulator
+pub fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        println!("Usage: calibrate <cassette recorded on a real server>");
+        return;
+    };
+    let entries = read_cassette(&path).unwrap();
+
+    let mut report = CalibrationReport::default();
+    let mut gs: Option<GameState> = None;
+    for entry in entries {
+        let now = chrono::Local::now().naive_local();
+        let resp = Response::parse(entry.response, now).unwrap();
+        let gs = match &mut gs {
+            Some(gs) => {
+                gs.update(resp).unwrap();
+                gs
+            }
+            None => gs.insert(GameState::new(resp).unwrap()),
+        };
+        let Some(fight) = &gs.last_fight else {
+            continue;
+        };
+        let own_id = i64::from(gs.character.player_id);
+        let other = fight
+            .fights
+            .first()
+            .into_iter()
+            .flat_map(|a| [&a.fighter_a, &a.fighter_b])
+            .flatten()
+            .find(|a| a.id != own_id)
+            .and_then(|a| gs.lookup.lookup_pid(a.id.try_into().ok()?));
+
+        match other.and_then(|other| RecordedFight::new(gs, other, fight)) {
+            Some(recorded) => report.add_fight(&recorded, 10_000),
+            None => println!("Skipping {}", entry.command),
+        }
+    }
+
+    for (class, other, pairing) in report.disagreements(0.1) {
+        println!("{class:?} vs {other:?}:");
+        println!(
+            "  won {:.1}% on the server, {:.1}% in the simulator",
+            pairing.server_win_rate() * 100.0,
+            pairing.simulated_win_rate() * 100.0
+        );
+        println!(
+            "  {}/{} hits and {}/{} crits out of range, up to {:.1}% off",
+            pairing.hits_out_of_range,
+            pairing.hits,
+            pairing.crits_out_of_range,
+            pairing.crits,
+            pairing.max_deviation * 100.0
+        );
+    }
+}
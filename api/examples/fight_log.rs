@@ -0,0 +1,57 @@
+use sf_api::{
+    gamestate::{arena::SingleFight, GameState},
+    response::Response,
+    transport::read_cassette,
+};
+
+/// Replays the responses of a cassette, that has been recorded with a
+/// `RecordingTransport`, and prints every round of the fights in it
+pub fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        println!("Usage: fight_log <cassette with fights>");
+        return;
+    };
+    let entries = read_cassette(&path).unwrap();
+
+    let mut gs: Option<GameState> = None;
+    for entry in entries {
+        let now = chrono::Local::now().naive_local();
+        let resp = Response::parse(entry.response, now).unwrap();
+        match &mut gs {
+            Some(gs) => gs.update(resp).unwrap(),
+            None => gs = Some(GameState::new(resp).unwrap()),
+        }
+        let Some(fight) = gs.as_ref().and_then(|a| a.last_fight.as_ref())
+        else {
+            continue;
+        };
+
+        println!("{}", entry.command);
+        for single in &fight.fights {
+            print_fight(single);
+        }
+        println!("won: {}\n", fight.has_player_won);
+    }
+}
+
+fn print_fight(fight: &SingleFight) {
+    for action in &fight.actions {
+        println!(
+            "  {} -> {:?}: {:?}{} for {:?}, {} life left",
+            action.acting_id,
+            action.target_id,
+            action.action,
+            if action.critical { " (crit)" } else { "" },
+            action.damage,
+            action.other_new_life
+        );
+    }
+    for fighter in [&fight.fighter_a, &fight.fighter_b].into_iter().flatten() {
+        println!(
+            "  {} ({:?}): {:?}",
+            fighter.name.as_deref().unwrap_or("?"),
+            fighter.class,
+            fight.stats(fighter.id)
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use std::sync::Arc;
+
+use sf_api::mock_server::{MockServer, MockWorld};
+
+#[tokio::main]
+pub async fn main() {
+    // Serves a small world on a local port. Log in with `mock`/`mock` and
+    // the printed url to test things without talking to the real servers
+    let server = Arc::new(MockServer::new(MockWorld::sample(100)));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    println!("Listening on http://{}/", listener.local_addr().unwrap());
+    server.serve(listener).await.unwrap();
+}
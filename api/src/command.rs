@@ -738,7 +738,7 @@ pub enum Command {
     BuyGoldFrame,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, FromPrimitive)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// This is the "Questing instead of expeditions" value in the settings
 pub enum ExpeditionSetting {
@@ -0,0 +1,431 @@
+use std::collections::{HashMap, HashSet};
+
+use log::{trace, warn};
+
+use crate::{
+    command::Command,
+    error::SFError,
+    gamestate::{
+        social::{HallOfFamePlayer, OtherPlayer},
+        unlockables::EquipmentIdent,
+        GameState,
+    },
+    session::SimpleSession,
+    simulate::{PlayerFighterSquad, UpgradeableFighter},
+    PlayerId,
+};
+
+/// The amount of players the server returns for one `HallOfFamePage`
+const HOF_PAGE_SIZE: usize = 51;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Decides, which players in the Hall of Fame the crawler looks at. The
+/// crawler sends its requests as fast as the session lets it, so give the
+/// session a rate limit, like `RateLimitOptions::conservative()`, before
+/// crawling. Sending hundreds of `ViewPlayer` requests without any delay is a
+/// good way to get yourself noticed
+pub struct CrawlerOptions {
+    /// The lowest level a player can have to be looked at
+    pub min_level: u32,
+    /// The highest level a player can have to be looked at
+    pub max_level: u32,
+    /// The first rank in the Hall of Fame the crawler starts at
+    pub min_rank: u32,
+    /// The last rank in the Hall of Fame the crawler looks at
+    pub max_rank: u32,
+}
+
+impl Default for CrawlerOptions {
+    fn default() -> Self {
+        Self {
+            min_level: 1,
+            max_level: u32::MAX,
+            min_rank: 1,
+            max_rank: u32::MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A player, that has items equipped, which are missing in the scrapbook of
+/// the own character
+pub struct CrawlTarget {
+    pub player_id: PlayerId,
+    pub name: String,
+    pub level: u16,
+    pub rank: u32,
+    /// The items this player has equipped, that are not yet in the scrapbook
+    pub missing: Vec<EquipmentIdent>,
+    /// The share of simulated fights against this player, that the character
+    /// has won. Only set for targets from `simulated_targets()`
+    pub win_probability: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Pages through the Hall of Fame and looks at every player in the configured
+/// window to figure out, who is worth attacking for new scrapbook items. The
+/// crawler keeps every player it has looked at, so you can store it and
+/// continue where you left off later, without querying anyone again
+pub struct HallOfFameCrawler {
+    options: CrawlerOptions,
+    /// The next page of the Hall of Fame, that will be fetched
+    next_page: usize,
+    /// The players, that were in the level/rank window so far, by their name
+    players: HashMap<String, OtherPlayer>,
+    finished: bool,
+}
+
+impl HallOfFameCrawler {
+    /// Creates a new crawler, that starts at the page containing `min_rank`
+    #[must_use]
+    pub fn new(options: CrawlerOptions) -> Self {
+        let next_page =
+            options.min_rank.saturating_sub(1) as usize / HOF_PAGE_SIZE;
+        Self {
+            options,
+            next_page,
+            players: HashMap::new(),
+            finished: false,
+        }
+    }
+
+    /// The options this crawler has been created with
+    #[must_use]
+    pub fn options(&self) -> &CrawlerOptions {
+        &self.options
+    }
+
+    /// The page of the Hall of Fame, that will be fetched next
+    #[must_use]
+    pub fn next_page(&self) -> usize {
+        self.next_page
+    }
+
+    /// All the players the crawler has looked at so far
+    pub fn players(&self) -> impl Iterator<Item = &OtherPlayer> {
+        self.players.values()
+    }
+
+    /// Checks if all pages in the rank window have been crawled
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn in_window(&self, player: &HallOfFamePlayer) -> bool {
+        let o = &self.options;
+        (o.min_rank..=o.max_rank).contains(&player.rank)
+            && (o.min_level..=o.max_level).contains(&player.level)
+    }
+
+    /// Fetches the next page of the Hall of Fame and looks at every player on
+    /// it, that is in the configured window and has not been looked at
+    /// before. Returns true, if there are more pages left to crawl
+    ///
+    /// # Errors
+    /// Have a look at `SimpleSession::send_command` for a full list of
+    /// possible errors. The page is only marked as done, once every player
+    /// on it has been looked at, so you can just call this again after an
+    /// error
+    pub async fn crawl_page(
+        &mut self,
+        session: &mut SimpleSession,
+    ) -> Result<bool, SFError> {
+        if self.finished {
+            return Ok(false);
+        }
+
+        trace!("Crawling hall of fame page {}", self.next_page);
+        let gs = session
+            .send_command(Command::HallOfFamePage {
+                page: self.next_page,
+            })
+            .await?;
+
+        let page_len = gs.hall_of_fames.players.len();
+        let total = gs.hall_of_fames.players_total;
+        let names: Vec<_> = gs
+            .hall_of_fames
+            .players
+            .iter()
+            .filter(|a| self.in_window(a))
+            .map(|a| a.name.clone())
+            .collect();
+
+        for name in names {
+            if self.players.contains_key(&name) {
+                continue;
+            }
+            let mut player = session
+                .game_state()
+                .and_then(|gs| gs.lookup.lookup_name(&name))
+                .cloned();
+            if player.is_none() {
+                let gs = session
+                    .send_command(Command::ViewPlayer {
+                        ident: name.clone(),
+                    })
+                    .await?;
+                player = gs.lookup.lookup_name(&name).cloned();
+            }
+            if let Some(player) = player {
+                self.players.insert(name, player);
+            } else {
+                warn!("Could not look at {name}");
+            }
+        }
+
+        let last_rank = (self.next_page + 1) * HOF_PAGE_SIZE;
+        self.next_page += 1;
+        self.finished = page_len == 0
+            || last_rank >= self.options.max_rank as usize
+            || last_rank >= total as usize;
+        Ok(!self.finished)
+    }
+
+    /// Crawls all remaining pages in the rank window and returns the sorted
+    /// targets afterwards. Have a look at `targets()` for the sorting
+    ///
+    /// # Errors
+    /// Have a look at `crawl_page` for a full list of possible errors
+    pub async fn crawl(
+        &mut self,
+        session: &mut SimpleSession,
+    ) -> Result<Vec<CrawlTarget>, SFError> {
+        while self.crawl_page(session).await? {}
+        Ok(session
+            .game_state()
+            .map(|gs| self.targets(gs))
+            .unwrap_or_default())
+    }
+
+    /// Scores every player the crawler has seen so far by the amount of
+    /// items they have equipped, that are missing in the scrapbook of the
+    /// character. The best target is at the front. Players without any new
+    /// items are not included
+    #[must_use]
+    pub fn targets(&self, gs: &GameState) -> Vec<CrawlTarget> {
+        let Some(scrapbook) = &gs.character.scrapbook else {
+            warn!("Can not rank targets without a scrapbook");
+            return Vec::new();
+        };
+
+        let mut done = HashSet::new();
+        let mut targets: Vec<_> = self
+            .players
+            .values()
+            .filter(|other| done.insert(other.player_id))
+            .map(|other: &OtherPlayer| CrawlTarget {
+                player_id: other.player_id,
+                name: other.name.clone(),
+                level: other.level,
+                rank: other.rank,
+                missing: scrapbook.missing_items(other),
+                win_probability: None,
+            })
+            .filter(|a| !a.missing.is_empty())
+            .collect();
+
+        targets.sort_by(|a, b| {
+            b.missing.len().cmp(&a.missing.len()).then(a.rank.cmp(&b.rank))
+        });
+        targets
+    }
+
+    /// Same as `targets()`, but also simulates `iterations` fights against
+    /// every target. Everyone, that the character beats less often than
+    /// `min_win_probability`, is dropped. The remaining targets are sorted by
+    /// the amount of new items you can expect from attacking them
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn simulated_targets(
+        &self,
+        gs: &GameState,
+        iterations: u32,
+        min_win_probability: f64,
+    ) -> Vec<CrawlTarget> {
+        let squad = PlayerFighterSquad::new(gs);
+        let mut targets: Vec<_> = self
+            .targets(gs)
+            .into_iter()
+            .filter_map(|mut target| {
+                let other = self.players.get(&target.name)?;
+                let other = UpgradeableFighter::from_other(other);
+                let win_prob = squad.win_probability(&other, iterations);
+                target.win_probability = Some(win_prob);
+                (win_prob >= min_win_probability).then_some(target)
+            })
+            .collect();
+
+        let expected_items = |a: &CrawlTarget| {
+            a.missing.len() as f64 * a.win_probability.unwrap_or_default()
+        };
+        targets.sort_by(|a, b| expected_items(b).total_cmp(&expected_items(a)));
+        targets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        mock_server::{tests::mock_session, MockServer, MockWorld},
+        rate_limit::{RateBudget, RateLimitOptions},
+        session::{ConnectionOptions, ServerConnection, Session},
+        transport::{Transport, TransportFuture, TransportRequest},
+    };
+
+    fn options() -> CrawlerOptions {
+        CrawlerOptions {
+            min_level: 60,
+            max_level: 120,
+            min_rank: 10,
+            max_rank: 80,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn crawl_the_window() {
+        let (server, mut session) = mock_session(MockWorld::sample(120));
+        let mut crawler = HallOfFameCrawler::new(options());
+        let targets = crawler.crawl(&mut session).await.unwrap();
+        assert!(crawler.is_finished());
+        assert!(!crawler.crawl_page(&mut session).await.unwrap());
+
+        // The sample world ranks players by their position
+        let world = server.world();
+        let expected: HashSet<_> = world.players[9..80]
+            .iter()
+            .filter(|a| (60..=120).contains(&a.level))
+            .map(|a| a.name.as_str())
+            .collect();
+        let seen: HashSet<_> =
+            crawler.players().map(|a| a.name.as_str()).collect();
+        assert_eq!(seen, expected);
+
+        let with_items = world.players[9..80]
+            .iter()
+            .filter(|a| expected.contains(a.name.as_str()))
+            .filter(|a| a.equipment.values().any(Option::is_some))
+            .count();
+        assert_eq!(targets.len(), with_items);
+        for pair in targets.windows(2) {
+            assert!(pair[0].missing.len() >= pair[1].missing.len());
+        }
+        for target in &targets {
+            let player = world
+                .players
+                .iter()
+                .find(|a| a.player_id == target.player_id)
+                .unwrap();
+            let equipped = player.equipment.values().flatten().count();
+            assert_eq!(target.missing.len(), equipped);
+        }
+    }
+
+    #[derive(Debug)]
+    /// Counts the requests and how often players are looked at
+    struct CountRequests {
+        inner: Arc<MockServer>,
+        total: AtomicUsize,
+        look_at: AtomicUsize,
+    }
+
+    impl CountRequests {
+        fn new(inner: Arc<MockServer>) -> Arc<Self> {
+            Arc::new(Self {
+                inner,
+                total: AtomicUsize::new(0),
+                look_at: AtomicUsize::new(0),
+            })
+        }
+
+        /// A new session, that talks to the server through this counter
+        fn session(
+            self: &Arc<Self>,
+            options: ConnectionOptions,
+        ) -> SimpleSession {
+            let connection = ServerConnection::new_with_options(
+                "http://mock.local/",
+                options,
+            )
+            .unwrap();
+            let mut session = Session::new("mock", "mock", connection);
+            session.set_transport(self.clone());
+            SimpleSession::new(session)
+        }
+    }
+
+    impl Transport for CountRequests {
+        fn send<'a>(
+            &'a self,
+            request: TransportRequest<'a>,
+        ) -> TransportFuture<'a> {
+            self.total.fetch_add(1, Ordering::Relaxed);
+            if request.command.starts_with("PlayerLookAt:") {
+                self.look_at.fetch_add(1, Ordering::Relaxed);
+            }
+            self.inner.send(request)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resume_without_looking_again() {
+        let (server, mut session) = mock_session(MockWorld::sample(120));
+        let mut crawler = HallOfFameCrawler::new(options());
+        assert!(crawler.crawl_page(&mut session).await.unwrap());
+        assert_eq!(crawler.next_page(), 1);
+        let seen = crawler.players().count();
+        assert!(seen > 0);
+
+        // A new session knows nobody, so only the crawler can remember, who
+        // has already been looked at
+        let counter = CountRequests::new(server);
+        let mut session = counter.session(ConnectionOptions::default());
+
+        crawler.next_page = 0;
+        crawler.crawl_page(&mut session).await.unwrap();
+        assert_eq!(crawler.players().count(), seen);
+        assert_eq!(counter.look_at.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn paced_by_the_session() {
+        let counter = CountRequests::new(Arc::new(MockServer::new(
+            MockWorld::sample(120),
+        )));
+        let budget = RateBudget {
+            requests: 1,
+            interval: Duration::from_secs(1),
+            burst: 1,
+        };
+        let limited = ConnectionOptions {
+            rate_limit: RateLimitOptions {
+                per_session: Some(budget),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut session = counter.session(limited);
+        let mut crawler = HallOfFameCrawler::new(options());
+
+        let start = tokio::time::Instant::now();
+        crawler.crawl(&mut session).await.unwrap();
+        let elapsed = start.elapsed().as_secs();
+
+        // The first request is sent right away and every other one has to
+        // wait for the budget. The crawler does not wait on top of that
+        let requests = counter.total.load(Ordering::Relaxed) as u64;
+        assert!(requests > 10);
+        assert_eq!(elapsed, requests - 1);
+    }
+}
@@ -37,6 +37,9 @@ pub enum SFError {
         /// The full array in debug print
         array: String,
     },
+    /// A local file, like a server list, or a cassette, could not be read, or
+    /// written
+    IOError(String),
 }
 
 impl Error for SFError {
@@ -81,6 +84,9 @@ impl Display for SFError {
                      the response is too short. The response is: {array}"
                 ))
             }
+            SFError::IOError(e) => {
+                f.write_fmt(format_args!("Could not access a file: {e}"))
+            }
         }
     }
 }
@@ -113,11 +113,18 @@ pub struct SingleFight {
     pub fighter_a: Option<Fighter>,
     /// The stats of the first fighter
     pub fighter_b: Option<Fighter>,
-    /// The action this fight involved. Note that this will likely be changed
-    /// in the future, as is it hard to interpret
+    /// The actions this fight involved in the order they have happened in
     pub actions: Vec<FightAction>,
 }
 
+/// The amount of values every round has in the fight log of fight versions
+/// above 1. These are, in order: the acting fighter, the target, the action
+/// type, whether or not the action was a critical hit, the damage, the life
+/// of the target and the life of the acting fighter after the action and the
+/// class skill, that was used (0 = none). This layout has not been checked
+/// against the logs of the real servers yet
+const ROUND_LEN: usize = 8;
+
 impl SingleFight {
     pub(crate) fn update_fighters(&mut self, data: &str) {
         let data = data.split('/').collect::<Vec<_>>();
@@ -141,8 +148,7 @@ impl SingleFight {
         self.actions.clear();
 
         if fight_version > 1 {
-            // TODO: Actually parse this
-            return Ok(());
+            return self.update_rounds_v2(data);
         }
         let mut iter = data.split(',');
         while let (Some(player_id), Some(damage_typ), Some(new_life)) =
@@ -155,18 +161,152 @@ impl SingleFight {
                 acting_id: player_id.parse().map_err(|_| {
                     SFError::ParsingError("action pid", player_id.to_string())
                 })?,
+                target_id: None,
                 action: FightActionType::parse(action),
+                critical: matches!(action, 1 | 25),
+                damage: None,
                 other_new_life: new_life.parse().map_err(|_| {
                     SFError::ParsingError(
                         "action new life",
                         player_id.to_string(),
                     )
                 })?,
+                acting_new_life: None,
+                skill: None,
             });
         }
 
         Ok(())
     }
+
+    fn update_rounds_v2(&mut self, data: &str) -> Result<(), SFError> {
+        let values = data
+            .split(',')
+            .filter(|a| !a.is_empty())
+            .map(|a| a.trim().parse::<i64>())
+            .collect::<Result<Vec<_>, _>>();
+        let Ok(values) = values else {
+            // The layout is not confirmed, so this should not make the whole
+            // response unusable
+            warn!("Could not parse the fight rounds: {data}");
+            return Ok(());
+        };
+
+        let rounds = values.chunks_exact(ROUND_LEN);
+        if !rounds.remainder().is_empty() {
+            warn!("Incomplete fight round: {:?}", rounds.remainder());
+        }
+        for round in rounds {
+            let action = round.csiget(2, "round action", 0)?;
+            let skill: u32 = round.csiget(7, "round skill", 0)?;
+            self.actions.push(FightAction {
+                acting_id: round.cget(0, "round acting id")?,
+                target_id: Some(round.cget(1, "round target id")?),
+                action: FightActionType::parse(action),
+                critical: round.cget(3, "round crit")? != 0,
+                damage: Some(round.cget(4, "round damage")?),
+                other_new_life: round.cget(5, "round other life")?,
+                acting_new_life: Some(round.cget(6, "round acting life")?),
+                skill: (skill != 0).then_some(skill),
+            });
+        }
+        Ok(())
+    }
+
+    /// The id of the fighter, that `fighter_id` fights against
+    fn opponent_id(&self, fighter_id: i64) -> Option<i64> {
+        let a = self.fighter_a.as_ref()?;
+        let b = self.fighter_b.as_ref()?;
+        if a.id == fighter_id {
+            Some(b.id)
+        } else if b.id == fighter_id {
+            Some(a.id)
+        } else {
+            None
+        }
+    }
+
+    /// The damage every action in `actions` has dealt. For fight versions,
+    /// that do not contain the damage, the damage is calculated from the life
+    /// of the fighters, which requires both fighters to be known
+    #[must_use]
+    pub fn damages(&self) -> Vec<Option<i64>> {
+        let mut lives: Vec<(i64, i64)> = [&self.fighter_a, &self.fighter_b]
+            .into_iter()
+            .flatten()
+            .map(|a| (a.id, i64::from(a.life)))
+            .collect();
+
+        let mut damages = Vec::with_capacity(self.actions.len());
+        for action in &self.actions {
+            let target = action
+                .target_id
+                .or_else(|| self.opponent_id(action.acting_id));
+            let target_life = lives.iter_mut().find(|a| Some(a.0) == target);
+            damages.push(action.damage.or_else(|| {
+                let life = target_life.as_ref()?.1;
+                Some((life - action.other_new_life).max(0))
+            }));
+            if let Some(life) = target_life {
+                life.1 = action.other_new_life;
+            }
+        }
+        damages
+    }
+
+    /// Sums up everything the fighter with the given id has done in this
+    /// fight. Have a look at `damages()` for how the damage is calculated
+    #[must_use]
+    pub fn stats(&self, fighter_id: i64) -> FighterStats {
+        let mut stats = FighterStats::default();
+        for (action, damage) in self.actions.iter().zip(self.damages()) {
+            if action.acting_id != fighter_id {
+                continue;
+            }
+
+            stats.actions += 1;
+            match action.action {
+                FightActionType::Blocked
+                | FightActionType::MinionAttackBlocked => stats.blocked += 1,
+                FightActionType::Evaded
+                | FightActionType::MinionAttackEvaded => stats.evaded += 1,
+                _ => {
+                    let damage = damage.unwrap_or_default();
+                    if damage > 0 {
+                        stats.hits += 1;
+                        stats.damage += damage.unsigned_abs();
+                    }
+                    if action.critical {
+                        stats.critical_hits += 1;
+                    }
+                }
+            }
+            if action.skill.is_some() {
+                stats.skills += 1;
+            }
+        }
+        stats
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Everything a single fighter has done in a fight. Have a look at
+/// `SingleFight::stats()`
+pub struct FighterStats {
+    /// The amount of actions this fighter has done
+    pub actions: u32,
+    /// The amount of actions, that have dealt damage
+    pub hits: u32,
+    pub critical_hits: u32,
+    /// The amount of attacks, that the target has blocked
+    pub blocked: u32,
+    /// The amount of attacks, that the target has evaded
+    pub evaded: u32,
+    /// The amount of times a class skill has been used
+    pub skills: u32,
+    /// The total damage dealt
+    pub damage: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -264,12 +404,26 @@ impl Fighter {
 pub struct FightAction {
     /// The id of the fighter, that does the action
     pub acting_id: i64,
+    /// The id of the fighter, that the action was aimed at. Only fight
+    /// versions above 1 contain this
+    pub target_id: Option<i64>,
     /// The new current life of the fighter, that was hit. Note that this may
     /// be 0 for actions, like spawning minions, that dont have a target
     /// and thus no target health.
     pub other_new_life: i64,
+    /// The new current life of the acting fighter. This changes with life
+    /// stealing skills. Only fight versions above 1 contain this
+    pub acting_new_life: Option<i64>,
     /// The action, that the active side does
     pub action: FightActionType,
+    /// Whether or not the action was a critical hit
+    pub critical: bool,
+    /// The damage the action has dealt. Only fight versions above 1 contain
+    /// this. Have a look at `SingleFight::stats()` for older versions
+    pub damage: Option<i64>,
+    /// The raw id of the class skill, that the acting fighter has used, like
+    /// the song of a bard, or the swoop of a druid
+    pub skill: Option<u32>,
 }
 
 #[non_exhaustive]
@@ -303,7 +457,8 @@ pub enum FightActionType {
 
 impl FightActionType {
     pub(crate) fn parse(val: u32) -> FightActionType {
-        // FIXME: Is this missing crit?
+        // 1 & 25 are the critical versions of 0 & 5. Whether or not an
+        // action was critical is stored in `FightAction::critical`
         match val {
             0 | 1 => FightActionType::Attack,
             2 => FightActionType::MushroomCatapult,
@@ -339,3 +494,61 @@ pub enum FighterTyp {
     /// A pet
     Pet,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_v2() {
+        let mut fight = SingleFight::default();
+        let log = "1,2,1,1,40,60,100,0,2,1,4,0,0,100,60,0,1,2,0,0,60,0,100,\
+                   201,7";
+        fight.update_rounds(log, 2).unwrap();
+        assert_eq!(fight.actions.len(), 3);
+
+        let crit = &fight.actions[0];
+        assert_eq!(crit.acting_id, 1);
+        assert_eq!(crit.target_id, Some(2));
+        assert_eq!(crit.action, FightActionType::Attack);
+        assert!(crit.critical);
+        assert_eq!(crit.damage, Some(40));
+        assert_eq!(crit.other_new_life, 60);
+        assert_eq!(crit.acting_new_life, Some(100));
+        assert_eq!(crit.skill, None);
+
+        let evaded = &fight.actions[1];
+        assert_eq!(evaded.action, FightActionType::Evaded);
+        assert_eq!(evaded.damage, Some(0));
+
+        let skill = &fight.actions[2];
+        assert_eq!(skill.skill, Some(201));
+        assert_eq!(skill.other_new_life, 0);
+        // The incomplete round at the end is dropped
+        assert_eq!(fight.damages(), [Some(40), Some(0), Some(60)]);
+
+        // Rounds, that do not fit the layout, are skipped
+        fight.update_rounds("1,2,x,0,0,0,0,0", 2).unwrap();
+        assert!(fight.actions.is_empty());
+    }
+
+    #[test]
+    fn rounds_v1() {
+        let mut fight = SingleFight::default();
+        fight.update_rounds("1,0,60,2,4,100,1,1,0", 1).unwrap();
+        let actions: Vec<_> = fight
+            .actions
+            .iter()
+            .map(|a| (a.acting_id, a.action, a.critical, a.other_new_life))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (1, FightActionType::Attack, false, 60),
+                (2, FightActionType::Evaded, false, 100),
+                (1, FightActionType::Attack, true, 0),
+            ]
+        );
+        assert!(fight.actions.iter().all(|a| a.damage.is_none()));
+    }
+}
@@ -0,0 +1,280 @@
+use std::{collections::HashMap, ops::RangeInclusive, sync::LazyLock};
+
+use num_traits::FromPrimitive;
+use strum::IntoEnumIterator;
+
+use super::{
+    character::Class, items::EquipmentSlot, unlockables::EquipmentIdent,
+};
+use crate::error::SFError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Everything we know about an item, that is not part of its ident
+pub struct CatalogueEntry {
+    /// The english name of the item
+    pub name: String,
+    /// Whether or not this item is an epic. Have a look at
+    /// `EquipmentIdent::is_epic()`
+    pub is_epic: bool,
+    /// Whether or not this item is a legendary. Have a look at
+    /// `EquipmentIdent::is_legendary()`
+    pub is_legendary: bool,
+    /// The set this item belongs to, if any
+    pub set: Option<String>,
+    /// The levels at which this item can drop, if known
+    pub level_range: Option<RangeInclusive<u16>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CatalogueKey {
+    class: Option<Class>,
+    typ: EquipmentSlot,
+    model_id: u16,
+    /// None, if the entry is the same for all colors
+    color: Option<u8>,
+}
+
+#[derive(Debug, Clone, Default)]
+/// Maps `EquipmentIdent`s to human readable information about the item. The
+/// catalogue is read from a simple text format. Have a look at
+/// `data/item_catalogue.csv` for a description of it
+pub struct ItemCatalogue {
+    entries: HashMap<CatalogueKey, CatalogueEntry>,
+}
+
+const EMBEDDED_DATA: &str = include_str!("../../data/item_catalogue.csv");
+
+static EMBEDDED: LazyLock<ItemCatalogue> =
+    LazyLock::new(|| match ItemCatalogue::parse(EMBEDDED_DATA) {
+        Ok(catalogue) => catalogue,
+        // The file is part of the library, so this is a bug, that the tests
+        // catch before a release
+        Err(e) => panic!("The embedded item catalogue is invalid: {e}"),
+    });
+
+impl ItemCatalogue {
+    /// The catalogue, that ships with this library
+    ///
+    /// # Panics
+    /// If the embedded catalogue file is invalid
+    #[must_use]
+    pub fn embedded() -> &'static ItemCatalogue {
+        &EMBEDDED
+    }
+
+    /// Parses a catalogue in the same format as the embedded one
+    ///
+    /// # Errors
+    /// Returns a `ParsingError`, if any of the lines is invalid
+    pub fn parse(data: &str) -> Result<ItemCatalogue, SFError> {
+        let mut entries = HashMap::new();
+        for line in data.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, entry) = parse_line(line).ok_or_else(|| {
+                SFError::ParsingError("item catalogue line", line.to_string())
+            })?;
+            entries.insert(key, entry);
+        }
+        Ok(ItemCatalogue { entries })
+    }
+
+    /// Adds all entries of the other catalogue to this one. Entries, that
+    /// exist in both, are replaced by the ones from `other`
+    pub fn extend(&mut self, other: ItemCatalogue) {
+        self.entries.extend(other.entries);
+    }
+
+    /// The amount of items in this catalogue
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if this catalogue does not contain any items
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Looks up the item with this ident. Entries for the exact color are
+    /// preferred over the ones, that apply to all colors
+    #[must_use]
+    pub fn get(&self, ident: &EquipmentIdent) -> Option<&CatalogueEntry> {
+        let mut key = CatalogueKey {
+            class: ident.class,
+            typ: ident.typ,
+            model_id: ident.model_id,
+            color: Some(ident.color),
+        };
+        self.entries.get(&key).or_else(|| {
+            key.color = None;
+            self.entries.get(&key)
+        })
+    }
+
+    /// The name of this item. If the item is not in the catalogue, a generic
+    /// name like "Epic Warrior Weapon 53" is returned instead, so this can
+    /// always be shown to a user
+    #[must_use]
+    pub fn name(&self, ident: &EquipmentIdent) -> String {
+        if let Some(entry) = self.get(ident) {
+            return entry.name.clone();
+        }
+        let rarity = match () {
+            () if ident.is_legendary() => "Legendary ",
+            () if ident.is_epic() => "Epic ",
+            () => "",
+        };
+        let class = ident.class.map(|a| format!("{a:?} ")).unwrap_or_default();
+        let color = if ident.is_epic() || ident.color <= 1 {
+            String::new()
+        } else {
+            format!(" ({})", ident.color)
+        };
+        format!("{rarity}{class}{:?} {}{color}", ident.typ, ident.model_id)
+    }
+}
+
+fn parse_line(line: &str) -> Option<(CatalogueKey, CatalogueEntry)> {
+    let data: Vec<_> = line.split(';').map(str::trim).collect();
+    let [slot, class, model_id, color, name, set, min_level, max_level] =
+        data.as_slice()
+    else {
+        return None;
+    };
+
+    let slot: u8 = slot.parse().ok()?;
+    let typ = EquipmentSlot::iter().find(|a| a.raw_id() == slot)?;
+    let class = match *class {
+        "" => None,
+        x => Some(Class::from_u8(x.parse::<u8>().ok()?.checked_sub(1)?)?),
+    };
+    let model_id: u16 = model_id.parse().ok()?;
+    let color = match *color {
+        "" => None,
+        x => Some(x.parse().ok()?),
+    };
+    let level_range = match (*min_level, *max_level) {
+        ("", "") => None,
+        (min, "") => Some(min.parse().ok()?..=u16::MAX),
+        ("", max) => Some(1..=max.parse().ok()?),
+        (min, max) => Some(min.parse().ok()?..=max.parse().ok()?),
+    };
+
+    let ident = EquipmentIdent {
+        class,
+        typ,
+        model_id,
+        color: color.unwrap_or_default(),
+    };
+    Some((
+        CatalogueKey {
+            class,
+            typ,
+            model_id,
+            color,
+        },
+        CatalogueEntry {
+            name: (*name).to_string(),
+            is_epic: ident.is_epic(),
+            is_legendary: ident.is_legendary(),
+            set: Some((*set).to_string()).filter(|a| !a.is_empty()),
+            level_range,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOGUE: &str = "
+        # A comment
+        1;1;3;;Sword;;10;20
+        1;1;3;2;Red Sword;;;
+        8;;51;;Shiny Amulet;Hero Set;;80
+        1;3;95;;Old Bow;;;
+    ";
+
+    fn ident(
+        class: Option<Class>,
+        typ: EquipmentSlot,
+        model_id: u16,
+        color: u8,
+    ) -> EquipmentIdent {
+        EquipmentIdent {
+            class,
+            typ,
+            model_id,
+            color,
+        }
+    }
+
+    #[test]
+    fn embedded_catalogue_is_valid() {
+        assert!(ItemCatalogue::parse(EMBEDDED_DATA).is_ok());
+    }
+
+    #[test]
+    fn lookup() {
+        use EquipmentSlot::*;
+        let catalogue = ItemCatalogue::parse(CATALOGUE).unwrap();
+        assert_eq!(catalogue.len(), 4);
+
+        let sword = ident(Some(Class::Warrior), Weapon, 3, 1);
+        let entry = catalogue.get(&sword).unwrap();
+        assert_eq!(entry.name, "Sword");
+        assert_eq!(entry.level_range, Some(10..=20));
+        assert!(!entry.is_epic);
+
+        // The exact color wins over the entry for all colors
+        let red = EquipmentIdent { color: 2, ..sword };
+        assert_eq!(catalogue.name(&red), "Red Sword");
+        assert_eq!(catalogue.get(&red).unwrap().level_range, None);
+
+        // Same model, but a different class
+        let mage = ident(Some(Class::Mage), Weapon, 3, 1);
+        assert_eq!(catalogue.get(&mage), None);
+
+        let amulet = catalogue.get(&ident(None, Amulet, 51, 4)).unwrap();
+        assert_eq!(amulet.name, "Shiny Amulet");
+        assert_eq!(amulet.set.as_deref(), Some("Hero Set"));
+        assert_eq!(amulet.level_range, Some(1..=80));
+        assert!(amulet.is_epic && !amulet.is_legendary);
+
+        let bow = ident(Some(Class::Scout), Weapon, 95, 1);
+        let entry = catalogue.get(&bow).unwrap();
+        assert!(entry.is_epic && entry.is_legendary);
+    }
+
+    #[test]
+    fn generic_names() {
+        use EquipmentSlot::*;
+        let catalogue = ItemCatalogue::default();
+        assert_eq!(
+            catalogue.name(&ident(Some(Class::Warrior), Weapon, 53, 1)),
+            "Epic Warrior Weapon 53"
+        );
+        assert_eq!(catalogue.name(&ident(None, Ring, 7, 3)), "Ring 7 (3)");
+        assert_eq!(
+            catalogue.name(&ident(Some(Class::Mage), Hat, 91, 1)),
+            "Legendary Mage Hat 91"
+        );
+    }
+
+    #[test]
+    fn invalid_lines() {
+        for line in [
+            "1;1;3;;Sword;;10",
+            "11;1;3;;Sword;;;",
+            "1;12;3;;Sword;;;",
+            "1;1;x;;Sword;;;",
+            "1;1;3;;Sword;;20;x",
+        ] {
+            assert!(ItemCatalogue::parse(line).is_err(), "{line}");
+        }
+    }
+}
@@ -2,6 +2,7 @@
 use chrono::{DateTime, Local};
 use enum_map::{Enum, EnumMap};
 use num_bigint::BigInt;
+use num_derive::FromPrimitive;
 use strum::EnumIter;
 
 use super::ServerTime;
@@ -101,7 +102,9 @@ impl IdleGame {
     }
 }
 
-#[derive(Debug, Clone, Copy, Enum, EnumIter, PartialEq, Eq, Hash)]
+#[derive(
+    Debug, Clone, Copy, Enum, EnumIter, PartialEq, Eq, Hash, FromPrimitive,
+)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 /// The type of a building in the idle game
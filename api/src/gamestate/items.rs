@@ -98,7 +98,7 @@ impl PlayerItemPlace {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Hash, FromPrimitive)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 /// All the parts of `ItemPlace`, that are owned by the player
@@ -119,7 +119,7 @@ impl InventoryType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Hash, FromPrimitive)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// All places, that items can be dragged to excluding companions
 pub enum ItemPlace {
@@ -280,43 +280,17 @@ impl Item {
     /// Returns `true` if the item does not have a class requirement, or if the
     /// class requirement matches the given class.
     #[must_use]
-    #[allow(clippy::enum_glob_use, clippy::match_same_arms)]
     pub fn can_be_used_by(&self, class: Class) -> bool {
-        use Class::*;
-
         // Without a class requirement any class can use this
         let Some(class_requirement) = self.class else {
             return true;
         };
-
-        // Class requirements
-        // Warrior => Weapon: Meele,  Armor: Heavy
-        // Scout   => Weapon: Ranged, Armor: Medium
-        // Mage    => Weapon: Magic,  Armor: Light
-        match (class, class_requirement) {
-            // Weapon: Meele, Armor: Heavy
-            (Warrior, Warrior) => true,
-            (Berserker, Warrior) => !self.typ.is_shield(),
-            // Weapon: Ranged, Armor: Medium
-            (Scout, Scout) => true,
-            // Weapon: Magic, Armor: Light
-            (Mage | Necromancer, Mage) => true,
-            // Weapon: Meele, Armor: Medium
-            (Assassin, Warrior) => self.typ.is_weapon(),
-            (Assassin, Scout) => !self.typ.is_weapon(),
-            // Weapon: Magic, Armor: Medium
-            (Bard | Druid, Mage) => self.typ.is_weapon(),
-            (Bard | Druid, Scout) => !self.typ.is_weapon(),
-            // Weapon: Meele, Armor: Light
-            (BattleMage, Warrior) => self.typ.is_weapon(),
-            (BattleMage, Mage) => !self.typ.is_weapon(),
-            // Weapon: Ranged, Armor: Heavy
-            (DemonHunter, Scout) => self.typ.is_weapon(),
-            (DemonHunter, Warrior) => {
-                !self.typ.is_weapon() && !self.typ.is_shield()
-            }
-            _ => false,
-        }
+        class_can_use(
+            class,
+            class_requirement,
+            self.typ.is_weapon(),
+            self.typ.is_shield(),
+        )
     }
 
     /// Parses an item, that starts at the start of the given data
@@ -441,6 +415,45 @@ impl Item {
     }
 }
 
+/// Checks if a character of the given class can use an item, that is tagged
+/// with the class requirement
+#[allow(clippy::enum_glob_use, clippy::match_same_arms)]
+pub(crate) fn class_can_use(
+    class: Class,
+    class_requirement: Class,
+    is_weapon: bool,
+    is_shield: bool,
+) -> bool {
+    use Class::*;
+
+    // Class requirements
+    // Warrior => Weapon: Meele,  Armor: Heavy
+    // Scout   => Weapon: Ranged, Armor: Medium
+    // Mage    => Weapon: Magic,  Armor: Light
+    match (class, class_requirement) {
+        // Weapon: Meele, Armor: Heavy
+        (Warrior | Paladin, Warrior) => true,
+        (Berserker, Warrior) => !is_shield,
+        // Weapon: Ranged, Armor: Medium
+        (Scout, Scout) => true,
+        // Weapon: Magic, Armor: Light
+        (Mage | Necromancer, Mage) => true,
+        // Weapon: Meele, Armor: Medium
+        (Assassin, Warrior) => is_weapon,
+        (Assassin, Scout) => !is_weapon,
+        // Weapon: Magic, Armor: Medium
+        (Bard | Druid, Mage) => is_weapon,
+        (Bard | Druid, Scout) => !is_weapon,
+        // Weapon: Meele, Armor: Light
+        (BattleMage, Warrior) => is_weapon,
+        (BattleMage, Mage) => !is_weapon,
+        // Weapon: Ranged, Armor: Heavy
+        (DemonHunter, Scout) => is_weapon,
+        (DemonHunter, Warrior) => !is_weapon && !is_shield,
+        _ => false,
+    }
+}
+
 #[derive(
     Debug, Clone, Copy, FromPrimitive, PartialEq, Eq, EnumIter, Hash, Enum,
 )]
@@ -971,7 +984,9 @@ impl GemType {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Enum, EnumIter)]
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, Enum, EnumIter, FromPrimitive,
+)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 /// Denotes the place, where an item is equipped
@@ -1,4 +1,5 @@
 pub mod arena;
+pub mod catalogue;
 pub mod character;
 pub mod dungeons;
 pub mod fortress;
@@ -181,6 +182,7 @@ impl GameState {
         let server_time = self.server_time();
 
         self.last_fight = None;
+        self.tavern.gamble_result = None;
         self.mail.open_claimable = None;
 
         let mut other_player: Option<OtherPlayer> = None;
@@ -41,7 +41,7 @@ pub struct Tavern {
     /// Decides if you can on on expeditions, or quests, when this event is
     /// currently ongoing
     pub questing_preference: ExpeditionSetting,
-    /// The result of playing the shell game
+    /// The result of playing the shell game in the last command
     pub gamble_result: Option<GambleResult>,
 }
 
@@ -2,9 +2,9 @@ use std::num::NonZeroU8;
 
 use chrono::{DateTime, Local};
 use enum_map::Enum;
-use log::error;
+use log::{error, warn};
 use num_derive::FromPrimitive;
-use strum::EnumIter;
+use strum::{EnumIter, IntoEnumIterator};
 
 use super::*;
 use crate::{gamestate::items::*, misc::*, PlayerId};
@@ -645,7 +645,9 @@ pub struct PetStats {
     pub element: HabitatType,
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Enum, EnumIter, Hash)]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Enum, EnumIter, Hash, FromPrimitive,
+)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HabitatType {
     #[default]
@@ -814,7 +816,7 @@ pub struct Achievement {
     pub progress: i64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Contains all the items & monsters you have found in the scrapbook
 pub struct ScrapBook {
@@ -822,16 +824,37 @@ pub struct ScrapBook {
     /// item is in this, you should call `equipment_ident()` on an item and see
     /// if this item contains that
     pub items: HashSet<EquipmentIdent>,
-    /// All the monsters, that the player has seen already. I have only checked
-    /// this once, but this should match the tavern monster id.
-    // TODO: Dungeon monster ids?
+    /// All the monsters, that the player has seen already. This is the
+    /// position of the monster in the scrapbook, which matches the tavern
+    /// monster id. Have a look at `ScrapBookLayout::monster_origin()` to find
+    /// out, where a monster can be fought
     pub monster: HashSet<u16>,
+    /// The version of the `ScrapBookLayout`, that was used to parse this
+    #[cfg_attr(feature = "serde", serde(default = "first_layout_version"))]
+    pub layout_version: u32,
+    /// Positions, that are owned, but are not part of the known layout, or
+    /// have the same ident as another position. These are kept around, so
+    /// that nothing gets lost, if the server adds new entries before this
+    /// library knows about them
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unknown: Vec<usize>,
+}
+
+/// Scrapbooks, that have been stored before the layout had a version, have
+/// all been parsed with the first one
+#[cfg(feature = "serde")]
+fn first_layout_version() -> u32 {
+    1
 }
 
 impl ScrapBook {
+    /// Parses the URL-safe base64 bitfield, that the server sends for the
+    /// scrapbook, or that `to_server_string()` has created. Returns None, if
+    /// the string is invalid, or nothing has been collected
     // 99% based on Hubert Lipińskis Code
     // https://github.com/HubertLipinski/sfgame-scrapbook-helper
-    pub(crate) fn parse(val: &str) -> Option<ScrapBook> {
+    #[must_use]
+    pub fn parse(val: &str) -> Option<ScrapBook> {
         let text = base64::Engine::decode(
             &base64::engine::general_purpose::URL_SAFE,
             val,
@@ -841,9 +864,11 @@ impl ScrapBook {
             return None;
         }
 
+        let layout = ScrapBookLayout::current();
         let mut index = 0;
         let mut items = HashSet::new();
         let mut monster = HashSet::new();
+        let mut unknown = Vec::new();
 
         for byte in text {
             for bit_pos in (0..=7).rev() {
@@ -852,22 +877,180 @@ impl ScrapBook {
                 if !is_owned {
                     continue;
                 }
-                if index < 801 {
-                    // Monster
+                if layout.monsters.contains(&index) {
                     monster.insert(index.try_into().unwrap_or_default());
-                } else if let Some(ident) = parse_scrapbook_item(index) {
-                    // Items
-                    if !items.insert(ident) {
-                        error!(
-                            "Two scrapbook positions parsed to the same ident"
-                        );
+                } else if let Some(ident) = layout.item_ident(index) {
+                    // Some positions look exactly like the ident of another
+                    // position. We keep these as unknown, so that encoding
+                    // this again gives back the same bits
+                    if layout.item_index(&ident) == Some(index) {
+                        items.insert(ident);
+                    } else {
+                        warn!("Scrapbook position {index} has the ident of \
+                               another position");
+                        unknown.push(index);
                     }
                 } else {
                     error!("Owned, but not parsed: {index}");
+                    unknown.push(index);
                 }
             }
         }
-        Some(ScrapBook { items, monster })
+        Some(ScrapBook {
+            items,
+            monster,
+            layout_version: layout.version,
+            unknown,
+        })
+    }
+
+    /// Encodes this scrapbook back into the URL-safe base64 bitfield, that the
+    /// server uses. This is a lot smaller, than the parsed version, so this is
+    /// the best way to store, or share a scrapbook. `ScrapBook::parse()` turns
+    /// this back into the same `ScrapBook`. Items, that are not part of the
+    /// layout, can not be encoded and are skipped with a warning
+    #[must_use]
+    pub fn to_server_string(&self) -> String {
+        let layout = ScrapBookLayout::current();
+        let item_index = |ident: &EquipmentIdent| {
+            let index = layout.item_index(ident);
+            if index.is_none() {
+                warn!("Can not encode {ident:?} in the scrapbook");
+            }
+            index
+        };
+        let indices: Vec<usize> = self
+            .monster
+            .iter()
+            .map(|a| usize::from(*a))
+            .chain(self.items.iter().filter_map(item_index))
+            .chain(self.unknown.iter().copied())
+            .filter(|a| *a > 0)
+            .collect();
+
+        let bits = indices.iter().copied().max().unwrap_or(0).max(layout.len());
+        let mut bytes = vec![0u8; bits.div_ceil(8)];
+        for index in indices {
+            let pos = index - 1;
+            if let Some(byte) = bytes.get_mut(pos / 8) {
+                *byte |= 1 << (7 - pos % 8);
+            }
+        }
+        base64::Engine::encode(
+            &base64::engine::general_purpose::URL_SAFE,
+            bytes,
+        )
+    }
+
+    /// Everything, that is in this scrapbook, but was not in the `older`
+    /// one. The result is a scrapbook itself, so you can use
+    /// `to_server_string()`, or `total_progress()` on it as well
+    #[must_use]
+    pub fn added(&self, older: &ScrapBook) -> ScrapBook {
+        ScrapBook {
+            items: self.items.difference(&older.items).copied().collect(),
+            monster: self.monster.difference(&older.monster).copied().collect(),
+            layout_version: self.layout_version,
+            unknown: self
+                .unknown
+                .iter()
+                .filter(|a| !older.unknown.contains(a))
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Everything, that was in the `older` scrapbook, but is not in this one
+    /// anymore. This should normally be empty, but can be useful to check
+    /// parsing, or to compare scrapbooks of different characters
+    #[must_use]
+    pub fn removed(&self, older: &ScrapBook) -> ScrapBook {
+        older.added(self)
+    }
+
+    /// Checks if this scrapbook does not contain anything. This is mainly
+    /// useful for the results of `added()` and `removed()`
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+            && self.monster.is_empty()
+            && self.unknown.is_empty()
+    }
+
+    /// Checks the equipment of another player for items, that are not yet in
+    /// this scrapbook. These are the items you would gain by fighting them.
+    /// Every item is only listed once, even if the player wears it in
+    /// multiple slots. Use `is_epic()` on the result to tell epics apart
+    #[must_use]
+    pub fn missing_items(&self, other: &OtherPlayer) -> Vec<EquipmentIdent> {
+        let mut missing = Vec::new();
+        for ident in other
+            .equipment
+            .0
+            .values()
+            .flatten()
+            .filter_map(Item::equipment_ident)
+        {
+            if !self.items.contains(&ident) && !missing.contains(&ident) {
+                missing.push(ident);
+            }
+        }
+        missing
+    }
+
+    /// The amount of collected and total entries in one section
+    #[must_use]
+    pub fn progress(&self, section: ScrapBookSection) -> ScrapBookProgress {
+        let layout = ScrapBookLayout::current();
+        let owned = match section {
+            ScrapBookSection::Monsters => self.monster.len(),
+            _ => self
+                .items
+                .iter()
+                .filter(|a| ScrapBookSection::of_ident(a) == section)
+                .count(),
+        };
+        ScrapBookProgress {
+            owned: owned.try_into().unwrap_or(u32::MAX),
+            total: layout.section_total(section),
+        }
+    }
+
+    /// The amount of collected and total entries in the whole scrapbook
+    #[must_use]
+    pub fn total_progress(&self) -> ScrapBookProgress {
+        ScrapBookSection::iter()
+            .map(|a| self.progress(a))
+            .fold(ScrapBookProgress::default(), |a, b| a + b)
+    }
+
+    /// The progress of all the items, that a character of the given class
+    /// can wear. Only the three main classes have their own items in the
+    /// scrapbook, so for all other classes this combines the parts of the
+    /// sections, that they can use. Monsters are not part of this
+    #[must_use]
+    pub fn class_progress(&self, class: Class) -> ScrapBookProgress {
+        let layout = ScrapBookLayout::current();
+        let mut progress = ScrapBookProgress::default();
+        for slots in layout.class_slots(class) {
+            progress.total += slots.len();
+        }
+        let owned = self
+            .items
+            .iter()
+            .filter(|a| {
+                a.class.is_none_or(|req| {
+                    class_can_use(
+                        class,
+                        req,
+                        a.typ == EquipmentSlot::Weapon,
+                        a.typ == EquipmentSlot::Shield,
+                    )
+                })
+            })
+            .count();
+        progress.owned = owned.try_into().unwrap_or(u32::MAX);
+        progress
     }
 }
 
@@ -885,6 +1068,22 @@ pub struct EquipmentIdent {
     pub color: u8,
 }
 
+impl EquipmentIdent {
+    /// Checks if this is the ident of an epic item. Same as
+    /// `Item::is_epic()`
+    #[must_use]
+    pub fn is_epic(&self) -> bool {
+        self.model_id >= 50
+    }
+
+    /// Checks if this is the ident of a legendary item. Same as
+    /// `Item::is_legendary()`
+    #[must_use]
+    pub fn is_legendary(&self) -> bool {
+        self.model_id >= 90
+    }
+}
+
 #[allow(clippy::to_string_trait_impl)]
 impl ToString for EquipmentIdent {
     fn to_string(&self) -> String {
@@ -901,90 +1100,502 @@ impl ToString for EquipmentIdent {
     }
 }
 
-#[allow(clippy::enum_glob_use)]
-fn parse_scrapbook_item(index: i64) -> Option<EquipmentIdent> {
-    use Class::*;
-    use EquipmentSlot::*;
-    let slots: [(_, _, _, &[_]); 44] = [
-        (801..1011, Amulet, None, &[]),
-        (1011..1051, Amulet, None, &[]),
-        (1051..1211, Ring, None, &[]),
-        (1211..1251, Ring, None, &[]),
-        (1251..1325, Talisman, None, &[]),
-        (1325..1365, Talisman, None, &[]),
-        (1365..1665, Weapon, Some(Warrior), &[]),
-        (1665..1705, Weapon, Some(Warrior), &[]),
-        (1705..1805, Shield, Some(Warrior), &[]),
-        (1805..1845, Shield, Some(Warrior), &[]),
-        (1845..1945, BreastPlate, Some(Warrior), &[]),
-        (1945..1985, BreastPlate, Some(Warrior), &[1954, 1955]),
-        (1985..2085, FootWear, Some(Warrior), &[]),
-        (2085..2125, FootWear, Some(Warrior), &[2094, 2095]),
-        (2125..2225, Gloves, Some(Warrior), &[]),
-        (2225..2265, Gloves, Some(Warrior), &[2234, 2235]),
-        (2265..2365, Hat, Some(Warrior), &[]),
-        (2365..2405, Hat, Some(Warrior), &[2374, 2375]),
-        (2405..2505, Belt, Some(Warrior), &[]),
-        (2505..2545, Belt, Some(Warrior), &[2514, 2515]),
-        (2545..2645, Weapon, Some(Mage), &[]),
-        (2645..2685, Weapon, Some(Mage), &[]),
-        (2685..2785, BreastPlate, Some(Mage), &[]),
-        (2785..2825, BreastPlate, Some(Mage), &[2794, 2795]),
-        (2825..2925, FootWear, Some(Mage), &[]),
-        (2925..2965, FootWear, Some(Mage), &[2934, 2935]),
-        (2965..3065, Gloves, Some(Mage), &[]),
-        (3065..3105, Gloves, Some(Mage), &[3074, 3075]),
-        (3105..3205, Hat, Some(Mage), &[]),
-        (3205..3245, Hat, Some(Mage), &[3214, 3215]),
-        (3245..3345, Belt, Some(Mage), &[]),
-        (3345..3385, Belt, Some(Mage), &[3354, 3355]),
-        (3385..3485, Weapon, Some(Scout), &[]),
-        (3485..3525, Weapon, Some(Scout), &[]),
-        (3525..3625, BreastPlate, Some(Scout), &[]),
-        (3625..3665, BreastPlate, Some(Scout), &[3634, 3635]),
-        (3665..3765, FootWear, Some(Scout), &[]),
-        (3765..3805, FootWear, Some(Scout), &[3774, 3775]),
-        (3805..3905, Gloves, Some(Scout), &[]),
-        (3905..3945, Gloves, Some(Scout), &[3914, 3915]),
-        (3945..4045, Hat, Some(Scout), &[]),
-        (4045..4085, Hat, Some(Scout), &[4054, 4055]),
-        (4085..4185, Belt, Some(Scout), &[]),
-        (4185..4225, Belt, Some(Scout), &[4194, 4195]),
-    ];
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Enum, EnumIter, PartialOrd, Ord,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The sections you can see in the scrapbook ingame
+pub enum ScrapBookSection {
+    /// Monsters from quests, dungeons & other fights
+    Monsters,
+    /// Amulets, rings & talismans, that every class can wear
+    Valuables,
+    /// Items, that are tagged as warrior items
+    Warrior,
+    /// Items, that are tagged as mage items
+    Mage,
+    /// Items, that are tagged as scout items
+    Scout,
+}
+
+impl ScrapBookSection {
+    /// The section an item with this ident will be listed in
+    #[must_use]
+    pub fn of_ident(ident: &EquipmentIdent) -> ScrapBookSection {
+        match ident.class {
+            None => ScrapBookSection::Valuables,
+            Some(class) => ScrapBookSection::of_item_class(class),
+        }
+    }
 
-    let mut is_epic = true;
-    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    for (range, typ, class, ignore) in slots {
-        is_epic = !is_epic;
-        if !range.contains(&index) {
-            continue;
+    fn of_item_class(class: Class) -> ScrapBookSection {
+        // Items are only ever tagged with one of the three main classes
+        match class {
+            Class::Mage => ScrapBookSection::Mage,
+            Class::Scout => ScrapBookSection::Scout,
+            _ => ScrapBookSection::Warrior,
         }
-        if ignore.contains(&index) {
-            return None;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// How much of a part of the scrapbook has been collected
+pub struct ScrapBookProgress {
+    /// The amount of entries, that have been collected
+    pub owned: u32,
+    /// The amount of entries, that can be collected
+    pub total: u32,
+}
+
+impl ScrapBookProgress {
+    /// The share of collected entries in percent (0.0 - 100.0)
+    #[must_use]
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        f64::from(self.owned) * 100.0 / f64::from(self.total)
+    }
+}
+
+impl std::ops::Add for ScrapBookProgress {
+    type Output = ScrapBookProgress;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        ScrapBookProgress {
+            owned: self.owned + rhs.owned,
+            total: self.total + rhs.total,
         }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The place, where a monster from the scrapbook can be fought
+pub enum MonsterOrigin {
+    /// The monster shows up in tavern quests
+    Tavern,
+    /// The monster is one of the enemies in this dungeon
+    Dungeon(Dungeon),
+    /// The monster is not yet part of `ScrapBookLayout::monster_origins`
+    Unknown,
+}
 
-        let relative_pos = index - range.start + 1;
+#[derive(Debug, Clone)]
+/// A continuous block of items in the scrapbook, that all share the same
+/// slot, class and rarity
+pub struct ScrapBookSlots {
+    /// The positions of the items in the scrapbook. The first bit is 1
+    pub indices: std::ops::Range<usize>,
+    /// The slot all of these items are worn in
+    pub typ: EquipmentSlot,
+    /// The class the items are tagged with. None for valuables
+    pub class: Option<Class>,
+    /// Whether or not all of these items are epics
+    pub is_epic: bool,
+    /// Positions in this block, that do not belong to any item
+    pub ignored: &'static [usize],
+}
+
+impl ScrapBookSlots {
+    /// The amount of items in this block
+    #[must_use]
+    pub fn len(&self) -> u32 {
+        (self.indices.len() - self.ignored.len())
+            .try_into()
+            .unwrap_or(u32::MAX)
+    }
+
+    /// Checks if this block contains no items at all
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if a character of the given class can wear the items in this
+    /// block
+    #[must_use]
+    pub fn usable_by(&self, class: Class) -> bool {
+        self.class.is_none_or(|req| {
+            class_can_use(
+                class,
+                req,
+                self.typ == EquipmentSlot::Weapon,
+                self.typ == EquipmentSlot::Shield,
+            )
+        })
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn ident(&self, index: usize) -> Option<EquipmentIdent> {
+        if !self.indices.contains(&index) || self.ignored.contains(&index) {
+            return None;
+        }
+        let relative_pos = index - self.indices.start + 1;
 
         let color = match relative_pos % 10 {
-            _ if typ == Talisman || is_epic => 1,
+            _ if self.typ == EquipmentSlot::Talisman || self.is_epic => 1,
             0 => 5,
             1..=5 => relative_pos % 10,
             _ => relative_pos % 10 - 5,
         } as u8;
 
         let model_id = match () {
-            () if is_epic => relative_pos + 49,
-            () if typ == Talisman => relative_pos,
-            () if relative_pos % 5 != 0 => relative_pos / 5 + 1,
+            () if self.is_epic => relative_pos + 49,
+            () if self.typ == EquipmentSlot::Talisman => relative_pos,
+            () if !relative_pos.is_multiple_of(5) => relative_pos / 5 + 1,
             () => relative_pos / 5,
         } as u16;
 
-        return Some(EquipmentIdent {
-            class,
-            typ,
+        Some(EquipmentIdent {
+            class: self.class,
+            typ: self.typ,
             model_id,
             color,
-        });
+        })
+    }
+
+    fn index(&self, ident: EquipmentIdent) -> Option<usize> {
+        if ident.typ != self.typ || ident.class != self.class {
+            return None;
+        }
+        let model_id = usize::from(ident.model_id);
+        let color = usize::from(ident.color);
+        let relative_pos = if self.is_epic {
+            model_id.checked_sub(49)?
+        } else if self.typ == EquipmentSlot::Talisman {
+            model_id
+        } else {
+            if !(1..=5).contains(&color) {
+                return None;
+            }
+            model_id.checked_sub(1)? * 5 + color
+        };
+        let index = self.indices.start + relative_pos.checked_sub(1)?;
+        // Make sure this was not just a color/model, that does not exist
+        (self.ident(index)? == ident).then_some(index)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Describes which position in the scrapbook belongs to which monster, or
+/// item. The server has added entries over time, so the layout is versioned
+pub struct ScrapBookLayout {
+    /// Increased every time new entries are added to the layout
+    pub version: u32,
+    /// The positions of all the monsters. The position is the monster id
+    pub monsters: std::ops::Range<usize>,
+    /// All the blocks of items in the order they are stored in
+    pub slots: &'static [ScrapBookSlots],
+    /// The monsters, that are known to be from the tavern, or a dungeon.
+    /// Everything else is reported as `MonsterOrigin::Unknown`
+    pub monster_origins: &'static [(std::ops::Range<usize>, MonsterOrigin)],
+}
+
+impl ScrapBookLayout {
+    /// The layout, that the current server version uses
+    #[must_use]
+    pub fn current() -> &'static ScrapBookLayout {
+        &SCRAPBOOK_LAYOUT
+    }
+
+    /// The total amount of positions (bits) in the scrapbook
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots
+            .iter()
+            .map(|a| a.indices.end)
+            .chain([self.monsters.end])
+            .max()
+            .unwrap_or(1)
+            - 1
+    }
+
+    /// Checks if this layout does not contain any entries
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The item, that is stored at this position in the scrapbook
+    #[must_use]
+    pub fn item_ident(&self, index: usize) -> Option<EquipmentIdent> {
+        self.slots.iter().find_map(|a| a.ident(index))
+    }
+
+    /// The position in the scrapbook, that stores this item. This is the
+    /// reverse of `item_ident()`
+    #[must_use]
+    pub fn item_index(&self, ident: &EquipmentIdent) -> Option<usize> {
+        // Some blocks of normal items have model ids, that look like epics. If
+        // both blocks could contain the item, we prefer the one matching
+        // `is_epic()`
+        let (matching, other): (Vec<_>, Vec<_>) = self
+            .slots
+            .iter()
+            .partition(|a| a.is_epic == ident.is_epic());
+        matching
+            .into_iter()
+            .chain(other)
+            .find_map(|a| a.index(*ident))
+    }
+
+    /// All the blocks of items, that a character of the given class can wear.
+    /// Items are only ever tagged with one of the three main classes, so all
+    /// other classes use parts of their blocks
+    pub fn class_slots(
+        &self,
+        class: Class,
+    ) -> impl Iterator<Item = &ScrapBookSlots> {
+        self.slots.iter().filter(move |a| a.usable_by(class))
+    }
+
+    /// The section, that the entry at this position is shown in
+    #[must_use]
+    pub fn section(&self, index: usize) -> Option<ScrapBookSection> {
+        if self.monsters.contains(&index) {
+            return Some(ScrapBookSection::Monsters);
+        }
+        self.item_ident(index).map(|a| ScrapBookSection::of_ident(&a))
+    }
+
+    /// The amount of entries in one section of the scrapbook
+    #[must_use]
+    pub fn section_total(&self, section: ScrapBookSection) -> u32 {
+        match section {
+            ScrapBookSection::Monsters => {
+                self.monsters.len().try_into().unwrap_or(u32::MAX)
+            }
+            _ => self
+                .slots
+                .iter()
+                .filter(|a| {
+                    a.class.map_or(ScrapBookSection::Valuables, |c| {
+                        ScrapBookSection::of_item_class(c)
+                    }) == section
+                })
+                .map(ScrapBookSlots::len)
+                .sum(),
+        }
+    }
+
+    /// The place, where the monster with this id can be fought. Returns None,
+    /// if the id is not a monster in this layout
+    #[must_use]
+    pub fn monster_origin(&self, monster_id: u16) -> Option<MonsterOrigin> {
+        let id = usize::from(monster_id);
+        if !self.monsters.contains(&id) {
+            return None;
+        }
+        Some(
+            self.monster_origins
+                .iter()
+                .find(|(range, _)| range.contains(&id))
+                .map_or(MonsterOrigin::Unknown, |a| a.1),
+        )
+    }
+}
+
+#[allow(clippy::enum_glob_use)]
+mod layout {
+    use super::{
+        Class::*, EquipmentSlot::*, ScrapBookLayout, ScrapBookSlots,
+    };
+
+    const fn slots(
+        indices: std::ops::Range<usize>,
+        typ: super::EquipmentSlot,
+        class: Option<super::Class>,
+        is_epic: bool,
+        ignored: &'static [usize],
+    ) -> ScrapBookSlots {
+        ScrapBookSlots {
+            indices,
+            typ,
+            class,
+            is_epic,
+            ignored,
+        }
+    }
+
+    #[rustfmt::skip]
+    pub(super) const SCRAPBOOK_LAYOUT: ScrapBookLayout = ScrapBookLayout {
+        version: 1,
+        monsters: 1..801,
+        slots: &[
+            slots(801..1011, Amulet, None, false, &[]),
+            slots(1011..1051, Amulet, None, true, &[]),
+            slots(1051..1211, Ring, None, false, &[]),
+            slots(1211..1251, Ring, None, true, &[]),
+            slots(1251..1325, Talisman, None, false, &[]),
+            slots(1325..1365, Talisman, None, true, &[]),
+            slots(1365..1665, Weapon, Some(Warrior), false, &[]),
+            slots(1665..1705, Weapon, Some(Warrior), true, &[]),
+            slots(1705..1805, Shield, Some(Warrior), false, &[]),
+            slots(1805..1845, Shield, Some(Warrior), true, &[]),
+            slots(1845..1945, BreastPlate, Some(Warrior), false, &[]),
+            slots(1945..1985, BreastPlate, Some(Warrior), true, &[1954, 1955]),
+            slots(1985..2085, FootWear, Some(Warrior), false, &[]),
+            slots(2085..2125, FootWear, Some(Warrior), true, &[2094, 2095]),
+            slots(2125..2225, Gloves, Some(Warrior), false, &[]),
+            slots(2225..2265, Gloves, Some(Warrior), true, &[2234, 2235]),
+            slots(2265..2365, Hat, Some(Warrior), false, &[]),
+            slots(2365..2405, Hat, Some(Warrior), true, &[2374, 2375]),
+            slots(2405..2505, Belt, Some(Warrior), false, &[]),
+            slots(2505..2545, Belt, Some(Warrior), true, &[2514, 2515]),
+            slots(2545..2645, Weapon, Some(Mage), false, &[]),
+            slots(2645..2685, Weapon, Some(Mage), true, &[]),
+            slots(2685..2785, BreastPlate, Some(Mage), false, &[]),
+            slots(2785..2825, BreastPlate, Some(Mage), true, &[2794, 2795]),
+            slots(2825..2925, FootWear, Some(Mage), false, &[]),
+            slots(2925..2965, FootWear, Some(Mage), true, &[2934, 2935]),
+            slots(2965..3065, Gloves, Some(Mage), false, &[]),
+            slots(3065..3105, Gloves, Some(Mage), true, &[3074, 3075]),
+            slots(3105..3205, Hat, Some(Mage), false, &[]),
+            slots(3205..3245, Hat, Some(Mage), true, &[3214, 3215]),
+            slots(3245..3345, Belt, Some(Mage), false, &[]),
+            slots(3345..3385, Belt, Some(Mage), true, &[3354, 3355]),
+            slots(3385..3485, Weapon, Some(Scout), false, &[]),
+            slots(3485..3525, Weapon, Some(Scout), true, &[]),
+            slots(3525..3625, BreastPlate, Some(Scout), false, &[]),
+            slots(3625..3665, BreastPlate, Some(Scout), true, &[3634, 3635]),
+            slots(3665..3765, FootWear, Some(Scout), false, &[]),
+            slots(3765..3805, FootWear, Some(Scout), true, &[3774, 3775]),
+            slots(3805..3905, Gloves, Some(Scout), false, &[]),
+            slots(3905..3945, Gloves, Some(Scout), true, &[3914, 3915]),
+            slots(3945..4045, Hat, Some(Scout), false, &[]),
+            slots(4045..4085, Hat, Some(Scout), true, &[4054, 4055]),
+            slots(4085..4185, Belt, Some(Scout), false, &[]),
+            slots(4185..4225, Belt, Some(Scout), true, &[4194, 4195]),
+        ],
+        monster_origins: &[],
+    };
+}
+use layout::SCRAPBOOK_LAYOUT;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLASSES: [Class; 11] = [
+        Class::Warrior,
+        Class::Mage,
+        Class::Scout,
+        Class::Assassin,
+        Class::BattleMage,
+        Class::Berserker,
+        Class::DemonHunter,
+        Class::Druid,
+        Class::Bard,
+        Class::Necromancer,
+        Class::Paladin,
+    ];
+
+    #[test]
+    fn every_class_has_items() {
+        let layout = ScrapBookLayout::current();
+        for class in CLASSES {
+            for slot in EquipmentSlot::iter() {
+                let can_wear =
+                    layout.class_slots(class).any(|a| a.typ == slot);
+                let has_shield =
+                    matches!(class, Class::Warrior | Class::Paladin);
+                assert_eq!(
+                    can_wear,
+                    slot != EquipmentSlot::Shield || has_shield,
+                    "{class:?} {slot:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn item_index_roundtrip() {
+        let layout = ScrapBookLayout::current();
+        for index in 1..=layout.len() {
+            let Some(ident) = layout.item_ident(index) else {
+                continue;
+            };
+            // Some blocks of normal items have more than 49 models. These
+            // have the same idents as the first epics, so they can only ever
+            // resolve to the epics
+            let in_normal_block = layout
+                .slots
+                .iter()
+                .any(|a| a.indices.contains(&index) && !a.is_epic);
+            if in_normal_block && ident.is_epic() {
+                continue;
+            }
+            assert_eq!(layout.item_index(&ident), Some(index), "{ident:?}");
+        }
+    }
+
+    #[test]
+    fn unknown_monster_origin() {
+        let layout = ScrapBookLayout::current();
+        assert_eq!(layout.monster_origin(0), None);
+        assert_eq!(layout.monster_origin(1), Some(MonsterOrigin::Unknown));
+        assert_eq!(layout.monster_origin(801), None);
+    }
+
+    #[cfg(all(feature = "serde", feature = "session"))]
+    #[test]
+    fn deserialize_unversioned_scrapbook() {
+        let scrapbook: ScrapBook =
+            serde_json::from_str(r#"{"items":[],"monster":[1,5]}"#).unwrap();
+        assert_eq!(scrapbook.monster, HashSet::from([1, 5]));
+        assert_eq!(scrapbook.layout_version, 1);
+        assert!(scrapbook.unknown.is_empty());
+    }
+
+    fn scrapbook_fixtures() -> impl Iterator<Item = &'static str> {
+        include_str!("../../data/scrapbooks.txt")
+            .lines()
+            .filter(|a| !a.starts_with('#') && !a.is_empty())
+    }
+
+    #[test]
+    fn scrapbook_roundtrip() {
+        for fixture in scrapbook_fixtures() {
+            let scrapbook = ScrapBook::parse(fixture).unwrap();
+            let encoded = scrapbook.to_server_string();
+            assert_eq!(encoded, fixture);
+            assert_eq!(ScrapBook::parse(&encoded), Some(scrapbook));
+        }
+    }
+
+    #[test]
+    fn scrapbook_diff() {
+        let mut rng = fastrand::Rng::with_seed(0);
+        for fixture in scrapbook_fixtures() {
+            let scrapbook = ScrapBook::parse(fixture).unwrap();
+            assert!(scrapbook.added(&scrapbook).is_empty());
+
+            for _ in 0..20 {
+                let mut older = scrapbook.clone();
+                older.items.retain(|_| rng.bool());
+                older.monster.retain(|_| rng.bool());
+                older.unknown.retain(|_| rng.bool());
+
+                let added = scrapbook.added(&older);
+                assert!(scrapbook.removed(&older).is_empty());
+                assert_eq!(older.removed(&scrapbook), added);
+                assert_eq!(
+                    added.items.len() + older.items.len(),
+                    scrapbook.items.len()
+                );
+                assert_eq!(
+                    added.monster.len() + older.monster.len(),
+                    scrapbook.monster.len()
+                );
+                if !added.is_empty() {
+                    assert_eq!(
+                        ScrapBook::parse(&added.to_server_string()),
+                        Some(added)
+                    );
+                }
+            }
+        }
     }
-    None
 }
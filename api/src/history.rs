@@ -0,0 +1,221 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use log::warn;
+
+use crate::{gamestate::unlockables::ScrapBook, PlayerId};
+
+#[derive(Debug, Clone, PartialEq)]
+/// The scrapbook of a character at some point in time
+pub struct ScrapBookSnapshot {
+    /// The time the response with this scrapbook was received at
+    pub time: NaiveDateTime,
+    pub scrapbook: ScrapBook,
+}
+
+/// Converts the local time of a snapshot into the unix timestamp, that is
+/// stored in the history files
+fn to_timestamp(time: NaiveDateTime) -> i64 {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        // This time does not exist locally, because the clock was turned
+        // forward at that time. This should never happen for real snapshots
+        .map_or_else(|| time.and_utc().timestamp(), |a| a.timestamp())
+}
+
+#[derive(Debug, Clone)]
+/// Stores the scrapbooks of characters on disk to be able to see, how they
+/// progress over time. Every character has its own file in
+/// `<dir>/<server>/<player_id>.scrapbook`, which contains one line per day
+/// with the timestamp and the scrapbook encoded via `to_server_string()`.
+/// Recording the same character multiple times on the same day just replaces
+/// the snapshot for that day
+pub struct ScrapBookHistory {
+    dir: PathBuf,
+}
+
+impl ScrapBookHistory {
+    /// Creates a history, that stores everything in the given directory. The
+    /// directory will be created, once the first snapshot is recorded
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The directory this history is stored in
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn file(&self, server: &str, player_id: PlayerId) -> PathBuf {
+        // The server is normally a host like s1.sfgame.net, but we do not want
+        // to create random directories, if someone passes in a full url
+        let server: String = server
+            .chars()
+            .map(|a| match a {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' => a,
+                _ => '_',
+            })
+            .collect();
+        self.dir.join(server).join(format!("{player_id}.scrapbook"))
+    }
+
+    /// Records the scrapbook of a character. If there already is a snapshot
+    /// for the same day, it is replaced by this one
+    ///
+    /// # Errors
+    /// If the history file could not be read, or written
+    pub fn record(
+        &self,
+        server: &str,
+        player_id: PlayerId,
+        scrapbook: &ScrapBook,
+        time: NaiveDateTime,
+    ) -> std::io::Result<()> {
+        let path = self.file(server, player_id);
+        let mut snapshots = self.snapshots(server, player_id)?;
+        snapshots.retain(|a| a.time.date() != time.date());
+        let pos = snapshots.partition_point(|a| a.time < time);
+        snapshots.insert(
+            pos,
+            ScrapBookSnapshot {
+                time,
+                scrapbook: scrapbook.clone(),
+            },
+        );
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        for snapshot in snapshots {
+            writeln!(
+                file,
+                "{} {}",
+                to_timestamp(snapshot.time),
+                snapshot.scrapbook.to_server_string()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// All the snapshots of a character, that have been recorded so far. The
+    /// oldest one is at the front
+    ///
+    /// # Errors
+    /// If the history file exists, but could not be read
+    pub fn snapshots(
+        &self,
+        server: &str,
+        player_id: PlayerId,
+    ) -> std::io::Result<Vec<ScrapBookSnapshot>> {
+        let content =
+            match std::fs::read_to_string(self.file(server, player_id)) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Ok(Vec::new())
+                }
+                Err(e) => return Err(e),
+            };
+
+        let mut snapshots = Vec::new();
+        for line in content.lines().filter(|a| !a.is_empty()) {
+            let parsed = line.split_once(' ').and_then(|(time, sb)| {
+                let time = DateTime::from_timestamp(time.parse().ok()?, 0)?;
+                Some(ScrapBookSnapshot {
+                    time: time.with_timezone(&Local).naive_local(),
+                    scrapbook: ScrapBook::parse(sb)?,
+                })
+            });
+            match parsed {
+                Some(snapshot) => snapshots.push(snapshot),
+                None => warn!("Invalid scrapbook history entry: {line}"),
+            }
+        }
+        snapshots.sort_by_key(|a| a.time);
+        Ok(snapshots)
+    }
+
+    /// Everything, that the character has collected since the given time.
+    /// This compares the newest snapshot with the last one recorded before
+    /// `since`. If there is none, the oldest snapshot is used instead. To get
+    /// the items gained this week, you would use `now - 7 days`
+    ///
+    /// # Errors
+    /// If the history file exists, but could not be read
+    pub fn gained_since(
+        &self,
+        server: &str,
+        player_id: PlayerId,
+        since: NaiveDateTime,
+    ) -> std::io::Result<Option<ScrapBook>> {
+        let snapshots = self.snapshots(server, player_id)?;
+        let Some(newest) = snapshots.last() else {
+            return Ok(None);
+        };
+        let oldest = snapshots
+            .iter()
+            .rev()
+            .find(|a| a.time <= since)
+            .or(snapshots.first())
+            .unwrap_or(newest);
+        Ok(Some(newest.scrapbook.added(&oldest.scrapbook)))
+    }
+
+    /// The total completion of the scrapbook in percent (0.0 - 100.0) for
+    /// every recorded day. The oldest one is at the front
+    ///
+    /// # Errors
+    /// If the history file exists, but could not be read
+    pub fn completion_over_time(
+        &self,
+        server: &str,
+        player_id: PlayerId,
+    ) -> std::io::Result<Vec<(NaiveDateTime, f64)>> {
+        Ok(self
+            .snapshots(server, player_id)?
+            .into_iter()
+            .map(|a| (a.time, a.scrapbook.total_progress().percent()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Timelike};
+
+    use super::*;
+
+    #[test]
+    fn history_stores_unix_timestamps() {
+        let dir = std::env::temp_dir()
+            .join(format!("sf_api_history_{}", std::process::id()));
+        let history = ScrapBookHistory::new(&dir);
+        let scrapbook = ScrapBook::parse("gA==").unwrap();
+
+        let now = Local::now().with_nanosecond(0).unwrap();
+        let yesterday = now.naive_local() - Duration::days(1);
+        history.record("s1", 1, &scrapbook, yesterday).unwrap();
+        history.record("s1", 1, &scrapbook, now.naive_local()).unwrap();
+
+        let content =
+            std::fs::read_to_string(dir.join("s1").join("1.scrapbook"));
+        let snapshots = history.snapshots("s1", 1).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let last_line = content.unwrap().lines().last().unwrap().to_string();
+        assert!(last_line.starts_with(&format!("{} ", now.timestamp())));
+        let times: Vec<_> = snapshots.iter().map(|a| a.time).collect();
+        assert_eq!(times, [yesterday, now.naive_local()]);
+    }
+}
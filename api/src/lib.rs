@@ -17,15 +17,28 @@
 #![allow(unsafe_code)] // Explicitly allowing unsafe code for FFI
 
 pub mod command;
+#[cfg(feature = "session")]
+pub mod crawler;
 pub mod error;
 pub mod gamestate;
+pub mod history;
 pub mod misc;
+#[cfg(any(feature = "mock-server", all(test, feature = "session")))]
+pub mod mock_server;
+pub mod outcome;
+pub mod quest_chooser;
+#[cfg(feature = "session")]
+pub mod rate_limit;
 pub mod response;
 #[cfg(feature = "session")]
 pub mod session;
+#[cfg(feature = "session")]
+pub mod shop_scanner;
 pub mod simulate;
 #[cfg(feature = "sso")]
 pub mod sso;
+#[cfg(feature = "session")]
+pub mod transport;
 
 /// Represents the numerical ID of a player on a server.
 pub type PlayerId = u32;
@@ -42,7 +55,7 @@ pub use crate::gamestate::unlockables::*;
 pub use crate::{
     gamestate::{
         character::*,
-        dungeons::{CompanionClass, Dungeon},
+        dungeons::{CompanionClass, Dungeon, LightDungeon, ShadowDungeon},
         guild::{Emblem, GuildSkill},
         idle::IdleBuildingType,
         items::*,
@@ -50,153 +63,3010 @@ pub use crate::{
     },
 };
 
+use std::cell::UnsafeCell;
 use std::ffi::{CStr, CString};
 use std::ptr;
-use tokio::runtime::Runtime;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::time::Duration;
+use tokio::{runtime::Runtime, time::sleep};
+
+use crate::gamestate::{
+    arena::Arena, catalogue::ItemCatalogue, guild::Guild, social::{HallOfFamePlayer, OtherPlayer}, tavern::{GambleResult, Quest, Tavern},
+    GameState,
+};
+use crate::history::ScrapBookHistory;
+#[cfg(feature = "session")]
+use crate::transport::{RecordingTransport, ReplayTransport, Transport};
+use crate::outcome::{CommandOutcome, FightOutcome};
+#[cfg(feature = "session")]
+use crate::rate_limit::{RateBudget, RateLimitOptions};
+#[cfg(feature = "session")]
+use crate::session::ConnectionOptions;
+#[cfg(feature = "sso")]
+use crate::sso::{SFAccount, ServerLookup};
+use crate::quest_chooser::{rate_quests, recommend_quest, QuestRating, QuestWeights};
+use strum::IntoEnumIterator;
+use crate::misc::EnumMapGet;
+use crate::simulate::{
+    estimator::{DamageDistribution, EstimateOptions, FightEstimate},
+    BattleFighter, PlayerFighterSquad, UpgradeableFighter,
+};
+#[cfg(all(feature = "serde", feature = "session"))]
+use crate::simulate::{timeline::TimelineRecorder, Battle};
+
+/// The session handed out to C++. While a command of this session runs in the
+/// background, that command has mutable access to everything but the busy
+/// flag, so nothing else may touch the session during that time
+#[derive(Debug)]
+pub struct GameSession {
+    /// Set, while a command of this session is running in the background.
+    /// Nothing may borrow the state during that time
+    busy: AtomicBool,
+    state: UnsafeCell<SessionState>,
+}
+
+impl GameSession {
+    fn new(session: Session) -> Self {
+        GameSession {
+            busy: AtomicBool::new(false),
+            state: UnsafeCell::new(SessionState::new(session)),
+        }
+    }
+}
+
+/// Everything a `GameSession` owns next to its busy flag. Next to the
+/// connection to the server, this also owns the `GameState` of the character,
+/// which is updated with every response, so that the C++ side can read parsed
+/// values instead of raw response strings
+#[derive(Debug)]
+struct SessionState {
+    session: Session,
+    /// The parsed state of the game. This is `None` until the first successful
+    /// login and gets cleared, if a response could not be parsed
+    gamestate: Option<GameState>,
+    /// If set, the scrapbook is recorded here after every `Update` and
+    /// `ViewScrapbook`
+    history: Option<ScrapBookHistory>,
+    /// Decides how failed commands are retried
+    recovery: CRecoveryPolicy,
+    /// Informs C++ about everything the recovery does
+    recovery_hook: Option<RecoveryHook>,
+    /// The direct result of the last command, if it has one
+    outcome: Option<CommandOutcome>,
+}
+
+impl SessionState {
+    fn new(session: Session) -> Self {
+        SessionState {
+            session,
+            gamestate: None,
+            history: None,
+            recovery: CRecoveryPolicy::default(),
+            recovery_hook: None,
+            outcome: None,
+        }
+    }
+
+    async fn login(&mut self) -> Result<(), SFError> {
+        self.gamestate = None;
+        self.outcome = None;
+        let resp = self.session.login().await?;
+        self.gamestate = Some(GameState::new(resp)?);
+        Ok(())
+    }
+
+    /// Sends the command and updates the gamestate with the response. Same as
+    /// with `SimpleSession`, the gamestate is cleared, if the response could
+    /// not be used to update it
+    async fn send_command(&mut self, command: Command) -> Result<Response, SFError> {
+        let record = matches!(command, Command::Update | Command::ViewScrapbook);
+        self.outcome = None;
+        let resp = self.session.send_command(&command).await?;
+        if let Some(gs) = &mut self.gamestate {
+            if let Err(e) = gs.update(&resp) {
+                self.gamestate = None;
+                return Err(e);
+            }
+            self.outcome = CommandOutcome::from_state(&command, gs);
+        }
+        if record {
+            self.record_scrapbook(resp.received_at());
+        }
+        Ok(resp)
+    }
+
+    /// Same as `send_command`, but recovers from lost connections and expired
+    /// sessions according to the recovery policy of this session. Same as
+    /// with `SimpleSession`, a session, whose gamestate has been cleared, is
+    /// logged in again before the command is sent
+    async fn send_command_recovering(&mut self, command: Command) -> Result<Response, SFError> {
+        let policy = self.recovery;
+        let mut relogin = policy.relogin && self.gamestate.is_none() && self.session.has_session_id();
+        let mut attempt = 0;
+        loop {
+            let login_result = match relogin {
+                true => self.recover_login(attempt).await,
+                false => Ok(()),
+            };
+            let login_failed = login_result.is_err();
+            let error = match login_result {
+                Ok(()) => match self.send_command(command.clone()).await {
+                    Ok(resp) => return Ok(resp),
+                    Err(e) => e,
+                },
+                Err(e) => e,
+            };
+
+            let Some(needs_login) = policy.recovery_for(&command, &error) else {
+                return Err(error);
+            };
+            if attempt >= policy.max_retries {
+                return Err(error);
+            }
+            attempt += 1;
+            self.recovery_event(CRecoveryEventKind::Retry, attempt, Some(&error));
+            sleep(policy.backoff(attempt)).await;
+            relogin = login_failed || needs_login;
+        }
+    }
+
+    /// Logs the session in again. If that fails for an SSO character, the
+    /// credentials are renewed from the account and the login is tried once
+    /// more
+    async fn recover_login(&mut self, attempt: u32) -> Result<(), SFError> {
+        let result = match self.login().await {
+            #[cfg(feature = "sso")]
+            Err(e) if self.recovery.renew_sso && self.session.is_sso() => {
+                log::warn!("Could not login again, renewing SSO credentials: {e}");
+                match self.session.renew_sso_creds().await {
+                    Ok(()) => {
+                        self.recovery_event(CRecoveryEventKind::SsoRenewed, attempt, Some(&e));
+                        self.login().await
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            result => result,
+        };
+        match &result {
+            Ok(()) => self.recovery_event(CRecoveryEventKind::Relogin, attempt, None),
+            Err(e) => self.recovery_event(CRecoveryEventKind::ReloginFailed, attempt, Some(e)),
+        }
+        result
+    }
 
-/// Common function to execute a command and return a response
-fn execute_command(session: *mut Session, command: Command) -> *mut Response {
+    fn recovery_event(&self, kind: CRecoveryEventKind, attempt: u32, error: Option<&SFError>) {
+        let Some(hook) = self.recovery_hook else {
+            return;
+        };
+        let error = error.map_or(CErrorKind::None, Into::into);
+        (hook.callback)(CRecoveryEvent { kind, attempt, error }, hook.user_data as *mut std::ffi::c_void);
+    }
+
+    /// The server this session is connected to. This is what the scrapbook
+    /// history uses to tell characters apart
+    fn server_host(&self) -> &str {
+        self.session.server_url().host_str().unwrap_or_default()
+    }
+
+    fn record_scrapbook(&self, time: chrono::NaiveDateTime) {
+        let (Some(history), Some(gs)) = (&self.history, &self.gamestate) else {
+            return;
+        };
+        let Some(scrapbook) = &gs.character.scrapbook else {
+            return;
+        };
+        let pid = gs.character.player_id;
+        if let Err(e) = history.record(self.server_host(), pid, scrapbook, time) {
+            log::warn!("Could not record scrapbook history: {e}");
+        }
+    }
+}
+
+/// The runtime all the commands are run on. Creating a new runtime for every
+/// command is slow and would not allow commands to run in the background
+static RUNTIME: LazyLock<Result<Runtime, String>> =
+    LazyLock::new(|| Runtime::new().map_err(|e| e.to_string()));
+
+/// The runtime to run commands on. If it could not be created, the `IOError`
+/// is set and `None` is returned
+fn runtime() -> Option<&'static Runtime> {
+    match &*RUNTIME {
+        Ok(runtime) => Some(runtime),
+        Err(e) => {
+            set_last_error(&SFError::IOError(format!("Could not create the Tokio runtime: {e}")));
+            None
+        }
+    }
+}
+
+/// Common function to execute a command and return a response. If a
+/// background command has been requested with `begin_async_command`, the
+/// command is started in the background instead and null is returned
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+unsafe fn execute_command(session: *mut GameSession, command: Command) -> *mut Response {
     if session.is_null() {
+        ASYNC_REQUEST.take();
+        return invalid_argument();
+    }
+    if let Some(request) = ASYNC_REQUEST.take() {
+        start_async_command(session, command, request);
         return ptr::null_mut();
     }
 
-    let session = unsafe { &mut *session };
-    let runtime = Runtime::new().expect("Failed to create Tokio runtime");
+    let Some(session) = idle_session_mut(session) else {
+        return ptr::null_mut();
+    };
 
-    match runtime.block_on(session.send_command(command)) {
+    clear_last_error();
+    let Some(runtime) = runtime() else {
+        return ptr::null_mut();
+    };
+    match runtime.block_on(session.send_command_recovering(command)) {
         Ok(response) => Box::into_raw(Box::new(response)),
         Err(e) => {
-            eprintln!("execute_command: Failed to execute command: {:?}", e);
+            set_last_error(&e);
             ptr::null_mut()
         }
     }
 }
 
+/// Turns a pointer handed to us by C++ into a reference, if it is not null
+///
+/// # Safety
+/// `ptr` has to be null, or point to a valid `T`, that lives for `'a`
+unsafe fn ffi_ref<'a, T>(ptr: *const T) -> Option<&'a T> {
+    unsafe { ptr.as_ref() }
+}
+
+/// Reads a string handed to us by C++. If the string is not valid UTF-8, it is
+/// read as an empty string
+///
+/// # Safety
+/// `value` has to be null, or a valid, nul terminated string, that lives for `'a`
+unsafe fn from_c_str<'a>(value: *const i8) -> Option<&'a str> {
+    if value.is_null() {
+        return None;
+    }
+    Some(unsafe { CStr::from_ptr(value) }.to_str().unwrap_or(""))
+}
+
+/// Copies the string into a newly allocated C string, that has to be freed
+/// with `destr_string`
+fn to_c_string(value: &str) -> *mut i8 {
+    CString::new(value).map_or(ptr::null_mut(), |a| a.into_raw().cast())
+}
+
+/// Converts an optional point in time into a unix timestamp. If there is no
+/// time, 0 is returned
+fn to_timestamp(time: Option<chrono::DateTime<chrono::Local>>) -> i64 {
+    time.map(|a| a.timestamp()).unwrap_or_default()
+}
+
+
+// #############################################
+// #                 ERRORS                    #
+// #############################################
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The C compatible version of the `SFError` variants
+pub enum CErrorKind {
+    /// The last call did not fail
+    None = 0,
+    InvalidRequest,
+    EmptyResponse,
+    ConnectionError,
+    ParsingError,
+    ServerError,
+    UnsupportedVersion,
+    TooShortResponse,
+    /// C++ handed us a null pointer, or a value, that is out of range
+    InvalidArgument,
+    /// The session is still busy with a command running in the background
+    SessionBusy,
+    IOError,
+}
+
+impl From<&SFError> for CErrorKind {
+    fn from(error: &SFError) -> Self {
+        match error {
+            SFError::InvalidRequest(_) => CErrorKind::InvalidRequest,
+            SFError::EmptyResponse => CErrorKind::EmptyResponse,
+            SFError::ConnectionError => CErrorKind::ConnectionError,
+            SFError::ParsingError(..) => CErrorKind::ParsingError,
+            SFError::ServerError(_) => CErrorKind::ServerError,
+            SFError::UnsupportedVersion(_) => CErrorKind::UnsupportedVersion,
+            SFError::TooShortResponse { .. } => CErrorKind::TooShortResponse,
+            SFError::IOError(_) => CErrorKind::IOError,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug)]
+/// An error, that occurred in the last call on this thread. The message has
+/// to be freed with `destr_error`
+pub struct CError {
+    pub kind: CErrorKind,
+    /// The full error message. For `ServerError` this is the raw error the
+    /// server sent back. Null, if `kind` is `None`
+    pub message: *mut i8,
+}
+
+thread_local! {
+    /// The error of the last failed `login`, or `exec_*` call on this thread
+    static LAST_ERROR: std::cell::RefCell<Option<(CErrorKind, String)>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+fn set_last_error(error: &SFError) {
+    let message = match error {
+        SFError::ServerError(msg) => msg.clone(),
+        _ => error.to_string(),
+    };
+    LAST_ERROR.set(Some((error.into(), message)));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.set(None);
+}
+
+/// Remembers, that C++ has handed us something invalid and returns the null
+/// response, that the `exec_*` functions hand back in that case
+fn invalid_argument<T>() -> *mut T {
+    LAST_ERROR.set(Some((
+        CErrorKind::InvalidArgument,
+        "An argument was null, or had an invalid value".to_string(),
+    )));
+    ptr::null_mut()
+}
+
+fn session_busy<T>() -> *mut T {
+    LAST_ERROR.set(Some((
+        CErrorKind::SessionBusy,
+        "The session is still running a command in the background".to_string(),
+    )));
+    ptr::null_mut()
+}
+
+/// The flag, that is set, while the session runs a command in the
+/// background. This is the only part of a busy session, that may be touched,
+/// because the command itself has mutable access to the state
+///
+/// # Safety
+/// `session` has to be a valid `GameSession`, that lives for `'a`
+unsafe fn busy_flag<'a>(session: *const GameSession) -> &'a AtomicBool {
+    unsafe { &(*session).busy }
+}
+
+/// Borrows the session, if it is neither null, nor busy. If it is busy, the
+/// `SessionBusy` error is set
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that lives for `'a`
+unsafe fn idle_session<'a>(session: *const GameSession) -> Option<&'a SessionState> {
+    if session.is_null() {
+        return None;
+    }
+    if busy_flag(session).load(Ordering::Acquire) {
+        session_busy::<GameSession>();
+        return None;
+    }
+    Some(unsafe { &*(*session).state.get() })
+}
+
+/// Mutably borrows the session, if it is neither null, nor busy. Otherwise
+/// the matching error is set
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else for `'a`
+unsafe fn idle_session_mut<'a>(
+    session: *mut GameSession,
+) -> Option<&'a mut SessionState> {
+    if session.is_null() {
+        invalid_argument::<GameSession>();
+        return None;
+    }
+    if busy_flag(session).load(Ordering::Acquire) {
+        session_busy::<GameSession>();
+        return None;
+    }
+    Some(unsafe { &mut *(*session).state.get() })
+}
+
+/// The kind of error the last `login`, or `exec_*` call on this thread has
+/// failed with. This is `None`, if the last call succeeded
+#[no_mangle]
+pub extern "C" fn last_error_kind() -> CErrorKind {
+    LAST_ERROR.with_borrow(|a| a.as_ref().map_or(CErrorKind::None, |a| a.0))
+}
+
+/// Takes the error of the last failed `login`, or `exec_*` call on this
+/// thread. Calling this a second time returns an error with the kind `None`
+#[no_mangle]
+pub extern "C" fn take_last_error() -> CError {
+    match LAST_ERROR.take() {
+        Some((kind, message)) => CError { kind, message: to_c_string(&message) },
+        None => CError { kind: CErrorKind::None, message: ptr::null_mut() },
+    }
+}
+
+/// Frees the message of an error returned by `take_last_error`
+///
+/// # Safety
+/// `error` has to be an error from `take_last_error`, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_error(error: CError) {
+    unsafe { destr_string(error.message) };
+}
+
 
 // ##############################################
 // #                 SESSION                    #
 // ##############################################
 
 /// Creates a new session instance
+///
+/// # Safety
+/// - `username` has to be a valid, nul terminated string
+/// - `password` has to be a valid, nul terminated string
+/// - `server_url` has to be a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn init_session(username: *const i8, password: *const i8, server_url: *const i8) -> *mut Session {
+pub unsafe extern "C" fn init_session(username: *const i8, password: *const i8, server_url: *const i8) -> *mut GameSession {
     let user = unsafe { CStr::from_ptr(username).to_str().unwrap_or("").to_string() };
     let pass = unsafe { CStr::from_ptr(password).to_str().unwrap_or("").to_string() };
     let server = unsafe { CStr::from_ptr(server_url).to_str().unwrap_or("").to_string() };
 
     let server_connection = match ServerConnection::new(&server) {
         Some(conn) => conn,
-        None => return ptr::null_mut(),
+        None => return invalid_argument(),
     };
 
     let session = Session::new(&user, &pass, server_connection);
-    Box::into_raw(Box::new(session))
+    Box::into_raw(Box::new(GameSession::new(session)))
 }
 
 /// Frees a session instance
+///
+/// # Safety
+/// `session` has to be null, or a pointer from this library, that has not been freed yet
 #[no_mangle]
-pub extern "C" fn destr_session(session: *mut Session) {
+pub unsafe extern "C" fn destr_session(session: *mut GameSession) {
     if !session.is_null() {
         unsafe { drop(Box::from_raw(session)) };
     }
 }
 
-/// Logs in using a session
+/// Logs in using a session. If this fails, the reason can be retrieved with
+/// `take_last_error`
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn login(session: *mut Session) -> bool {
-    if session.is_null() {
+pub unsafe extern "C" fn login(session: *mut GameSession) -> bool {
+    let Some(session) = idle_session_mut(session) else {
+        return false;
+    };
+    let Some(runtime) = runtime() else {
         return false;
+    };
+    match runtime.block_on(session.login()) {
+        Ok(()) => {
+            clear_last_error();
+            true
+        }
+        Err(e) => {
+            set_last_error(&e);
+            false
+        }
     }
-    let session = unsafe { &mut *session };
-    let runtime = Runtime::new().expect("Failed to create Tokio runtime");
-    runtime.block_on(session.login()).is_ok()
 }
 
 
-// #######################################################
-// #                 RESPONSE FREEING                    #
-// #######################################################
+// ################################################
+// #                 RECOVERY                     #
+// ################################################
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Decides how a session recovers from failed commands. Only lost
+/// connections, empty responses and expired sessions are recovered from.
+/// Every other error, like not having enough silver, is returned right away.
+/// A command, that failed with a lost connection, or an empty response, might
+/// have already been executed by the server, so only commands, that just look
+/// at things, like `Update`, or `ViewPlayer`, are retried after those. The
+/// same goes for expired sessions, because their error messages have not been
+/// confirmed yet
+pub struct CRecoveryPolicy {
+    /// How often a failed command is tried again. 0 disables the recovery
+    pub max_retries: u32,
+    /// The time to wait before the first retry. This is doubled with every
+    /// further retry
+    pub backoff_ms: u64,
+    /// The longest time to wait between two retries
+    pub max_backoff_ms: u64,
+    /// Logs the session in again, if the server says, that our session has
+    /// expired. This happens after a server restart, or if the character has
+    /// been logged in somewhere else
+    pub relogin: bool,
+    /// Renews the credentials of SSO characters from their account, if
+    /// logging in again fails
+    pub renew_sso: bool,
+}
 
-/// Frees a response object
-#[no_mangle]
-pub extern "C" fn destr_response(response: *mut Response) {
-    if !response.is_null() {
-        unsafe { drop(Box::from_raw(response)) };
+impl Default for CRecoveryPolicy {
+    fn default() -> Self {
+        Self { max_retries: 3, backoff_ms: 1000, max_backoff_ms: 30_000, relogin: true, renew_sso: true }
     }
 }
 
+impl CRecoveryPolicy {
+    /// Decides, if the command can be retried after this error. If it can,
+    /// this returns if the session has to be logged in again beforehand
+    fn recovery_for(&self, command: &Command, error: &SFError) -> Option<bool> {
+        match error {
+            SFError::ConnectionError if is_read_only(command) => Some(false),
+            SFError::EmptyResponse if self.relogin && is_read_only(command) => Some(true),
+            // Until the messages are confirmed, a different error could be
+            // mistaken for an expired session, so the same applies here
+            SFError::ServerError(msg) if self.relogin && is_read_only(command) && is_session_expired(msg) => Some(true),
+            _ => None,
+        }
+    }
 
-// #####################################################
-// #                 RESPONSE VALUE                    #
-// #####################################################
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u64.checked_shl(attempt.saturating_sub(1)).unwrap_or(u64::MAX);
+        Duration::from_millis(self.backoff_ms.saturating_mul(factor).min(self.max_backoff_ms))
+    }
+}
 
-/// Retrieves a value from a parsed response
+/// The errors, that the server rejects commands of an expired session with.
+/// These have not been confirmed against recorded server responses yet, so
+/// anything else is treated as a normal error and not recovered from. Commands,
+/// that change something, are never resent after these, until they are
+const SESSION_EXPIRED_ERRORS: [&str; 2] = ["sessionid invalid", "session expired"];
+
+/// The server does not have a dedicated error code for this, so we have to
+/// look at the message
+fn is_session_expired(msg: &str) -> bool {
+    SESSION_EXPIRED_ERRORS.iter().any(|a| msg.trim().eq_ignore_ascii_case(a))
+}
+
+/// Checks if the command only looks at things and does not change anything
+/// on the server. Only these can safely be sent twice. Everything not listed
+/// here counts as changing something, so that it is never repeated by accident
+#[allow(deprecated)]
+fn is_read_only(command: &Command) -> bool {
+    #[cfg(feature = "sso")]
+    if matches!(command, Command::SSOLogin { .. }) {
+        return true;
+    }
+    matches!(
+        command,
+        Command::Login { .. }
+            | Command::Update
+            | Command::HallOfFamePage { .. }
+            | Command::HallOfFameFortressPage { .. }
+            | Command::HallOfFameGroupPage { .. }
+            | Command::HallOfFameUnderworldPage { .. }
+            | Command::HallOfFamePetsPage { .. }
+            | Command::HallOfFameHellevatorPage { .. }
+            | Command::ViewPlayer { .. }
+            | Command::ViewGuild { .. }
+            | Command::ViewScrapbook
+            | Command::ViewPet { .. }
+            | Command::CheckArena
+            | Command::CheckNameAvailable { .. }
+            | Command::GuildGetFightableTargets
+            | Command::HellevatorViewGuildRanking
+            | Command::HellevatorPreviewRewards
+            | Command::ClaimablePreview { .. }
+    )
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CRecoveryEventKind {
+    /// A command has failed and will be tried again after the backoff
+    Retry = 0,
+    /// The session has been logged in again. The gamestate has been replaced
+    /// by the one from the login response
+    Relogin,
+    /// The credentials of an SSO character have been renewed
+    SsoRenewed,
+    /// Logging the session in again has failed
+    ReloginFailed,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// Something the recovery of a session has done
+pub struct CRecoveryEvent {
+    pub kind: CRecoveryEventKind,
+    /// The retry this happened in. Starts at 1. This is 0 for logins before
+    /// the first attempt to send a command
+    pub attempt: u32,
+    /// The error, that caused this. `None` for `Relogin`
+    pub error: CErrorKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RecoveryHook {
+    callback: extern "C" fn(event: CRecoveryEvent, user_data: *mut std::ffi::c_void),
+    user_data: usize,
+}
+
+/// The recovery policy new sessions start with
 #[no_mangle]
-pub extern "C" fn response_get_value(response: *mut Response, key: *const i8) -> *mut i8 {
-    if response.is_null() || key.is_null() {
-        return ptr::null_mut();
+pub extern "C" fn default_recovery_policy() -> CRecoveryPolicy {
+    CRecoveryPolicy::default()
+}
+
+/// The recovery policy of the session. If the session is null, or busy, the
+/// default policy is returned
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`
+#[no_mangle]
+pub unsafe extern "C" fn session_recovery_policy(session: *const GameSession) -> CRecoveryPolicy {
+    idle_session(session).map(|a| a.recovery).unwrap_or_default()
+}
+
+/// Changes how the session recovers from failed commands. Returns false, if
+/// the session is null, or busy
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn session_set_recovery_policy(session: *mut GameSession, policy: CRecoveryPolicy) -> bool {
+    let Some(session) = idle_session_mut(session) else {
+        return false;
+    };
+    session.recovery = policy;
+    true
+}
+
+/// Sets the callback, that is called with everything the recovery of the
+/// session does. Passing null removes the callback. The callback is called
+/// on the thread, that runs the command, which is a thread of the runtime for
+/// background commands. Returns false, if the session is null, or busy
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn session_set_recovery_callback(
+    session: *mut GameSession,
+    callback: Option<extern "C" fn(event: CRecoveryEvent, user_data: *mut std::ffi::c_void)>,
+    user_data: *mut std::ffi::c_void,
+) -> bool {
+    let Some(session) = idle_session_mut(session) else {
+        return false;
+    };
+    session.recovery_hook = callback.map(|callback| RecoveryHook { callback, user_data: user_data as usize });
+    true
+}
+
+
+// ##################################################
+// #                 TRANSPORT                      #
+// ##################################################
+
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `path` has to be null, or a valid, nul terminated string
+#[cfg(feature = "session")]
+unsafe fn set_transport(
+    session: *mut GameSession,
+    path: *const i8,
+    transport: impl FnOnce(&Session, &str) -> Result<Arc<dyn Transport>, SFError>,
+) -> bool {
+    let Some(path) = from_c_str(path) else {
+        invalid_argument::<GameSession>();
+        return false;
+    };
+    let Some(session) = idle_session_mut(session) else {
+        return false;
+    };
+    match transport(&session.session, path) {
+        Ok(transport) => {
+            clear_last_error();
+            session.session.set_transport(transport);
+            true
+        }
+        Err(e) => {
+            set_last_error(&e);
+            false
+        }
     }
+}
 
-    let key_cstr = unsafe { CStr::from_ptr(key) };
-    let key_str = key_cstr.to_str().unwrap_or("");
+/// Writes every command the session sends, together with the response of the
+/// server, into the cassette file at `path`. New entries are appended, if the
+/// file already exists. Returns false, if the file could not be opened
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `path` has to be null, or a valid, nul terminated string
+#[cfg(feature = "session")]
+#[no_mangle]
+pub unsafe extern "C" fn session_record_cassette(session: *mut GameSession, path: *const i8) -> bool {
+    set_transport(session, path, |session, path| {
+        let inner = session.transport().clone();
+        Ok(Arc::new(RecordingTransport::new(inner, path)?))
+    })
+}
 
-    let response = unsafe { &*response };
+/// Makes the session answer all commands with the responses from the
+/// cassette file at `path`, instead of talking to the server. Returns false,
+/// if the cassette could not be read
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `path` has to be null, or a valid, nul terminated string
+#[cfg(feature = "session")]
+#[no_mangle]
+pub unsafe extern "C" fn session_replay_cassette(session: *mut GameSession, path: *const i8) -> bool {
+    set_transport(session, path, |_, path| Ok(Arc::new(ReplayTransport::load(path)?)))
+}
+
+
+// ###################################################
+// #                 RATE LIMIT                      #
+// ###################################################
+
+#[cfg(feature = "session")]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The amount of requests, that can be sent in some time frame
+pub struct CRateBudget {
+    /// The amount of requests, that can be sent per `interval_ms` on average.
+    /// 0 disables the budget
+    pub requests: u32,
+    pub interval_ms: u64,
+    /// The amount of requests, that can be sent right after each other, after
+    /// nothing has been sent for a while
+    pub burst: u32,
+}
+
+#[cfg(feature = "session")]
+impl From<Option<RateBudget>> for CRateBudget {
+    fn from(value: Option<RateBudget>) -> Self {
+        value.map_or_else(CRateBudget::default, |a| CRateBudget {
+            requests: a.requests,
+            interval_ms: a.interval.as_millis().try_into().unwrap_or(u64::MAX),
+            burst: a.burst,
+        })
+    }
+}
 
-    if let Some(value) = response.values().get(key_str) {
-        let c_string = CString::new(value.as_str()).unwrap();
-        return c_string.into_raw();  // Now returning a mutable pointer
+#[cfg(feature = "session")]
+impl From<CRateBudget> for Option<RateBudget> {
+    fn from(value: CRateBudget) -> Self {
+        (value.requests > 0 && value.interval_ms > 0).then(|| RateBudget {
+            requests: value.requests,
+            interval: Duration::from_millis(value.interval_ms),
+            burst: value.burst,
+        })
     }
-    ptr::null_mut()
 }
 
-/// Frees a C string allocated by `sf_response_get_value`
+#[cfg(feature = "session")]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Decides how fast the sessions of a server connection send their requests
+pub struct CRateLimit {
+    /// The budget of every single session
+    pub per_session: CRateBudget,
+    /// The budget shared by all sessions of the same server connection
+    pub per_server: CRateBudget,
+    /// A random delay between 0 and this is added before every request
+    pub jitter_ms: u64,
+}
+
+#[cfg(feature = "session")]
+impl From<&RateLimitOptions> for CRateLimit {
+    fn from(value: &RateLimitOptions) -> Self {
+        CRateLimit {
+            per_session: value.per_session.into(),
+            per_server: value.per_server.into(),
+            jitter_ms: value.jitter.as_millis().try_into().unwrap_or(u64::MAX),
+        }
+    }
+}
+
+#[cfg(feature = "session")]
+impl From<CRateLimit> for RateLimitOptions {
+    fn from(value: CRateLimit) -> Self {
+        RateLimitOptions {
+            per_session: value.per_session.into(),
+            per_server: value.per_server.into(),
+            jitter: Duration::from_millis(value.jitter_ms),
+        }
+    }
+}
+
+/// No limits at all. This is what `init_session` uses
+#[cfg(feature = "session")]
 #[no_mangle]
-pub extern "C" fn destr_response_value(value: *mut i8) {
-    if value.is_null() {
+pub extern "C" fn default_rate_limit() -> CRateLimit {
+    (&RateLimitOptions::default()).into()
+}
+
+/// Limits, that should look like a human clicking through the game. Use
+/// these for anything, that sends thousands of requests, like crawling the
+/// Hall of Fame
+#[cfg(feature = "session")]
+#[no_mangle]
+pub extern "C" fn conservative_rate_limit() -> CRateLimit {
+    (&RateLimitOptions::conservative()).into()
+}
+
+/// Creates a connection to a server, that all sessions created from it with
+/// `init_session_on` share. They all count against the same `per_server`
+/// budget. Returns null, if the url is invalid
+///
+/// # Safety
+/// `server_url` has to be null, or a valid, nul terminated string
+#[cfg(feature = "session")]
+#[no_mangle]
+pub unsafe extern "C" fn init_server_connection(server_url: *const i8, limit: CRateLimit) -> *mut ServerConnection {
+    let Some(server_url) = from_c_str(server_url) else {
+        return invalid_argument();
+    };
+    let options = ConnectionOptions { rate_limit: limit.into(), ..Default::default() };
+    match ServerConnection::new_with_options(server_url, options) {
+        Some(connection) => Box::into_raw(Box::new(connection)),
+        None => invalid_argument(),
+    }
+}
+
+/// Frees a server connection. Sessions created from it stay valid
+///
+/// # Safety
+/// `connection` has to be null, or a pointer from this library, that has not been freed yet
+#[cfg(feature = "session")]
+#[no_mangle]
+pub unsafe extern "C" fn destr_server_connection(connection: *mut ServerConnection) {
+    if !connection.is_null() {
+        unsafe { drop(Box::from_raw(connection)) };
+    }
+}
+
+/// Creates a new session on the server connection. Just like with
+/// `init_session`, you have to call `login` before using it
+///
+/// # Safety
+/// - `connection` has to be null, or a valid `ServerConnection`
+/// - `username` has to be null, or a valid, nul terminated string
+/// - `password` has to be null, or a valid, nul terminated string
+#[cfg(feature = "session")]
+#[no_mangle]
+pub unsafe extern "C" fn init_session_on(
+    connection: *const ServerConnection,
+    username: *const i8,
+    password: *const i8,
+) -> *mut GameSession {
+    let (Some(connection), Some(user), Some(pass)) = (ffi_ref(connection), from_c_str(username), from_c_str(password))
+    else {
+        return invalid_argument();
+    };
+    let session = Session::new(user, pass, connection.clone());
+    Box::into_raw(Box::new(GameSession::new(session)))
+}
+
+
+// ######################################################
+// #                 ASYNC COMMANDS                     #
+// ######################################################
+
+/// Called once a background command has finished. This is called from one of
+/// the threads of the runtime, not the thread, that started the command, so
+/// no blocking `exec_*` calls can be made from inside of it
+type TicketCallback = extern "C" fn(user_data: *mut std::ffi::c_void);
+
+/// The handle to a command, that runs in the background. Has to be freed with
+/// `destr_ticket`
+#[derive(Debug)]
+pub struct CommandTicket {
+    state: Arc<TicketState>,
+}
+
+#[derive(Debug, Default)]
+struct TicketState {
+    /// The result of the command. This is only `None`, until the command has
+    /// finished, or after the result has been taken
+    result: Mutex<Option<Result<Response, SFError>>>,
+    finished: AtomicBool,
+    done: Condvar,
+}
+
+/// The things C++ has requested with `begin_async_command`. This is taken by
+/// the next command, so it can never start more than one command
+#[derive(Clone, Copy)]
+struct AsyncRequest {
+    callback: Option<TicketCallback>,
+    user_data: usize,
+}
+
+thread_local! {
+    static ASYNC_REQUEST: std::cell::RefCell<Option<AsyncRequest>> =
+        const { std::cell::RefCell::new(None) };
+    /// The handle to the command started by the last request, until it is
+    /// taken with `end_async_command`
+    static ASYNC_TICKET: std::cell::RefCell<Option<Box<CommandTicket>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// The session pointer has to be moved onto the runtime. C++ has promised us
+/// not to touch, or free the session, while it is busy
+struct SessionPtr(*mut GameSession);
+unsafe impl Send for SessionPtr {}
+
+/// # Safety
+/// `session` has to be a valid `GameSession`, that is neither used, nor freed anywhere else, until the command has finished
+unsafe fn start_async_command(session: *mut GameSession, command: Command, request: AsyncRequest) {
+    let AsyncRequest { callback, user_data } = request;
+    let Some(runtime) = runtime() else {
+        return;
+    };
+    if busy_flag(session)
+        .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        session_busy::<Response>();
         return;
     }
-    unsafe {
-        drop(CString::from_raw(value)); // Reclaim and drop the CString
+
+    let state = Arc::new(TicketState::default());
+    let task_state = state.clone();
+    let session = SessionPtr(session);
+    runtime.spawn(async move {
+        let session = session;
+        // Nothing else borrows the state, until the busy flag is cleared
+        let state = unsafe { &mut *(*session.0).state.get() };
+        let result = state.send_command_recovering(command).await;
+        unsafe { busy_flag(session.0) }.store(false, Ordering::Release);
+
+        {
+            let mut guard = task_state.result.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+            *guard = Some(result);
+            task_state.finished.store(true, Ordering::Release);
+        }
+        task_state.done.notify_all();
+        if let Some(callback) = callback {
+            callback(user_data as *mut std::ffi::c_void);
+        }
+    });
+
+    ASYNC_TICKET.set(Some(Box::new(CommandTicket { state })));
+}
+
+/// Makes the next `exec_*` call on this thread start the command in the
+/// background instead of waiting for it. That call returns null and the
+/// handle to the command has to be taken with `end_async_command`. The
+/// optional callback is invoked with `user_data`, once the command has
+/// finished. The session must not be used, or freed, until then. Anything
+/// left over from an earlier `begin_async_command`, that was never ended, is
+/// discarded
+#[no_mangle]
+pub extern "C" fn begin_async_command(callback: Option<extern "C" fn(user_data: *mut std::ffi::c_void)>, user_data: *mut std::ffi::c_void) {
+    clear_last_error();
+    ASYNC_TICKET.take();
+    ASYNC_REQUEST.set(Some(AsyncRequest { callback, user_data: user_data as usize }));
+}
+
+/// Returns the handle to the command started since `begin_async_command`.
+/// This is null, if the command could not be started. In that case the
+/// reason can be retrieved with `take_last_error`
+#[no_mangle]
+pub extern "C" fn end_async_command() -> *mut CommandTicket {
+    // The request is still there, if no command has been called since
+    ASYNC_REQUEST.take();
+    ASYNC_TICKET.take().map_or(ptr::null_mut(), Box::into_raw)
+}
+
+/// Checks if the command has finished without blocking
+///
+/// # Safety
+/// `ticket` has to be null, or a valid `CommandTicket`
+#[no_mangle]
+pub unsafe extern "C" fn ticket_poll(ticket: *const CommandTicket) -> bool {
+    ffi_ref(ticket).is_some_and(|a| a.state.finished.load(Ordering::Acquire))
+}
+
+/// Blocks, until the command has finished, or `timeout_ms` have passed.
+/// Returns true, if the command has finished
+///
+/// # Safety
+/// `ticket` has to be null, or a valid `CommandTicket`
+#[no_mangle]
+pub unsafe extern "C" fn ticket_wait_for(ticket: *const CommandTicket, timeout_ms: u64) -> bool {
+    let Some(ticket) = ffi_ref(ticket) else {
+        return false;
+    };
+    let state = &ticket.state;
+    let guard = state.result.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    let _guard = state
+        .done
+        .wait_timeout_while(guard, Duration::from_millis(timeout_ms), |_| {
+            !state.finished.load(Ordering::Acquire)
+        })
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    state.finished.load(Ordering::Acquire)
+}
+
+/// Blocks, until the command has finished and returns the response. This
+/// works just like the blocking `exec_*` call would have, so on failure this
+/// returns null and the error can be retrieved with `take_last_error`. The
+/// response can only be taken once
+///
+/// # Safety
+/// `ticket` has to be null, or a valid `CommandTicket`
+#[no_mangle]
+pub unsafe extern "C" fn ticket_wait(ticket: *const CommandTicket) -> *mut Response {
+    let Some(ticket) = ffi_ref(ticket) else {
+        return invalid_argument();
+    };
+    let state = &ticket.state;
+    let guard = state.result.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    let mut guard = state
+        .done
+        .wait_while(guard, |_| !state.finished.load(Ordering::Acquire))
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match guard.take() {
+        Some(Ok(response)) => {
+            clear_last_error();
+            Box::into_raw(Box::new(response))
+        }
+        Some(Err(e)) => {
+            set_last_error(&e);
+            ptr::null_mut()
+        }
+        None => invalid_argument(),
+    }
+}
+
+/// Frees the handle to a command. The command itself keeps running, if it
+/// has not yet finished
+///
+/// # Safety
+/// `ticket` has to be null, or a pointer from this library, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_ticket(ticket: *mut CommandTicket) {
+    if !ticket.is_null() {
+        unsafe { drop(Box::from_raw(ticket)) };
+    }
+}
+
+
+// ##########################################
+// #                 SSO                    #
+// ##########################################
+
+/// All the characters of an SSO account. Has to be freed with
+/// `destr_sso_characters`
+#[cfg(feature = "sso")]
+#[derive(Debug)]
+pub struct SSOCharacters {
+    /// The sessions of the characters. `None`, once the session has been
+    /// taken with `sso_characters_take_session`
+    sessions: Vec<Option<Result<Session, SFError>>>,
+}
+
+/// Logs into an S&F SSO account. This is null, if the login failed. In that
+/// case the reason can be retrieved with `take_last_error`
+///
+/// # Safety
+/// - `username` has to be null, or a valid, nul terminated string
+/// - `password` has to be null, or a valid, nul terminated string
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn sso_account_login(username: *const i8, password: *const i8) -> *mut SFAccount {
+    let (Some(username), Some(password)) = (from_c_str(username), from_c_str(password)) else {
+        return invalid_argument();
+    };
+    clear_last_error();
+    let Some(runtime) = runtime() else {
+        return ptr::null_mut();
+    };
+    match runtime.block_on(SFAccount::login(username.to_string(), password.to_string())) {
+        Ok(account) => Box::into_raw(Box::new(account)),
+        Err(e) => {
+            set_last_error(&e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// `account` has to be null, or a pointer from this library, that has not been freed yet
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn destr_sso_account(account: *mut SFAccount) {
+    if !account.is_null() {
+        unsafe { drop(Box::from_raw(account)) };
+    }
+}
+
+/// Downloads the current list of servers. This is null, if that failed
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub extern "C" fn server_lookup_fetch() -> *mut ServerLookup {
+    clear_last_error();
+    let Some(runtime) = runtime() else {
+        return ptr::null_mut();
+    };
+    match runtime.block_on(ServerLookup::fetch()) {
+        Ok(lookup) => Box::into_raw(Box::new(lookup)),
+        Err(e) => {
+            set_last_error(&e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Reads a server list from a local JSON file, that has the same format as
+/// the one `server_lookup_fetch` downloads. This is null, if that failed
+///
+/// # Safety
+/// `path` has to be null, or a valid, nul terminated string
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn server_lookup_load(path: *const i8) -> *mut ServerLookup {
+    let Some(path) = from_c_str(path) else {
+        return invalid_argument();
+    };
+    clear_last_error();
+    match ServerLookup::from_file(path) {
+        Ok(lookup) => Box::into_raw(Box::new(lookup)),
+        Err(e) => {
+            set_last_error(&e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// `lookup` has to be null, or a pointer from this library, that has not been freed yet
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn destr_server_lookup(lookup: *mut ServerLookup) {
+    if !lookup.is_null() {
+        unsafe { drop(Box::from_raw(lookup)) };
+    }
+}
+
+/// Fetches all the characters of the account. This consumes the account, so
+/// it must not be used, or freed afterwards, even if this fails. All the
+/// character sessions share the account to renew their credentials. If
+/// `lookup` is null, the current server list is fetched
+///
+/// # Safety
+/// - `account` has to be null, or an account from `sso_account_login`, that has not been freed yet
+/// - `lookup` has to be null, or a valid `ServerLookup`
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn sso_account_characters(account: *mut SFAccount, lookup: *const ServerLookup) -> *mut SSOCharacters {
+    if account.is_null() {
+        return invalid_argument();
+    }
+    let account = unsafe { Box::from_raw(account) };
+    clear_last_error();
+    let Some(runtime) = runtime() else {
+        return ptr::null_mut();
+    };
+    let characters = runtime.block_on(async move {
+        match ffi_ref(lookup) {
+            Some(lookup) => account.characters_with_lookup(lookup).await,
+            None => account.characters().await,
+        }
+    });
+    match characters {
+        Ok(sessions) => Box::into_raw(Box::new(SSOCharacters {
+            sessions: sessions.into_iter().map(Some).collect(),
+        })),
+        Err(e) => {
+            set_last_error(&e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// `characters` has to be null, or a valid `SSOCharacters`
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn sso_characters_len(characters: *const SSOCharacters) -> usize {
+    ffi_ref(characters).map(|a| a.sessions.len()).unwrap_or_default()
+}
+
+#[cfg(feature = "sso")]
+impl SSOCharacters {
+    /// The session of the character at `pos`, if it exists and has not been
+    /// taken yet
+    fn character(&self, pos: usize) -> Option<&Session> {
+        match self.sessions.get(pos)? {
+            Some(Ok(session)) => Some(session),
+            _ => None,
+        }
+    }
+}
+
+/// The name of a character. This is null, if the server of the character
+/// could not be found, or the session has already been taken
+///
+/// # Safety
+/// `characters` has to be null, or a valid `SSOCharacters`
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn sso_characters_name(characters: *const SSOCharacters, pos: usize) -> *mut i8 {
+    ffi_ref(characters)
+        .and_then(|a| a.character(pos))
+        .map_or(ptr::null_mut(), |a| to_c_string(a.username()))
+}
+
+/// The url of the server the character is on. This is null, if the server of
+/// the character could not be found, or the session has already been taken
+///
+/// # Safety
+/// `characters` has to be null, or a valid `SSOCharacters`
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn sso_characters_server_url(characters: *const SSOCharacters, pos: usize) -> *mut i8 {
+    ffi_ref(characters)
+        .and_then(|a| a.character(pos))
+        .map_or(ptr::null_mut(), |a| to_c_string(a.server_url().as_str()))
+}
+
+/// Takes the session of a character out of the list. It still has to be
+/// logged in with `login`. This is null, if the server of the character could
+/// not be found, or the session has already been taken
+///
+/// # Safety
+/// `characters` has to be null, or a valid `SSOCharacters`, that is not used anywhere else during this call
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn sso_characters_take_session(characters: *mut SSOCharacters, pos: usize) -> *mut GameSession {
+    let Some(slot) = unsafe { characters.as_mut() }.and_then(|a| a.sessions.get_mut(pos)) else {
+        return invalid_argument();
+    };
+    match slot.take() {
+        Some(Ok(session)) => {
+            clear_last_error();
+            Box::into_raw(Box::new(GameSession::new(session)))
+        }
+        Some(Err(e)) => {
+            set_last_error(&e);
+            ptr::null_mut()
+        }
+        None => invalid_argument(),
+    }
+}
+
+/// # Safety
+/// `characters` has to be null, or a pointer from this library, that has not been freed yet
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn destr_sso_characters(characters: *mut SSOCharacters) {
+    if !characters.is_null() {
+        unsafe { drop(Box::from_raw(characters)) };
+    }
+}
+
+/// Gets new SSO credentials from the account this session belongs to. All
+/// sessions of the same account share this, so the account is only logged in
+/// again, if no other character has done so already
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[cfg(feature = "sso")]
+#[no_mangle]
+pub unsafe extern "C" fn session_renew_sso_creds(session: *mut GameSession) -> bool {
+    let Some(session) = idle_session_mut(session) else {
+        return false;
+    };
+    clear_last_error();
+    let Some(runtime) = runtime() else {
+        return false;
+    };
+    match runtime.block_on(session.session.renew_sso_creds()) {
+        Ok(()) => true,
+        Err(e) => {
+            set_last_error(&e);
+            false
+        }
+    }
+}
+
+
+// #######################################################
+// #                 RESPONSE FREEING                    #
+// #######################################################
+
+/// Frees a response object
+///
+/// # Safety
+/// `response` has to be null, or a pointer from this library, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_response(response: *mut Response) {
+    if !response.is_null() {
+        unsafe { drop(Box::from_raw(response)) };
+    }
+}
+
+
+// #####################################################
+// #                 RESPONSE VALUE                    #
+// #####################################################
+
+/// Retrieves a value from a parsed response
+///
+/// # Safety
+/// - `response` has to be null, or a valid `Response`
+/// - `key` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn response_get_value(response: *mut Response, key: *const i8) -> *mut i8 {
+    if response.is_null() || key.is_null() {
+        return ptr::null_mut();
+    }
+
+    let key_cstr = unsafe { CStr::from_ptr(key) };
+    let key_str = key_cstr.to_str().unwrap_or("");
+
+    let response = unsafe { &*response };
+
+    response.values().get(key_str).map_or(ptr::null_mut(), |value| to_c_string(value.as_str()))
+}
+
+/// Frees a C string allocated by `sf_response_get_value`
+///
+/// # Safety
+/// `value` has to be null, or a string from this library, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_response_value(value: *mut i8) {
+    if value.is_null() {
+        return;
+    }
+    unsafe {
+        drop(CString::from_raw(value)); // Reclaim and drop the CString
+    }
+}
+
+
+// ####################################################
+// #                 RESPONSE KEYS                    #
+// ####################################################
+
+/// Retrieves the keys from a parsed response
+///
+/// # Safety
+/// - `response` has to be null, or a valid `Response`
+/// - `out_len` has to be null, or point to memory, that a `usize` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn response_get_keys(response: *mut Response, out_len: *mut usize) -> *mut *const i8 {
+    if response.is_null() || out_len.is_null() {
+        return ptr::null_mut();
+    }
+
+    let response = unsafe { &*response };
+    let keys: Vec<CString> = response.values().keys().filter_map(|&key| CString::new(key).ok()).collect();
+    
+    // Convert CString to *const i8 (C-compatible)
+    let c_keys: Box<[*const i8]> = keys.iter().map(|s| s.as_ptr()).collect();
+    
+    // Store length in out_len
+    unsafe { *out_len = c_keys.len(); }
+
+    // Leak memory (C++ must call sf_response_free_keys() later)
+    std::mem::forget(keys);
+    Box::into_raw(c_keys).cast()
+}
+
+/// Frees the memory allocated by sf_response_get_keys
+///
+/// # Safety
+/// `keys` has to be null, or the keys from `response_get_keys`, that have not
+/// been freed yet. `len` has to be the length `response_get_keys` has written
+/// to its `out_len`
+#[no_mangle]
+pub unsafe extern "C" fn destr_response_keys(keys: *mut *const i8, len: usize) {
+    if keys.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(keys, len))); // Reclaim ownership, drop it
+    }
+}
+
+
+// #################################################
+// #                 GAME STATE                    #
+// #################################################
+
+/// Returns the game state of the session. This is null, if the session has not
+/// been logged in yet, or is busy with a background command. The returned
+/// pointer, as well as all pointers derived from it, are only valid until the
+/// next command is sent, or started in the background, using this session
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`
+#[no_mangle]
+pub unsafe extern "C" fn session_game_state(session: *const GameSession) -> *const GameState {
+    idle_session(session)
+        .and_then(|a| a.gamestate.as_ref())
+        .map_or(ptr::null(), ptr::from_ref)
+}
+
+/// Returns the character of the game state
+///
+/// # Safety
+/// `gs` has to be null, or a valid `GameState`
+#[no_mangle]
+pub unsafe extern "C" fn game_state_character(gs: *const GameState) -> *const Character {
+    ffi_ref(gs).map_or(ptr::null(), |a| ptr::from_ref(&a.character))
+}
+
+/// Returns the tavern of the game state
+///
+/// # Safety
+/// `gs` has to be null, or a valid `GameState`
+#[no_mangle]
+pub unsafe extern "C" fn game_state_tavern(gs: *const GameState) -> *const Tavern {
+    ffi_ref(gs).map_or(ptr::null(), |a| ptr::from_ref(&a.tavern))
+}
+
+/// Returns the arena of the game state
+///
+/// # Safety
+/// `gs` has to be null, or a valid `GameState`
+#[no_mangle]
+pub unsafe extern "C" fn game_state_arena(gs: *const GameState) -> *const Arena {
+    ffi_ref(gs).map_or(ptr::null(), |a| ptr::from_ref(&a.arena))
+}
+
+/// Returns the guild of the game state. This is null, if the character is not
+/// in a guild
+///
+/// # Safety
+/// `gs` has to be null, or a valid `GameState`
+#[no_mangle]
+pub unsafe extern "C" fn game_state_guild(gs: *const GameState) -> *const Guild {
+    ffi_ref(gs)
+        .and_then(|a| a.guild.as_ref())
+        .map_or(ptr::null(), ptr::from_ref)
+}
+
+/// Returns the fortress of the game state. This is null, if the fortress has
+/// not been unlocked yet
+///
+/// # Safety
+/// `gs` has to be null, or a valid `GameState`
+#[no_mangle]
+pub unsafe extern "C" fn game_state_fortress(gs: *const GameState) -> *const Fortress {
+    ffi_ref(gs)
+        .and_then(|a| a.fortress.as_ref())
+        .map_or(ptr::null(), ptr::from_ref)
+}
+
+/// Returns another player, that has previously been looked at with
+/// `exec_ViewPlayer`. This is null, if the player has not been looked at
+///
+/// # Safety
+/// `gs` has to be null, or a valid `GameState`
+#[no_mangle]
+pub unsafe extern "C" fn game_state_lookup_pid(gs: *const GameState, player_id: u32) -> *const OtherPlayer {
+    ffi_ref(gs)
+        .and_then(|a| a.lookup.lookup_pid(player_id))
+        .map_or(ptr::null(), ptr::from_ref)
+}
+
+/// Returns another player, that has previously been looked at with
+/// `exec_ViewPlayer`. This is null, if the player has not been looked at
+///
+/// # Safety
+/// - `gs` has to be null, or a valid `GameState`
+/// - `name` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn game_state_lookup_name(gs: *const GameState, name: *const i8) -> *const OtherPlayer {
+    let Some(name) = from_c_str(name) else {
+        return ptr::null();
+    };
+    ffi_ref(gs)
+        .and_then(|a| a.lookup.lookup_name(name))
+        .map_or(ptr::null(), ptr::from_ref)
+}
+
+/// Frees a C string returned by one of the game state accessors
+///
+/// # Safety
+/// `value` has to be null, or a string from this library, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_string(value: *mut i8) {
+    if value.is_null() {
+        return;
+    }
+    unsafe {
+        drop(CString::from_raw(value));
+    }
+}
+
+
+// ###########################################
+// #                 JSON                    #
+// ###########################################
+
+#[cfg(all(feature = "serde", feature = "session"))]
+fn to_json_c_string(value: &impl serde::Serialize) -> *mut i8 {
+    match serde_json::to_string(value) {
+        Ok(json) => {
+            clear_last_error();
+            to_c_string(&json)
+        }
+        Err(e) => {
+            set_last_error(&SFError::ParsingError("json", e.to_string()));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Serializes the whole game state into a JSON string, that has to be freed
+/// with `destr_string`
+///
+/// # Safety
+/// `gs` has to be null, or a valid `GameState`
+#[cfg(all(feature = "serde", feature = "session"))]
+#[no_mangle]
+pub unsafe extern "C" fn game_state_to_json(gs: *const GameState) -> *mut i8 {
+    match ffi_ref(gs) {
+        Some(gs) => to_json_c_string(gs),
+        None => invalid_argument(),
+    }
+}
+
+/// Serializes one field of the game state, like `character`, `fortress`,
+/// `guild`, `hall_of_fames`, or `lookup`, into a JSON string, that has to be
+/// freed with `destr_string`. Fields, that are not available yet, like the
+/// guild of a character without one, are serialized as `null`. Returns null,
+/// if there is no field with this name
+///
+/// # Safety
+/// - `gs` has to be null, or a valid `GameState`
+/// - `name` has to be null, or a valid, nul terminated string
+#[cfg(all(feature = "serde", feature = "session"))]
+#[no_mangle]
+pub unsafe extern "C" fn game_state_subtree_to_json(gs: *const GameState, name: *const i8) -> *mut i8 {
+    let (Some(gs), Some(name)) = (ffi_ref(gs), from_c_str(name)) else {
+        return invalid_argument();
+    };
+    match name {
+        "character" => to_json_c_string(&gs.character),
+        "fortress" => to_json_c_string(&gs.fortress),
+        "guild" => to_json_c_string(&gs.guild),
+        "hall_of_fames" => to_json_c_string(&gs.hall_of_fames),
+        "lookup" => to_json_c_string(&gs.lookup),
+        "tavern" => to_json_c_string(&gs.tavern),
+        "arena" => to_json_c_string(&gs.arena),
+        "dungeons" => to_json_c_string(&gs.dungeons),
+        "underworld" => to_json_c_string(&gs.underworld),
+        "pets" => to_json_c_string(&gs.pets),
+        "idle_game" => to_json_c_string(&gs.idle_game),
+        _ => invalid_argument(),
+    }
+}
+
+/// Serializes a response into a JSON string, that has to be freed with
+/// `destr_string`. Saving the response of a login lets you build the game
+/// state again later with `game_state_from_response_json`
+///
+/// # Safety
+/// `response` has to be null, or a valid `Response`
+#[cfg(all(feature = "serde", feature = "session"))]
+#[no_mangle]
+pub unsafe extern "C" fn response_to_json(response: *const Response) -> *mut i8 {
+    match ffi_ref(response) {
+        Some(response) => to_json_c_string(response),
+        None => invalid_argument(),
+    }
+}
+
+/// Builds a game state from a login response, that has been saved with
+/// `response_to_json`. The game state has to be freed with
+/// `destr_game_state`. If this fails, the reason can be retrieved with
+/// `take_last_error`
+///
+/// # Safety
+/// `json` has to be null, or a valid, nul terminated string
+#[cfg(all(feature = "serde", feature = "session"))]
+#[no_mangle]
+pub unsafe extern "C" fn game_state_from_response_json(json: *const i8) -> *mut GameState {
+    let Some(json) = from_c_str(json) else {
+        return invalid_argument();
+    };
+    let result = serde_json::from_str::<Response>(json)
+        .map_err(|e| SFError::ParsingError("response json", e.to_string()))
+        .and_then(GameState::new);
+    match result {
+        Ok(gs) => {
+            clear_last_error();
+            Box::into_raw(Box::new(gs))
+        }
+        Err(e) => {
+            set_last_error(&e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a game state returned by `game_state_from_response_json`. This must
+/// not be called with the game state of a session
+///
+/// # Safety
+/// `gs` has to be null, or a pointer from this library, that has not been freed yet
+#[cfg(all(feature = "serde", feature = "session"))]
+#[no_mangle]
+pub unsafe extern "C" fn destr_game_state(gs: *mut GameState) {
+    if !gs.is_null() {
+        unsafe { drop(Box::from_raw(gs)) };
+    }
+}
+
+
+// ################################################
+// #                 CHARACTER                    #
+// ################################################
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_player_id(character: *const Character) -> u32 {
+    ffi_ref(character).map(|a| a.player_id).unwrap_or_default()
+}
+
+/// Returns the name of the character. Has to be freed with `destr_string`
+///
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_name(character: *const Character) -> *mut i8 {
+    ffi_ref(character).map_or(ptr::null_mut(), |a| to_c_string(&a.name))
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_level(character: *const Character) -> u16 {
+    ffi_ref(character).map(|a| a.level).unwrap_or_default()
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_silver(character: *const Character) -> u64 {
+    ffi_ref(character).map(|a| a.silver).unwrap_or_default()
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_mushrooms(character: *const Character) -> u32 {
+    ffi_ref(character).map(|a| a.mushrooms).unwrap_or_default()
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_class(character: *const Character) -> u8 {
+    ffi_ref(character).map(|a| a.class as u8).unwrap_or_default()
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_race(character: *const Character) -> u8 {
+    ffi_ref(character).map(|a| a.race as u8).unwrap_or_default()
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_experience(character: *const Character) -> u64 {
+    ffi_ref(character).map(|a| a.experience).unwrap_or_default()
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_next_level_xp(character: *const Character) -> u64 {
+    ffi_ref(character).map(|a| a.next_level_xp).unwrap_or_default()
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_honor(character: *const Character) -> u32 {
+    ffi_ref(character).map(|a| a.honor).unwrap_or_default()
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_rank(character: *const Character) -> u32 {
+    ffi_ref(character).map(|a| a.rank).unwrap_or_default()
+}
+
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_armor(character: *const Character) -> u64 {
+    ffi_ref(character).map(|a| a.armor).unwrap_or_default()
+}
+
+/// Returns the scrapbook of the character. This is null, if the scrapbook has
+/// not been unlocked yet
+///
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_scrapbook(character: *const Character) -> *const ScrapBook {
+    ffi_ref(character)
+        .and_then(|a| a.scrapbook.as_ref())
+        .map_or(ptr::null(), ptr::from_ref)
+}
+
+/// Returns the base value of the attribute without any equipment, or other
+/// boosts
+///
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_attribute_basis(character: *const Character, attribute: u8) -> u32 {
+    let Some(attribute) = AttributeType::from_u8(attribute) else {
+        return 0;
+    };
+    ffi_ref(character).map(|a| *a.attribute_basis.get(attribute)).unwrap_or_default()
+}
+
+/// Returns the bonus value of the attribute from equipment, pets and potions
+///
+/// # Safety
+/// `character` has to be null, or a valid `Character`
+#[no_mangle]
+pub unsafe extern "C" fn character_attribute_additions(character: *const Character, attribute: u8) -> u32 {
+    let Some(attribute) = AttributeType::from_u8(attribute) else {
+        return 0;
+    };
+    ffi_ref(character).map(|a| *a.attribute_additions.get(attribute)).unwrap_or_default()
+}
+
+
+// #############################################
+// #                 TAVERN                    #
+// #############################################
+
+/// # Safety
+/// `tavern` has to be null, or a valid `Tavern`
+#[no_mangle]
+pub unsafe extern "C" fn tavern_thirst_for_adventure_sec(tavern: *const Tavern) -> u32 {
+    ffi_ref(tavern).map(|a| a.thirst_for_adventure_sec).unwrap_or_default()
+}
+
+/// # Safety
+/// `tavern` has to be null, or a valid `Tavern`
+#[no_mangle]
+pub unsafe extern "C" fn tavern_beer_drunk(tavern: *const Tavern) -> u8 {
+    ffi_ref(tavern).map(|a| a.beer_drunk).unwrap_or_default()
+}
+
+/// # Safety
+/// `tavern` has to be null, or a valid `Tavern`
+#[no_mangle]
+pub unsafe extern "C" fn tavern_quicksand_glasses(tavern: *const Tavern) -> u32 {
+    ffi_ref(tavern).map(|a| a.quicksand_glasses).unwrap_or_default()
+}
+
+/// # Safety
+/// `tavern` has to be null, or a valid `Tavern`
+#[no_mangle]
+pub unsafe extern "C" fn tavern_mushroom_skip_allowed(tavern: *const Tavern) -> bool {
+    ffi_ref(tavern).is_some_and(|a| a.mushroom_skip_allowed)
+}
+
+/// Returns one of the three quests (0-2) in the tavern. This is null, if the
+/// position is out of range
+///
+/// # Safety
+/// `tavern` has to be null, or a valid `Tavern`
+#[no_mangle]
+pub unsafe extern "C" fn tavern_quest(tavern: *const Tavern, quest_pos: usize) -> *const Quest {
+    ffi_ref(tavern)
+        .and_then(|a| a.quests.get(quest_pos))
+        .map_or(ptr::null(), ptr::from_ref)
+}
+
+/// # Safety
+/// `quest` has to be null, or a valid `Quest`
+#[no_mangle]
+pub unsafe extern "C" fn quest_base_length(quest: *const Quest) -> u32 {
+    ffi_ref(quest).map(|a| a.base_length).unwrap_or_default()
+}
+
+/// # Safety
+/// `quest` has to be null, or a valid `Quest`
+#[no_mangle]
+pub unsafe extern "C" fn quest_base_silver(quest: *const Quest) -> u32 {
+    ffi_ref(quest).map(|a| a.base_silver).unwrap_or_default()
+}
+
+/// # Safety
+/// `quest` has to be null, or a valid `Quest`
+#[no_mangle]
+pub unsafe extern "C" fn quest_base_experience(quest: *const Quest) -> u32 {
+    ffi_ref(quest).map(|a| a.base_experience).unwrap_or_default()
+}
+
+/// # Safety
+/// `quest` has to be null, or a valid `Quest`
+#[no_mangle]
+pub unsafe extern "C" fn quest_location(quest: *const Quest) -> u8 {
+    ffi_ref(quest).map(|a| a.location_id as u8).unwrap_or_default()
+}
+
+/// # Safety
+/// `quest` has to be null, or a valid `Quest`
+#[no_mangle]
+pub unsafe extern "C" fn quest_monster_id(quest: *const Quest) -> u16 {
+    ffi_ref(quest).map(|a| a.monster_id).unwrap_or_default()
+}
+
+/// # Safety
+/// `quest` has to be null, or a valid `Quest`
+#[no_mangle]
+pub unsafe extern "C" fn quest_has_item(quest: *const Quest) -> bool {
+    ffi_ref(quest).is_some_and(|a| a.item.is_some())
+}
+
+
+// ############################################
+// #                 ARENA                    #
+// ############################################
+
+/// Returns the player id of one of the three (0-2) arena enemies. This is 0,
+/// if the position is out of range
+///
+/// # Safety
+/// `arena` has to be null, or a valid `Arena`
+#[no_mangle]
+pub unsafe extern "C" fn arena_enemy_id(arena: *const Arena, pos: usize) -> u32 {
+    ffi_ref(arena)
+        .and_then(|a| a.enemy_ids.get(pos).copied())
+        .unwrap_or_default()
+}
+
+/// Returns the unix timestamp, at which the next free fight is possible. This
+/// is 0, if there is no such time
+///
+/// # Safety
+/// `arena` has to be null, or a valid `Arena`
+#[no_mangle]
+pub unsafe extern "C" fn arena_next_free_fight(arena: *const Arena) -> i64 {
+    ffi_ref(arena).map(|a| to_timestamp(a.next_free_fight)).unwrap_or_default()
+}
+
+/// # Safety
+/// `arena` has to be null, or a valid `Arena`
+#[no_mangle]
+pub unsafe extern "C" fn arena_fights_for_xp(arena: *const Arena) -> u8 {
+    ffi_ref(arena).map(|a| a.fights_for_xp).unwrap_or_default()
+}
+
+
+// ############################################
+// #                 GUILD                    #
+// ############################################
+
+/// # Safety
+/// `guild` has to be null, or a valid `Guild`
+#[no_mangle]
+pub unsafe extern "C" fn guild_id(guild: *const Guild) -> u32 {
+    ffi_ref(guild).map(|a| a.id).unwrap_or_default()
+}
+
+/// Returns the name of the guild. Has to be freed with `destr_string`
+///
+/// # Safety
+/// `guild` has to be null, or a valid `Guild`
+#[no_mangle]
+pub unsafe extern "C" fn guild_name(guild: *const Guild) -> *mut i8 {
+    ffi_ref(guild).map_or(ptr::null_mut(), |a| to_c_string(&a.name))
+}
+
+/// # Safety
+/// `guild` has to be null, or a valid `Guild`
+#[no_mangle]
+pub unsafe extern "C" fn guild_honor(guild: *const Guild) -> u32 {
+    ffi_ref(guild).map(|a| a.honor).unwrap_or_default()
+}
+
+/// # Safety
+/// `guild` has to be null, or a valid `Guild`
+#[no_mangle]
+pub unsafe extern "C" fn guild_rank(guild: *const Guild) -> u32 {
+    ffi_ref(guild).map(|a| a.rank).unwrap_or_default()
+}
+
+/// # Safety
+/// `guild` has to be null, or a valid `Guild`
+#[no_mangle]
+pub unsafe extern "C" fn guild_member_count(guild: *const Guild) -> usize {
+    ffi_ref(guild).map(|a| a.members.len()).unwrap_or_default()
+}
+
+/// # Safety
+/// `guild` has to be null, or a valid `Guild`
+#[no_mangle]
+pub unsafe extern "C" fn guild_finished_raids(guild: *const Guild) -> u16 {
+    ffi_ref(guild).map(|a| a.finished_raids).unwrap_or_default()
+}
+
+
+// ###############################################
+// #                 FORTRESS                    #
+// ###############################################
+
+/// # Safety
+/// `fortress` has to be null, or a valid `Fortress`
+#[no_mangle]
+pub unsafe extern "C" fn fortress_building_level(fortress: *const Fortress, building: u8) -> u16 {
+    let Some(building) = FortressBuildingType::from_u8(building) else {
+        return 0;
+    };
+    ffi_ref(fortress).map(|a| a.buildings.get(building).level).unwrap_or_default()
+}
+
+/// # Safety
+/// `fortress` has to be null, or a valid `Fortress`
+#[no_mangle]
+pub unsafe extern "C" fn fortress_unit_count(fortress: *const Fortress, unit: u8) -> u16 {
+    let Some(unit) = FortressUnitType::from_u8(unit) else {
+        return 0;
+    };
+    ffi_ref(fortress).map(|a| a.units.get(unit).count).unwrap_or_default()
+}
+
+/// # Safety
+/// `fortress` has to be null, or a valid `Fortress`
+#[no_mangle]
+pub unsafe extern "C" fn fortress_resource_current(fortress: *const Fortress, resource: u8) -> u64 {
+    let Some(resource) = FortressResourceType::from_u8(resource) else {
+        return 0;
+    };
+    ffi_ref(fortress).map(|a| a.resources.get(resource).current).unwrap_or_default()
+}
+
+/// # Safety
+/// `fortress` has to be null, or a valid `Fortress`
+#[no_mangle]
+pub unsafe extern "C" fn fortress_resource_limit(fortress: *const Fortress, resource: u8) -> u64 {
+    let Some(resource) = FortressResourceType::from_u8(resource) else {
+        return 0;
+    };
+    ffi_ref(fortress).map(|a| a.resources.get(resource).limit).unwrap_or_default()
+}
+
+/// # Safety
+/// `fortress` has to be null, or a valid `Fortress`
+#[no_mangle]
+pub unsafe extern "C" fn fortress_upgrades(fortress: *const Fortress) -> u16 {
+    ffi_ref(fortress).map(|a| a.upgrades).unwrap_or_default()
+}
+
+/// # Safety
+/// `fortress` has to be null, or a valid `Fortress`
+#[no_mangle]
+pub unsafe extern "C" fn fortress_honor(fortress: *const Fortress) -> u32 {
+    ffi_ref(fortress).map(|a| a.honor).unwrap_or_default()
+}
+
+/// Returns the rank in the fortress Hall of Fame. This is 0, if there is none
+///
+/// # Safety
+/// `fortress` has to be null, or a valid `Fortress`
+#[no_mangle]
+pub unsafe extern "C" fn fortress_rank(fortress: *const Fortress) -> u32 {
+    ffi_ref(fortress).and_then(|a| a.rank).unwrap_or_default()
+}
+
+/// # Safety
+/// `fortress` has to be null, or a valid `Fortress`
+#[no_mangle]
+pub unsafe extern "C" fn fortress_wall_combat_lvl(fortress: *const Fortress) -> u16 {
+    ffi_ref(fortress).map(|a| a.wall_combat_lvl).unwrap_or_default()
+}
+
+
+// ###################################################
+// #                 OTHER PLAYER                    #
+// ###################################################
+
+/// # Safety
+/// `other` has to be null, or a valid `OtherPlayer`
+#[no_mangle]
+pub unsafe extern "C" fn other_player_id(other: *const OtherPlayer) -> u32 {
+    ffi_ref(other).map(|a| a.player_id).unwrap_or_default()
+}
+
+/// Returns the name of the player. Has to be freed with `destr_string`
+///
+/// # Safety
+/// `other` has to be null, or a valid `OtherPlayer`
+#[no_mangle]
+pub unsafe extern "C" fn other_player_name(other: *const OtherPlayer) -> *mut i8 {
+    ffi_ref(other).map_or(ptr::null_mut(), |a| to_c_string(&a.name))
+}
+
+/// # Safety
+/// `other` has to be null, or a valid `OtherPlayer`
+#[no_mangle]
+pub unsafe extern "C" fn other_player_level(other: *const OtherPlayer) -> u16 {
+    ffi_ref(other).map(|a| a.level).unwrap_or_default()
+}
+
+/// # Safety
+/// `other` has to be null, or a valid `OtherPlayer`
+#[no_mangle]
+pub unsafe extern "C" fn other_player_class(other: *const OtherPlayer) -> u8 {
+    ffi_ref(other).map(|a| a.class as u8).unwrap_or_default()
+}
+
+/// Simulates `iterations` fights of the own character against the other
+/// player and returns the share of fights won (0.0 - 1.0)
+///
+/// # Safety
+/// - `gs` has to be null, or a valid `GameState`
+/// - `other` has to be null, or a valid `OtherPlayer`
+#[no_mangle]
+pub unsafe extern "C" fn game_state_win_probability(
+    gs: *const GameState,
+    other: *const OtherPlayer,
+    iterations: u32,
+) -> f64 {
+    let (Some(gs), Some(other)) = (ffi_ref(gs), ffi_ref(other)) else {
+        return 0.0;
+    };
+    PlayerFighterSquad::new(gs)
+        .win_probability(&UpgradeableFighter::from_other(other), iterations)
+}
+
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct CEstimateOptions {
+    /// The amount of fights to simulate. The maximum, if `target_margin` is
+    /// set
+    pub samples: u32,
+    /// Stops once the win rate is known with this margin. 0 disables this
+    pub target_margin: f64,
+    /// 0 uses all available cores
+    pub threads: usize,
+    pub seed: u64,
+}
+
+impl From<CEstimateOptions> for EstimateOptions {
+    fn from(value: CEstimateOptions) -> Self {
+        EstimateOptions {
+            samples: value.samples,
+            target_margin: (value.target_margin > 0.0).then_some(value.target_margin),
+            threads: value.threads,
+            seed: value.seed,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct CDamageDistribution {
+    pub min: i64,
+    pub max: i64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl From<DamageDistribution> for CDamageDistribution {
+    fn from(value: DamageDistribution) -> Self {
+        CDamageDistribution { min: value.min, max: value.max, mean: value.mean, std_dev: value.std_dev }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// Everything is from the perspective of the own character
+pub struct CFightEstimate {
+    pub samples: u32,
+    pub wins: u32,
+    pub win_rate: f64,
+    /// The 95% confidence interval of `win_rate`
+    pub lower: f64,
+    pub upper: f64,
+    pub average_rounds: f64,
+    pub own_damage: CDamageDistribution,
+    pub other_damage: CDamageDistribution,
+}
+
+impl From<FightEstimate> for CFightEstimate {
+    fn from(value: FightEstimate) -> Self {
+        CFightEstimate {
+            samples: value.samples,
+            wins: value.wins,
+            win_rate: value.win_rate,
+            lower: value.lower,
+            upper: value.upper,
+            average_rounds: value.average_rounds,
+            own_damage: value.left_damage.into(),
+            other_damage: value.right_damage.into(),
+        }
+    }
+}
+
+/// Simulates fights of the own character against the other player on
+/// multiple threads and writes the estimated win rate into `out`
+///
+/// # Safety
+/// - `gs` has to be null, or a valid `GameState`
+/// - `other` has to be null, or a valid `OtherPlayer`
+/// - `out` has to be null, or point to memory, that a `CFightEstimate` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn game_state_estimate_fight(
+    gs: *const GameState,
+    other: *const OtherPlayer,
+    options: CEstimateOptions,
+    out: *mut CFightEstimate,
+) -> bool {
+    let (Some(gs), Some(other), false) = (ffi_ref(gs), ffi_ref(other), out.is_null()) else {
+        invalid_argument::<CFightEstimate>();
+        return false;
+    };
+    let result = PlayerFighterSquad::new(gs).estimate(&UpgradeableFighter::from_other(other), &options.into());
+    unsafe { *out = result.into() };
+    clear_last_error();
+    true
+}
+
+
+/// Simulates one fight of the own character against the other player and
+/// returns everything, that has happened in it, as a JSON string, that has to
+/// be freed with `destr_string`. The same seed always gives the same fight
+///
+/// # Safety
+/// - `gs` has to be null, or a valid `GameState`
+/// - `other` has to be null, or a valid `OtherPlayer`
+#[no_mangle]
+#[cfg(all(feature = "serde", feature = "session"))]
+pub unsafe extern "C" fn game_state_simulate_fight_json(gs: *const GameState, other: *const OtherPlayer, seed: u64) -> *mut i8 {
+    let (Some(gs), Some(other)) = (ffi_ref(gs), ffi_ref(other)) else {
+        return invalid_argument();
+    };
+    let mut own = [BattleFighter::from_upgradeable(&PlayerFighterSquad::new(gs).character)];
+    let mut other = [BattleFighter::from_upgradeable(&UpgradeableFighter::from_other(other))];
+    let mut recorder = TimelineRecorder::new();
+    Battle::with_seed(&mut own, &mut other, seed).simulate(&mut recorder);
+    to_json_c_string(&recorder.into_timeline())
+}
+
+
+// ################################################
+// #                 OUTCOME                      #
+// ################################################
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The result of a fight from the perspective of the own character
+pub struct CFightOutcome {
+    pub won: bool,
+    pub honor_change: i64,
+    pub silver: i64,
+    pub experience: u64,
+    pub mushrooms: u8,
+    pub rank_before: u32,
+    pub rank_after: u32,
+    /// Set, if the fight gave the character an equipment item
+    pub has_item: bool,
+    /// The item the fight gave the character. Only valid, if `has_item` is set
+    pub item: CEquipmentIdent,
+}
+
+impl From<&FightOutcome> for CFightOutcome {
+    fn from(fight: &FightOutcome) -> Self {
+        let item = fight.item.as_ref().and_then(Item::equipment_ident);
+        CFightOutcome {
+            won: fight.won,
+            honor_change: fight.honor_change,
+            silver: fight.silver,
+            experience: fight.experience,
+            mushrooms: fight.mushrooms,
+            rank_before: fight.rank_before,
+            rank_after: fight.rank_after,
+            has_item: item.is_some(),
+            item: item.map_or(
+                CEquipmentIdent { class_id: 0, slot: 0, model_id: 0, color: 0, is_epic: false },
+                Into::into,
+            ),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The silver, or mushrooms won (positive), or lost (negative) gambling
+pub struct CGambleResult {
+    /// Set, if mushrooms have been gambled instead of silver
+    pub mushrooms: bool,
+    pub change: i64,
+}
+
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that lives for `'a`
+unsafe fn session_outcome<'a>(session: *const GameSession) -> Option<&'a CommandOutcome> {
+    idle_session(session).and_then(|a| a.outcome.as_ref())
+}
+
+/// Writes the result of the last command into `out`. Returns false, if the
+/// last command was not a fight
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`
+/// - `out` has to be null, or point to memory, that a `CFightOutcome` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn session_fight_outcome(session: *const GameSession, out: *mut CFightOutcome) -> bool {
+    let (Some(CommandOutcome::Fight(fight)), false) = (session_outcome(session), out.is_null()) else {
+        return false;
+    };
+    unsafe { *out = fight.into() };
+    true
+}
+
+/// Writes the result of the last command into `out`. Returns false, if the
+/// last command was not a gamble
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`
+/// - `out` has to be null, or point to memory, that a `CGambleResult` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn session_gamble_outcome(session: *const GameSession, out: *mut CGambleResult) -> bool {
+    let (Some(CommandOutcome::Gamble(result)), false) = (session_outcome(session), out.is_null()) else {
+        return false;
+    };
+    let result = match *result {
+        GambleResult::SilverChange(change) => CGambleResult { mushrooms: false, change },
+        GambleResult::MushroomChange(change) => CGambleResult { mushrooms: true, change: change.into() },
+    };
+    unsafe { *out = result };
+    true
+}
+
+/// The player the last command has looked at. Null, if the last command was
+/// not `view_player`. Only valid until the next command
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`
+#[no_mangle]
+pub unsafe extern "C" fn session_player_outcome(session: *const GameSession) -> *const OtherPlayer {
+    match session_outcome(session) {
+        Some(CommandOutcome::Player(player)) => ptr::from_ref(&**player),
+        _ => ptr::null(),
+    }
+}
+
+/// The amount of players on the Hall of Fame page, that the last command has
+/// fetched. 0, if the last command was not `hall_of_fame_page`
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`
+#[no_mangle]
+pub unsafe extern "C" fn session_hall_of_fame_outcome_len(session: *const GameSession) -> usize {
+    match session_outcome(session) {
+        Some(CommandOutcome::HallOfFame(players)) => players.len(),
+        _ => 0,
+    }
+}
+
+/// One of the players on the Hall of Fame page, that the last command has
+/// fetched. Only valid until the next command
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`
+#[no_mangle]
+pub unsafe extern "C" fn session_hall_of_fame_outcome_player(session: *const GameSession, pos: usize) -> *const HallOfFamePlayer {
+    match session_outcome(session) {
+        Some(CommandOutcome::HallOfFame(players)) => players.get(pos).map_or(ptr::null(), ptr::from_ref),
+        _ => ptr::null(),
+    }
+}
+
+/// # Safety
+/// `player` has to be null, or a valid `HallOfFamePlayer`
+#[no_mangle]
+pub unsafe extern "C" fn hof_player_rank(player: *const HallOfFamePlayer) -> u32 {
+    ffi_ref(player).map(|a| a.rank).unwrap_or_default()
+}
+
+/// Has to be freed with `destr_string`
+///
+/// # Safety
+/// `player` has to be null, or a valid `HallOfFamePlayer`
+#[no_mangle]
+pub unsafe extern "C" fn hof_player_name(player: *const HallOfFamePlayer) -> *mut i8 {
+    ffi_ref(player).map_or(ptr::null_mut(), |a| to_c_string(&a.name))
+}
+
+/// Null, if the player is not in a guild. Has to be freed with `destr_string`
+///
+/// # Safety
+/// `player` has to be null, or a valid `HallOfFamePlayer`
+#[no_mangle]
+pub unsafe extern "C" fn hof_player_guild(player: *const HallOfFamePlayer) -> *mut i8 {
+    ffi_ref(player).and_then(|a| a.guild.as_deref()).map_or(ptr::null_mut(), to_c_string)
+}
+
+/// # Safety
+/// `player` has to be null, or a valid `HallOfFamePlayer`
+#[no_mangle]
+pub unsafe extern "C" fn hof_player_level(player: *const HallOfFamePlayer) -> u32 {
+    ffi_ref(player).map(|a| a.level).unwrap_or_default()
+}
+
+/// # Safety
+/// `player` has to be null, or a valid `HallOfFamePlayer`
+#[no_mangle]
+pub unsafe extern "C" fn hof_player_honor(player: *const HallOfFamePlayer) -> u32 {
+    ffi_ref(player).map(|a| a.honor).unwrap_or_default()
+}
+
+/// # Safety
+/// `player` has to be null, or a valid `HallOfFamePlayer`
+#[no_mangle]
+pub unsafe extern "C" fn hof_player_class(player: *const HallOfFamePlayer) -> u8 {
+    ffi_ref(player).map(|a| a.class as u8).unwrap_or_default()
+}
+
+
+// ################################################
+// #                 SCRAPBOOK                    #
+// ################################################
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The C compatible version of an `EquipmentIdent`
+pub struct CEquipmentIdent {
+    /// The class of the item + 1, like in the item key. 0, if the item has no
+    /// class
+    pub class_id: u8,
+    /// The raw value of the `EquipmentSlot` the item is worn in
+    pub slot: u8,
+    /// The model id, this is basically the "name" of the item
+    pub model_id: u16,
+    /// The color variation of this item
+    pub color: u8,
+    /// Whether or not this item is an epic
+    pub is_epic: bool,
+}
+
+impl From<EquipmentIdent> for CEquipmentIdent {
+    fn from(ident: EquipmentIdent) -> Self {
+        CEquipmentIdent {
+            class_id: ident.class.map_or(0, |a| a as u8 + 1),
+            slot: ident.typ as u8,
+            model_id: ident.model_id,
+            color: ident.color,
+            is_epic: ident.is_epic(),
+        }
+    }
+}
+
+/// Hands a list of items to C++. The list has to be freed with
+/// `destr_equipment_idents`
+///
+/// # Safety
+/// `out_len` has to point to memory, that a `usize` can be written to
+unsafe fn to_c_idents(idents: Vec<EquipmentIdent>, out_len: *mut usize) -> *mut CEquipmentIdent {
+    let idents: Box<[CEquipmentIdent]> = idents.into_iter().map(CEquipmentIdent::from).collect();
+    unsafe { *out_len = idents.len() };
+    Box::into_raw(idents).cast()
+}
+
+/// # Safety
+/// `scrapbook` has to be null, or a valid `ScrapBook`
+#[no_mangle]
+pub unsafe extern "C" fn scrapbook_item_count(scrapbook: *const ScrapBook) -> usize {
+    ffi_ref(scrapbook).map(|a| a.items.len()).unwrap_or_default()
+}
+
+/// Retrieves all the items the other player has equipped, that are not yet in
+/// the scrapbook. These are the items you would gain by attacking them
+///
+/// # Safety
+/// - `scrapbook` has to be null, or a valid `ScrapBook`
+/// - `other` has to be null, or a valid `OtherPlayer`
+/// - `out_len` has to be null, or point to memory, that a `usize` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn scrapbook_missing_items(
+    scrapbook: *const ScrapBook,
+    other: *const OtherPlayer,
+    out_len: *mut usize,
+) -> *mut CEquipmentIdent {
+    if out_len.is_null() {
+        return ptr::null_mut();
+    }
+    let (Some(scrapbook), Some(other)) = (ffi_ref(scrapbook), ffi_ref(other)) else {
+        unsafe { *out_len = 0 };
+        return ptr::null_mut();
+    };
+    to_c_idents(scrapbook.missing_items(other), out_len)
+}
+
+/// Frees a list of items returned by one of the scrapbook functions
+///
+/// # Safety
+/// `idents` has to be null, or a list from this library with its `len`, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_equipment_idents(idents: *mut CEquipmentIdent, len: usize) {
+    if idents.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(idents, len)));
+    }
+}
+
+
+// #####################################################
+// #                 ITEM CATALOGUE                    #
+// #####################################################
+
+#[repr(C)]
+#[derive(Debug)]
+/// The C compatible version of a `CatalogueEntry`. Has to be freed with
+/// `destr_catalogue_entry`
+pub struct CCatalogueEntry {
+    /// The name of the item. If the item is not in the catalogue, this is a
+    /// generic name based on the ident
+    pub name: *mut i8,
+    /// The set this item belongs to. Null, if there is none
+    pub set: *mut i8,
+    pub is_epic: bool,
+    pub is_legendary: bool,
+    /// The lowest level this item drops at. 0, if unknown
+    pub min_level: u16,
+    /// The highest level this item drops at. 0, if unknown
+    pub max_level: u16,
+    /// Whether or not the item was found in the catalogue
+    pub is_known: bool,
+}
+
+impl CEquipmentIdent {
+    fn to_ident(self) -> Option<EquipmentIdent> {
+        let class = match self.class_id {
+            0 => None,
+            x => Some(FromPrimitive::from_u8(x - 1)?),
+        };
+        let typ = EquipmentSlot::iter().find(|a| *a as u8 == self.slot)?;
+        Some(EquipmentIdent {
+            class,
+            typ,
+            model_id: self.model_id,
+            color: self.color,
+        })
+    }
+}
+
+/// The catalogue, that ships with the library. This must not be freed
+#[no_mangle]
+pub extern "C" fn item_catalogue_embedded() -> *const ItemCatalogue {
+    ItemCatalogue::embedded()
+}
+
+/// Loads the catalogue file at `path` on top of the embedded catalogue.
+/// Returns null, if the file could not be read, or parsed. Has to be freed
+/// with `destr_item_catalogue`
+///
+/// # Safety
+/// `path` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn item_catalogue_load(path: *const i8) -> *mut ItemCatalogue {
+    let Some(path) = from_c_str(path) else {
+        return ptr::null_mut();
+    };
+    let loaded = std::fs::read_to_string(path)
+        .map_err(|e| log::warn!("Could not read item catalogue: {e}"))
+        .and_then(|a| {
+            ItemCatalogue::parse(&a)
+                .map_err(|e| log::warn!("Could not parse item catalogue: {e}"))
+        });
+    let Ok(loaded) = loaded else {
+        return ptr::null_mut();
+    };
+    let mut catalogue = ItemCatalogue::embedded().clone();
+    catalogue.extend(loaded);
+    Box::into_raw(Box::new(catalogue))
+}
+
+/// # Safety
+/// `catalogue` has to be null, or a pointer from this library, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_item_catalogue(catalogue: *mut ItemCatalogue) {
+    if !catalogue.is_null() {
+        unsafe { drop(Box::from_raw(catalogue)) };
+    }
+}
+
+/// Looks up an item in the catalogue
+///
+/// # Safety
+/// `catalogue` has to be null, or a valid `ItemCatalogue`
+#[no_mangle]
+pub unsafe extern "C" fn item_catalogue_lookup(
+    catalogue: *const ItemCatalogue,
+    ident: CEquipmentIdent,
+) -> CCatalogueEntry {
+    let catalogue = ffi_ref(catalogue).unwrap_or(ItemCatalogue::embedded());
+    let ident = ident.to_ident();
+    let entry = ident.as_ref().and_then(|a| catalogue.get(a));
+    let level_range = entry.and_then(|a| a.level_range.clone());
+    CCatalogueEntry {
+        name: ident.map_or(ptr::null_mut(), |a| to_c_string(&catalogue.name(&a))),
+        set: entry
+            .and_then(|a| a.set.as_deref())
+            .map_or(ptr::null_mut(), to_c_string),
+        is_epic: ident.is_some_and(|a| a.is_epic()),
+        is_legendary: ident.is_some_and(|a| a.is_legendary()),
+        min_level: level_range.as_ref().map(|a| *a.start()).unwrap_or_default(),
+        max_level: level_range.as_ref().map(|a| *a.end()).unwrap_or_default(),
+        is_known: entry.is_some(),
+    }
+}
+
+/// Frees the strings of an entry returned by `item_catalogue_lookup`
+///
+/// # Safety
+/// `entry` has to be an entry from `item_catalogue_lookup`, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_catalogue_entry(entry: CCatalogueEntry) {
+    unsafe {
+        destr_string(entry.name);
+        destr_string(entry.set);
+    }
+}
+
+
+// ########################################################
+// #                 SCRAPBOOK HISTORY                    #
+// ########################################################
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The completion of the scrapbook at one point in time
+pub struct CScrapBookCompletion {
+    /// The unix timestamp of the snapshot
+    pub timestamp: i64,
+    /// The total completion in percent (0.0 - 100.0)
+    pub percent: f64,
+}
+
+/// The history stores the local time the responses were received at, while
+/// C++ uses unix timestamps
+fn from_c_timestamp(timestamp: i64) -> Option<chrono::NaiveDateTime> {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|a| a.with_timezone(&chrono::Local).naive_local())
+}
+
+fn to_c_timestamp(time: chrono::NaiveDateTime) -> i64 {
+    to_timestamp(time.and_local_timezone(chrono::Local).earliest())
+}
+
+/// Makes the session record the scrapbook after every `Update` and
+/// `ViewScrapbook` into the history stored in `dir`. Passing null stops the
+/// recording
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `dir` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn session_set_scrapbook_history(session: *mut GameSession, dir: *const i8) {
+    let Some(session) = idle_session_mut(session) else {
+        return;
+    };
+    session.history = from_c_str(dir).map(ScrapBookHistory::new);
+}
+
+/// Returns the host of the server the session is connected to, which is what
+/// the history uses as the server. Has to be freed with `destr_string`
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`
+#[no_mangle]
+pub unsafe extern "C" fn session_server_host(session: *const GameSession) -> *mut i8 {
+    idle_session(session).map_or(ptr::null_mut(), |a| to_c_string(a.server_host()))
+}
+
+/// Opens the scrapbook history stored in `dir`. Has to be freed with
+/// `destr_scrapbook_history`
+///
+/// # Safety
+/// `dir` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn init_scrapbook_history(dir: *const i8) -> *mut ScrapBookHistory {
+    match from_c_str(dir) {
+        Some(dir) => Box::into_raw(Box::new(ScrapBookHistory::new(dir))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// # Safety
+/// `history` has to be null, or a pointer from this library, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_scrapbook_history(history: *mut ScrapBookHistory) {
+    if !history.is_null() {
+        unsafe { drop(Box::from_raw(history)) };
+    }
+}
+
+/// Retrieves all the items the character has collected since the unix
+/// timestamp `since`. The list has to be freed with `destr_equipment_idents`
+///
+/// # Safety
+/// - `history` has to be null, or a valid `ScrapBookHistory`
+/// - `server` has to be null, or a valid, nul terminated string
+/// - `out_len` has to be null, or point to memory, that a `usize` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn scrapbook_history_gained_since(
+    history: *const ScrapBookHistory,
+    server: *const i8,
+    player_id: u32,
+    since: i64,
+    out_len: *mut usize,
+) -> *mut CEquipmentIdent {
+    if out_len.is_null() {
+        return ptr::null_mut();
+    }
+    let gained = match (ffi_ref(history), from_c_str(server), from_c_timestamp(since)) {
+        (Some(history), Some(server), Some(since)) => {
+            history.gained_since(server, player_id, since).unwrap_or_else(|e| {
+                log::warn!("Could not read scrapbook history: {e}");
+                None
+            })
+        }
+        _ => None,
+    };
+    let Some(gained) = gained else {
+        unsafe { *out_len = 0 };
+        return ptr::null_mut();
+    };
+    to_c_idents(gained.items.into_iter().collect(), out_len)
+}
+
+/// Retrieves the completion of the scrapbook for every recorded day. The list
+/// has to be freed with `destr_scrapbook_completions`
+///
+/// # Safety
+/// - `history` has to be null, or a valid `ScrapBookHistory`
+/// - `server` has to be null, or a valid, nul terminated string
+/// - `out_len` has to be null, or point to memory, that a `usize` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn scrapbook_history_completion(
+    history: *const ScrapBookHistory,
+    server: *const i8,
+    player_id: u32,
+    out_len: *mut usize,
+) -> *mut CScrapBookCompletion {
+    if out_len.is_null() {
+        return ptr::null_mut();
+    }
+    let completion = match (ffi_ref(history), from_c_str(server)) {
+        (Some(history), Some(server)) => {
+            history.completion_over_time(server, player_id).unwrap_or_else(|e| {
+                log::warn!("Could not read scrapbook history: {e}");
+                Vec::new()
+            })
+        }
+        _ => Vec::new(),
+    };
+    let completion: Box<[CScrapBookCompletion]> = completion
+        .into_iter()
+        .map(|(time, percent)| CScrapBookCompletion {
+            timestamp: to_c_timestamp(time),
+            percent,
+        })
+        .collect();
+    unsafe { *out_len = completion.len() };
+    Box::into_raw(completion).cast()
+}
+
+/// # Safety
+/// `completions` has to be null, or a list from this library with its `len`, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_scrapbook_completions(completions: *mut CScrapBookCompletion, len: usize) {
+    if completions.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(completions, len)));
+    }
+}
+
+
+// #####################################################
+// #                 QUEST CHOOSER                    #
+// #####################################################
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The C compatible version of `QuestWeights`
+pub struct CQuestWeights {
+    pub experience: f64,
+    pub silver: f64,
+    pub new_monster: f64,
+    pub new_item: f64,
+}
+
+impl From<CQuestWeights> for QuestWeights {
+    fn from(w: CQuestWeights) -> Self {
+        QuestWeights {
+            experience: w.experience,
+            silver: w.silver,
+            new_monster: w.new_monster,
+            new_item: w.new_item,
+        }
+    }
+}
+
+impl From<QuestWeights> for CQuestWeights {
+    fn from(w: QuestWeights) -> Self {
+        CQuestWeights {
+            experience: w.experience,
+            silver: w.silver,
+            new_monster: w.new_monster,
+            new_item: w.new_item,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The C compatible version of a `QuestRating`
+pub struct CQuestRating {
+    pub quest_pos: usize,
+    pub experience_per_sec: f64,
+    pub silver_per_sec: f64,
+    pub new_monster: bool,
+    pub new_item: bool,
+    pub startable: bool,
+    pub score: f64,
+}
+
+impl From<QuestRating> for CQuestRating {
+    fn from(r: QuestRating) -> Self {
+        CQuestRating {
+            quest_pos: r.quest_pos,
+            experience_per_sec: r.experience_per_sec,
+            silver_per_sec: r.silver_per_sec,
+            new_monster: r.new_monster,
+            new_item: r.new_item,
+            startable: r.startable,
+            score: r.score,
+        }
+    }
+}
+
+/// The default weights, that `QuestWeights::default()` uses
+#[no_mangle]
+pub extern "C" fn default_quest_weights() -> CQuestWeights {
+    QuestWeights::default().into()
+}
+
+/// Rates the tavern quests. The best quest is at the front. The list has to
+/// be freed with `destr_quest_ratings`
+///
+/// # Safety
+/// - `gs` has to be null, or a valid `GameState`
+/// - `out_len` has to be null, or point to memory, that a `usize` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn game_state_rate_quests(
+    gs: *const GameState,
+    weights: CQuestWeights,
+    out_len: *mut usize,
+) -> *mut CQuestRating {
+    if out_len.is_null() {
+        return ptr::null_mut();
+    }
+    let ratings = ffi_ref(gs)
+        .map(|gs| rate_quests(gs, &weights.into()))
+        .unwrap_or_default();
+    let ratings: Box<[CQuestRating]> = ratings.into_iter().map(CQuestRating::from).collect();
+    unsafe { *out_len = ratings.len() };
+    Box::into_raw(ratings).cast()
+}
+
+/// # Safety
+/// `ratings` has to be null, or a list from this library with its `len`, that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn destr_quest_ratings(ratings: *mut CQuestRating, len: usize) {
+    if ratings.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ratings, len)));
+    }
+}
+
+/// Writes the position of the quest, that should be started next, into
+/// `out_quest_pos`. Returns false, if there is no quest to start right now
+///
+/// # Safety
+/// - `gs` has to be null, or a valid `GameState`
+/// - `out_quest_pos` has to be null, or point to memory, that a `usize` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn game_state_recommended_quest(
+    gs: *const GameState,
+    weights: CQuestWeights,
+    out_quest_pos: *mut usize,
+) -> bool {
+    if out_quest_pos.is_null() {
+        return false;
+    }
+    let Some(Command::StartQuest { quest_pos, .. }) =
+        ffi_ref(gs).and_then(|gs| recommend_quest(gs, &weights.into()))
+    else {
+        return false;
+    };
+    unsafe { *out_quest_pos = quest_pos };
+    true
+}
+
+/// Starts the quest, that `game_state_recommended_quest` would pick. Returns
+/// null, if there is no quest to start
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_RecommendedQuest(session: *mut GameSession, weights: CQuestWeights) -> *mut Response {
+    if session.is_null() {
+        return invalid_argument();
+    }
+    if busy_flag(session).load(Ordering::Acquire) {
+        return session_busy();
+    }
+    let command = idle_session(session)
+        .and_then(|a| a.gamestate.as_ref())
+        .and_then(|gs| recommend_quest(gs, &weights.into()));
+    if let Some(command) = command {
+        execute_command(session, command)
+    } else {
+        clear_last_error();
+        ptr::null_mut()
+    }
+}
+
+
+// ####################################################
+// #                 C ENUM MIRRORS                   #
+// ####################################################
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, num_derive::FromPrimitive)]
+/// The raw values of `PlayerItemPlace`, that C++ passes in
+pub enum CPlayerItemPlace {
+    Equipment = 1,
+    MainInventory = 2,
+    ExtendedInventory = 5,
+}
+
+impl From<CPlayerItemPlace> for PlayerItemPlace {
+    fn from(value: CPlayerItemPlace) -> Self {
+        match value {
+            CPlayerItemPlace::Equipment => PlayerItemPlace::Equipment,
+            CPlayerItemPlace::MainInventory => PlayerItemPlace::MainInventory,
+            CPlayerItemPlace::ExtendedInventory => PlayerItemPlace::ExtendedInventory,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, num_derive::FromPrimitive)]
+/// The raw values of `ShopType`, that C++ passes in
+pub enum CShopType {
+    Weapon = 3,
+    Magic = 4,
+}
+
+impl From<CShopType> for ShopType {
+    fn from(value: CShopType) -> Self {
+        match value {
+            CShopType::Weapon => ShopType::Weapon,
+            CShopType::Magic => ShopType::Magic,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, num_derive::FromPrimitive)]
+/// The raw values of `BlacksmithAction`, that C++ passes in
+pub enum CBlacksmithAction {
+    Dismantle = 201,
+    SocketUpgrade = 202,
+    SocketUpgradeWithMushrooms = 212,
+    GemExtract = 203,
+    GemExtractWithMushrooms = 213,
+    Upgrade = 204,
+}
+
+impl From<CBlacksmithAction> for BlacksmithAction {
+    fn from(value: CBlacksmithAction) -> Self {
+        match value {
+            CBlacksmithAction::Dismantle => BlacksmithAction::Dismantle,
+            CBlacksmithAction::SocketUpgrade => BlacksmithAction::SocketUpgrade,
+            CBlacksmithAction::SocketUpgradeWithMushrooms => BlacksmithAction::SocketUpgradeWithMushrooms,
+            CBlacksmithAction::GemExtract => BlacksmithAction::GemExtract,
+            CBlacksmithAction::GemExtractWithMushrooms => BlacksmithAction::GemExtractWithMushrooms,
+            CBlacksmithAction::Upgrade => BlacksmithAction::Upgrade,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, num_derive::FromPrimitive)]
+/// The raw values of `RollDicePrice`, that C++ passes in
+pub enum CRollDicePrice {
+    Free = 0,
+    Mushrooms,
+    Hourglass,
+}
+
+impl From<CRollDicePrice> for RollDicePrice {
+    fn from(value: CRollDicePrice) -> Self {
+        match value {
+            CRollDicePrice::Free => RollDicePrice::Free,
+            CRollDicePrice::Mushrooms => RollDicePrice::Mushrooms,
+            CRollDicePrice::Hourglass => RollDicePrice::Hourglass,
+        }
     }
 }
 
+#[repr(C)]
+#[derive(Debug, Clone, Copy, num_derive::FromPrimitive)]
+/// Whether a `CDungeon` is a light, or a shadow dungeon
+pub enum CDungeonType {
+    Light = 0,
+    Shadow = 1,
+}
 
-// ####################################################
-// #                 RESPONSE KEYS                    #
-// ####################################################
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+/// The C compatible version of `Dungeon`
+pub struct CDungeon {
+    /// The raw value of the `CDungeonType`
+    pub typ: u8,
+    /// The raw value of the `LightDungeon`, or `ShadowDungeon`
+    pub id: u8,
+}
 
-/// Retrieves the keys from a parsed response
-#[no_mangle]
-pub extern "C" fn response_get_keys(response: *mut Response, out_len: *mut usize) -> *mut *const i8 {
-    if response.is_null() || out_len.is_null() {
-        return ptr::null_mut();
+impl CDungeon {
+    fn to_dungeon(self) -> Option<Dungeon> {
+        let id = usize::from(self.id);
+        Some(match CDungeonType::from_u8(self.typ)? {
+            CDungeonType::Light => Dungeon::Light(LightDungeon::from_usize(id)?),
+            CDungeonType::Shadow => Dungeon::Shadow(ShadowDungeon::from_usize(id)?),
+        })
     }
-
-    let response = unsafe { &*response };
-    let keys: Vec<CString> = response.values().keys().map(|&key| CString::new(key).unwrap()).collect();
-    
-    // Convert CString to *const i8 (C-compatible)
-    let mut c_keys: Vec<*const i8> = keys.iter().map(|s| s.as_ptr()).collect();
-    
-    // Store length in out_len
-    unsafe { *out_len = c_keys.len(); }
-
-    // Leak memory (C++ must call sf_response_free_keys() later)
-    let ptr = c_keys.as_mut_ptr();
-    std::mem::forget(keys);
-    std::mem::forget(c_keys);
-    ptr
 }
 
-/// Frees the memory allocated by sf_response_get_keys
-#[no_mangle]
-pub extern "C" fn destr_response_keys(keys: *mut *const i8, len: usize) {
-    if keys.is_null() {
-        return;
-    }
-    unsafe {
-        let _ = Vec::from_raw_parts(keys, len, len); // Reclaim ownership, drop it
-    }
+
+/// Converts the raw value of a C enum mirror into the Rust enum. C++ could
+/// pass in any value, so we never take the enums themselves as arguments
+fn from_raw<C: FromPrimitive + Into<T>, T>(raw: u8) -> Option<T> {
+    C::from_u8(raw).map(Into::into)
 }
 
 
@@ -204,158 +3074,220 @@ pub extern "C" fn destr_response_keys(keys: *mut *const i8, len: usize) {
 // #                 NO-ARGUMENT COMMANDS                    #
 // ###########################################################
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_Update(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_Update(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::Update)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_BuyBeer(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_BuyBeer(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::BuyBeer)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_CancelQuest(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_CancelQuest(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::CancelQuest)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FinishWork(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_FinishWork(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::FinishWork)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_CheckArena(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_CheckArena(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::CheckArena)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_CollectCalendar(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_CollectCalendar(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::CollectCalendar)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ToiletFlush(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_ToiletFlush(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::ToiletFlush)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ToiletOpen(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_ToiletOpen(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::ToiletOpen)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_CancelWork(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_CancelWork(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::CancelWork)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GuildLoadMushrooms(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildLoadMushrooms(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::GuildLoadMushrooms)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GuildJoinAttack(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildJoinAttack(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::GuildJoinAttack)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GuildJoinDefense(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildJoinDefense(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::GuildJoinDefense)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GuildRaid(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildRaid(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::GuildRaid)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GuildPortalBattle(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildPortalBattle(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::GuildPortalBattle)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GuildGetFightableTargets(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildGetFightableTargets(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::GuildGetFightableTargets)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ViewScrapbook(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_ViewScrapbook(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::ViewScrapbook)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FightPortal(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_FightPortal(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::FightPortal)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_SwapManequin(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_SwapManequin(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::SwapManequin)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_IdleSacrifice(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_IdleSacrifice(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::IdleSacrifice)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorEnter(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorEnter(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::HellevatorEnter)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorViewGuildRanking(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorViewGuildRanking(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::HellevatorViewGuildRanking)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorRefreshShop(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorRefreshShop(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::HellevatorRefreshShop)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorClaimDaily(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorClaimDaily(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::HellevatorClaimDaily)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorClaimDailyYesterday(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorClaimDailyYesterday(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::HellevatorClaimDailyYesterday)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorClaimFinal(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorClaimFinal(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::HellevatorClaimFinal)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorPreviewRewards(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorPreviewRewards(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::HellevatorPreviewRewards)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_BuyGoldFrame(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_BuyGoldFrame(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::BuyGoldFrame)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressGemStoneSearch(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressGemStoneSearch(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::FortressGemStoneSearch)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressGemStoneSearchCancel(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressGemStoneSearchCancel(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::FortressGemStoneSearchCancel)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressUpgradeHallOfKnights(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressUpgradeHallOfKnights(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::FortressUpgradeHallOfKnights)
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ExpeditionContinue(session: *mut Session) -> *mut Response {
+pub unsafe extern "C" fn exec_ExpeditionContinue(session: *mut GameSession) -> *mut Response {
     execute_command(session, Command::ExpeditionContinue)
 }
 
@@ -364,166 +3296,222 @@ pub extern "C" fn exec_ExpeditionContinue(session: *mut Session) -> *mut Respons
 // #                 COMMANDS WITH ARGUMENTS                    #
 // ##############################################################
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HallOfFamePage(session: *mut Session, page: usize) -> *mut Response {
+pub unsafe extern "C" fn exec_HallOfFamePage(session: *mut GameSession, page: usize) -> *mut Response {
     execute_command(session, Command::HallOfFamePage { page })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HallOfFameFortressPage(session: *mut Session, page: usize) -> *mut Response {
+pub unsafe extern "C" fn exec_HallOfFameFortressPage(session: *mut GameSession, page: usize) -> *mut Response {
     execute_command(session, Command::HallOfFameFortressPage { page })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `ident` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_ViewPlayer(session: *mut Session, ident: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_ViewPlayer(session: *mut GameSession, ident: *const i8) -> *mut Response {
     if ident.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let ident_cstr = unsafe { CStr::from_ptr(ident) };
     let ident_str = ident_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::ViewPlayer { ident: ident_str })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_StartQuest(session: *mut Session, quest_pos: usize, overwrite_inv: bool) -> *mut Response {
+pub unsafe extern "C" fn exec_StartQuest(session: *mut GameSession, quest_pos: usize, overwrite_inv: bool) -> *mut Response {
     execute_command(session, Command::StartQuest { quest_pos, overwrite_inv })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_StartWork(session: *mut Session, hours: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_StartWork(session: *mut GameSession, hours: u8) -> *mut Response {
     execute_command(session, Command::StartWork { hours })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_RemovePotion(session: *mut Session, pos: usize) -> *mut Response {
+pub unsafe extern "C" fn exec_RemovePotion(session: *mut GameSession, pos: usize) -> *mut Response {
     execute_command(session, Command::RemovePotion { pos })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `name` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_Fight(session: *mut Session, name: *const i8, use_mushroom: bool) -> *mut Response {
+pub unsafe extern "C" fn exec_Fight(session: *mut GameSession, name: *const i8, use_mushroom: bool) -> *mut Response {
     if name.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let name_cstr = unsafe { CStr::from_ptr(name) };
     let name_str = name_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::Fight { name: name_str, use_mushroom })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `guild` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_GuildAttack(session: *mut Session, guild: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildAttack(session: *mut GameSession, guild: *const i8) -> *mut Response {
     if guild.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let guild_cstr = unsafe { CStr::from_ptr(guild) };
     let guild_str = guild_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::GuildAttack { guild: guild_str })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FinishQuest(session: *mut Session, skip: bool) -> *mut Response {
+pub unsafe extern "C" fn exec_FinishQuest(session: *mut GameSession, skip: bool) -> *mut Response {
     let time_skip = if skip { Some(crate::command::TimeSkip::Mushroom) } else { None };
     execute_command(session, Command::FinishQuest { skip: time_skip })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `name` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_CheckNameAvailable(session: *mut Session, name: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_CheckNameAvailable(session: *mut GameSession, name: *const i8) -> *mut Response {
     if name.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let name_cstr = unsafe { CStr::from_ptr(name) };
     let name_str = name_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::CheckNameAvailable { name: name_str })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `guild_ident` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_ViewGuild(session: *mut Session, guild_ident: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_ViewGuild(session: *mut GameSession, guild_ident: *const i8) -> *mut Response {
     if guild_ident.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let guild_cstr = unsafe { CStr::from_ptr(guild_ident) };
     let guild_str = guild_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::ViewGuild { guild_ident: guild_str })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `name` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_GuildFound(session: *mut Session, name: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildFound(session: *mut GameSession, name: *const i8) -> *mut Response {
     if name.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let name_cstr = unsafe { CStr::from_ptr(name) };
     let name_str = name_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::GuildFound { name: name_str })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `name` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_GuildInvitePlayer(session: *mut Session, name: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildInvitePlayer(session: *mut GameSession, name: *const i8) -> *mut Response {
     if name.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let name_cstr = unsafe { CStr::from_ptr(name) };
     let name_str = name_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::GuildInvitePlayer { name: name_str })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `name` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_GuildKickPlayer(session: *mut Session, name: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildKickPlayer(session: *mut GameSession, name: *const i8) -> *mut Response {
     if name.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let name_cstr = unsafe { CStr::from_ptr(name) };
     let name_str = name_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::GuildKickPlayer { name: name_str })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `name` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_GuildSetLeader(session: *mut Session, name: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildSetLeader(session: *mut GameSession, name: *const i8) -> *mut Response {
     if name.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let name_cstr = unsafe { CStr::from_ptr(name) };
     let name_str = name_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::GuildSetLeader { name: name_str })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `name` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_GuildToggleOfficer(session: *mut Session, name: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildToggleOfficer(session: *mut GameSession, name: *const i8) -> *mut Response {
     if name.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let name_cstr = unsafe { CStr::from_ptr(name) };
     let name_str = name_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::GuildToggleOfficer { name: name_str })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_MessageOpen(session: *mut Session, pos: i32) -> *mut Response {
+pub unsafe extern "C" fn exec_MessageOpen(session: *mut GameSession, pos: i32) -> *mut Response {
     execute_command(session, Command::MessageOpen { pos })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_MessageDelete(session: *mut Session, pos: i32) -> *mut Response {
+pub unsafe extern "C" fn exec_MessageDelete(session: *mut GameSession, pos: i32) -> *mut Response {
     execute_command(session, Command::MessageDelete { pos })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ViewPet(session: *mut Session, pet_id: u16) -> *mut Response {
+pub unsafe extern "C" fn exec_ViewPet(session: *mut GameSession, pet_id: u16) -> *mut Response {
     execute_command(session, Command::ViewPet { pet_id })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GambleSilver(session: *mut Session, amount: u64) -> *mut Response {
+pub unsafe extern "C" fn exec_GambleSilver(session: *mut GameSession, amount: u64) -> *mut Response {
     execute_command(session, Command::GambleSilver { amount })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GambleMushrooms(session: *mut Session, amount: u64) -> *mut Response {
+pub unsafe extern "C" fn exec_GambleMushrooms(session: *mut GameSession, amount: u64) -> *mut Response {
     execute_command(session, Command::GambleMushrooms { amount })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `to` has to be null, or a valid, nul terminated string
+/// - `msg` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_SendMessage(session: *mut Session, to: *const i8, msg: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_SendMessage(session: *mut GameSession, to: *const i8, msg: *const i8) -> *mut Response {
     if to.is_null() || msg.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let to_cstr = unsafe { CStr::from_ptr(to) };
     let to_str = to_cstr.to_str().unwrap_or("").to_string();
@@ -534,10 +3522,14 @@ pub extern "C" fn exec_SendMessage(session: *mut Session, to: *const i8, msg: *c
     execute_command(session, Command::SendMessage { to: to_str, msg: msg_str })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `player_name` has to be null, or a valid, nul terminated string
+/// - `message` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_Whisper(session: *mut Session, player_name: *const i8, message: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_Whisper(session: *mut GameSession, player_name: *const i8, message: *const i8) -> *mut Response {
     if player_name.is_null() || message.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let player_name_cstr = unsafe { CStr::from_ptr(player_name) };
     let player_name_str = player_name_cstr.to_str().unwrap_or("").to_string();
@@ -548,118 +3540,349 @@ pub extern "C" fn exec_Whisper(session: *mut Session, player_name: *const i8, me
     execute_command(session, Command::Whisper { player_name: player_name_str, message: message_str })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `language` has to be null, or a valid, nul terminated string
 #[no_mangle]
-pub extern "C" fn exec_SetLanguage(session: *mut Session, language: *const i8) -> *mut Response {
+pub unsafe extern "C" fn exec_SetLanguage(session: *mut GameSession, language: *const i8) -> *mut Response {
     if language.is_null() {
-        return ptr::null_mut();
+        return invalid_argument();
     }
     let language_cstr = unsafe { CStr::from_ptr(language) };
     let language_str = language_cstr.to_str().unwrap_or("").to_string();
     execute_command(session, Command::SetLanguage { language: language_str })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressNewEnemy(session: *mut Session, use_mushroom: bool) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressNewEnemy(session: *mut GameSession, use_mushroom: bool) -> *mut Response {
     execute_command(session, Command::FortressNewEnemy { use_mushroom })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressSetCAEnemy(session: *mut Session, msg_id: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressSetCAEnemy(session: *mut GameSession, msg_id: u32) -> *mut Response {
     execute_command(session, Command::FortressSetCAEnemy { msg_id })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GuildPetBattle(session: *mut Session, use_mushroom: bool) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildPetBattle(session: *mut GameSession, use_mushroom: bool) -> *mut Response {
     execute_command(session, Command::GuildPetBattle { use_mushroom })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HallOfFameGroupPage(session: *mut Session, page: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_HallOfFameGroupPage(session: *mut GameSession, page: u32) -> *mut Response {
     execute_command(session, Command::HallOfFameGroupPage { page })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HallOfFameUnderworldPage(session: *mut Session, page: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_HallOfFameUnderworldPage(session: *mut GameSession, page: u32) -> *mut Response {
     execute_command(session, Command::HallOfFameUnderworldPage { page })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HallOfFamePetsPage(session: *mut Session, page: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_HallOfFamePetsPage(session: *mut GameSession, page: u32) -> *mut Response {
     execute_command(session, Command::HallOfFamePetsPage { page })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_BlockGuildInvites(session: *mut Session, block_invites: bool) -> *mut Response {
+pub unsafe extern "C" fn exec_BlockGuildInvites(session: *mut GameSession, block_invites: bool) -> *mut Response {
     execute_command(session, Command::BlockGuildInvites { block_invites })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ShowTips(session: *mut Session, show_tips: bool) -> *mut Response {
+pub unsafe extern "C" fn exec_ShowTips(session: *mut GameSession, show_tips: bool) -> *mut Response {
     execute_command(session, Command::ShowTips { show_tips })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ExpeditionStart(session: *mut Session, pos: usize) -> *mut Response {
+pub unsafe extern "C" fn exec_ExpeditionStart(session: *mut GameSession, pos: usize) -> *mut Response {
     execute_command(session, Command::ExpeditionStart { pos })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ExpeditionPickEncounter(session: *mut Session, pos: usize) -> *mut Response {
+pub unsafe extern "C" fn exec_ExpeditionPickEncounter(session: *mut GameSession, pos: usize) -> *mut Response {
     execute_command(session, Command::ExpeditionPickEncounter { pos })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ExpeditionPickReward(session: *mut Session, pos: usize) -> *mut Response {
+pub unsafe extern "C" fn exec_ExpeditionPickReward(session: *mut GameSession, pos: usize) -> *mut Response {
     execute_command(session, Command::ExpeditionPickReward { pos })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HallOfFameHellevatorPage(session: *mut Session, page: usize) -> *mut Response {
+pub unsafe extern "C" fn exec_HallOfFameHellevatorPage(session: *mut GameSession, page: usize) -> *mut Response {
     execute_command(session, Command::HallOfFameHellevatorPage { page })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ClaimablePreview(session: *mut Session, msg_id: i64) -> *mut Response {
+pub unsafe extern "C" fn exec_ClaimablePreview(session: *mut GameSession, msg_id: i64) -> *mut Response {
     execute_command(session, Command::ClaimablePreview { msg_id })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ClaimableClaim(session: *mut Session, msg_id: i64) -> *mut Response {
+pub unsafe extern "C" fn exec_ClaimableClaim(session: *mut GameSession, msg_id: i64) -> *mut Response {
     execute_command(session, Command::ClaimableClaim { msg_id })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorJoinHellAttack(session: *mut Session, use_mushroom: bool, plain: usize) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorJoinHellAttack(session: *mut GameSession, use_mushroom: bool, plain: usize) -> *mut Response {
     execute_command(session, Command::HellevatorJoinHellAttack { use_mushroom, plain })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorFight(session: *mut Session, use_mushroom: bool) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorFight(session: *mut GameSession, use_mushroom: bool) -> *mut Response {
     execute_command(session, Command::HellevatorFight { use_mushroom })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressGemStoneSearchFinish(session: *mut Session, mushrooms: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressGemStoneSearchFinish(session: *mut GameSession, mushrooms: u32) -> *mut Response {
     execute_command(session, Command::FortressGemStoneSearchFinish { mushrooms })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressAttack(session: *mut Session, soldiers: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressAttack(session: *mut GameSession, soldiers: u32) -> *mut Response {
     execute_command(session, Command::FortressAttack { soldiers })
 }
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressGatherSecretStorage(session: *mut Session, stone: u64, wood: u64) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressGatherSecretStorage(session: *mut GameSession, stone: u64, wood: u64) -> *mut Response {
     execute_command(session, Command::FortressGatherSecretStorage { stone, wood })
 }
 
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `username` has to be null, or a valid, nul terminated string
+/// - `pw_hash` has to be null, or a valid, nul terminated string
+#[no_mangle]
+#[allow(deprecated)]
+pub unsafe extern "C" fn exec_Login(session: *mut GameSession, username: *const i8, pw_hash: *const i8, login_count: u32) -> *mut Response {
+    let (Some(username), Some(pw_hash)) = (from_c_str(username), from_c_str(pw_hash)) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::Login { username: username.to_string(), pw_hash: pw_hash.to_string(), login_count })
+}
+
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `uuid` has to be null, or a valid, nul terminated string
+/// - `character_id` has to be null, or a valid, nul terminated string
+/// - `bearer_token` has to be null, or a valid, nul terminated string
+#[no_mangle]
+#[cfg(feature = "sso")]
+#[allow(deprecated)]
+pub unsafe extern "C" fn exec_SSOLogin(session: *mut GameSession, uuid: *const i8, character_id: *const i8, bearer_token: *const i8) -> *mut Response {
+    let (Some(uuid), Some(character_id), Some(bearer_token)) = (from_c_str(uuid), from_c_str(character_id), from_c_str(bearer_token)) else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::SSOLogin {
+            uuid: uuid.to_string(),
+            character_id: character_id.to_string(),
+            bearer_token: bearer_token.to_string(),
+        },
+    )
+}
+
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `username` has to be null, or a valid, nul terminated string
+/// - `old` has to be null, or a valid, nul terminated string
+/// - `new` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn exec_ChangePassword(session: *mut GameSession, username: *const i8, old: *const i8, new: *const i8) -> *mut Response {
+    let (Some(username), Some(old), Some(new)) = (from_c_str(username), from_c_str(old), from_c_str(new)) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::ChangePassword { username: username.to_string(), old: old.to_string(), new: new.to_string() })
+}
+
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `old_mail` has to be null, or a valid, nul terminated string
+/// - `new_mail` has to be null, or a valid, nul terminated string
+/// - `password` has to be null, or a valid, nul terminated string
+/// - `username` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn exec_ChangeMailAddress(session: *mut GameSession, old_mail: *const i8, new_mail: *const i8, password: *const i8, username: *const i8) -> *mut Response {
+    let (Some(old_mail), Some(new_mail), Some(password), Some(username)) =
+        (from_c_str(old_mail), from_c_str(new_mail), from_c_str(password), from_c_str(username))
+    else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::ChangeMailAddress {
+            old_mail: old_mail.to_string(),
+            new_mail: new_mail.to_string(),
+            password: password.to_string(),
+            username: username.to_string(),
+        },
+    )
+}
+
+/// Sends a command, that has no proper wrapper. `arguments` is a list of
+/// `arguments_len` strings, which will be joined with '/'
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `cmd_name` has to be null, or a valid, nul terminated string
+/// - `arguments` has to be null, or point to `arguments_len` valid, nul terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn exec_Custom(session: *mut GameSession, cmd_name: *const i8, arguments: *const *const i8, arguments_len: usize) -> *mut Response {
+    let Some(cmd_name) = from_c_str(cmd_name) else {
+        return invalid_argument();
+    };
+    let raw_args = if arguments.is_null() {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(arguments, arguments_len) }
+    };
+    let Some(arguments) = raw_args.iter().map(|a| from_c_str(*a).map(str::to_string)).collect() else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::Custom { cmd_name: cmd_name.to_string(), arguments })
+}
+
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_CollectDailyQuestReward(session: *mut GameSession, pos: usize) -> *mut Response {
+    execute_command(session, Command::CollectDailyQuestReward { pos })
+}
+
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_CollectEventTaskReward(session: *mut GameSession, pos: usize) -> *mut Response {
+    execute_command(session, Command::CollectEventTaskReward { pos })
+}
+
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_FightTower(session: *mut GameSession, current_level: u8, use_mush: bool) -> *mut Response {
+    execute_command(session, Command::FightTower { current_level, use_mush })
+}
+
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_PetFeed(session: *mut GameSession, pet_id: u32, fruit_idx: u32) -> *mut Response {
+    execute_command(session, Command::PetFeed { pet_id, fruit_idx })
+}
+
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_UnderworldAttack(session: *mut GameSession, player_id: u32) -> *mut Response {
+    execute_command(session, Command::UnderworldAttack { player_id })
+}
+
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_SetPortraitFrame(session: *mut GameSession, portrait_id: i64) -> *mut Response {
+    execute_command(session, Command::SetPortraitFrame { portrait_id })
+}
+
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `message` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn exec_GuildSendChat(session: *mut GameSession, message: *const i8) -> *mut Response {
+    let Some(message) = from_c_str(message) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::GuildSendChat { message: message.to_string() })
+}
+
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `description` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn exec_SetDescription(session: *mut GameSession, description: *const i8) -> *mut Response {
+    let Some(description) = from_c_str(description) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::SetDescription { description: description.to_string() })
+}
+
+/// The emblem is the raw server encoded string, that `Emblem::server_encode`
+/// returns
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `description` has to be null, or a valid, nul terminated string
+/// - `emblem` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn exec_GuildSetInfo(session: *mut GameSession, description: *const i8, emblem: *const i8) -> *mut Response {
+    let (Some(description), Some(raw_emblem)) = (from_c_str(description), from_c_str(emblem)) else {
+        return invalid_argument();
+    };
+    let mut emblem = Emblem::default();
+    emblem.update(raw_emblem);
+    execute_command(session, Command::GuildSetInfo { description: description.to_string(), emblem })
+}
+
+/// Sets the flag to the country code (like "de"). Null, or an empty string
+/// removes the flag
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `flag` has to be null, or a valid, nul terminated string
+#[no_mangle]
+pub unsafe extern "C" fn exec_UpdateFlag(session: *mut GameSession, flag: *const i8) -> *mut Response {
+    let flag = from_c_str(flag).and_then(Flag::parse);
+    execute_command(session, Command::UpdateFlag { flag })
+}
+
 
 // #########################################################################
 // #                 COMMANDS WITH CUSTOM ENUMS/STRUCTS                    #
 // #########################################################################
 
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_UnlockFeature(session: *mut Session, main_ident: i64, sub_ident: i64) -> *mut Response {
+pub unsafe extern "C" fn exec_UnlockFeature(session: *mut GameSession, main_ident: i64, sub_ident: i64) -> *mut Response {
     execute_command(session,
         Command::UnlockFeature {
             unlockable: Unlockable { main_ident, sub_ident }
@@ -667,175 +3890,833 @@ pub extern "C" fn exec_UnlockFeature(session: *mut Session, main_ident: i64, sub
     )
 }
 
+/// Returns null, if `f_type` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressBuild(session: *mut Session, f_type: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressBuild(session: *mut GameSession, f_type: u8) -> *mut Response {
+    let Some(f_type) = FortressBuildingType::from_usize(f_type as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::FortressBuild {
-            f_type: FortressBuildingType::from_usize(f_type as usize).unwrap_or(FortressBuildingType::Fortress),
+            f_type,
         },
     )
 }
 
+/// Returns null, if `f_type` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressBuildCancel(session: *mut Session, f_type: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressBuildCancel(session: *mut GameSession, f_type: u8) -> *mut Response {
+    let Some(f_type) = FortressBuildingType::from_usize(f_type as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::FortressBuildCancel {
-            f_type: FortressBuildingType::from_usize(f_type as usize).unwrap_or(FortressBuildingType::Fortress),
+            f_type,
         },
     )
 }
 
+/// Returns null, if `f_type` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressBuildFinish(session: *mut Session, f_type: u8, mushrooms: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressBuildFinish(session: *mut GameSession, f_type: u8, mushrooms: u32) -> *mut Response {
+    let Some(f_type) = FortressBuildingType::from_usize(f_type as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::FortressBuildFinish {
-            f_type: FortressBuildingType::from_usize(f_type as usize).unwrap_or(FortressBuildingType::Fortress),
+            f_type,
             mushrooms,
         },
     )
 }
 
+/// Returns null, if `unit` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressBuildUnit(session: *mut Session, unit: u8, count: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressBuildUnit(session: *mut GameSession, unit: u8, count: u32) -> *mut Response {
+    let Some(unit) = FortressUnitType::from_usize(unit as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::FortressBuildUnit {
-            unit: FortressUnitType::from_usize(unit as usize).unwrap_or(FortressUnitType::Soldier),
+            unit,
             count,
         },
     )
 }
 
+/// Returns null, if `resource` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_FortressGather(session: *mut Session, resource: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_FortressGather(session: *mut GameSession, resource: u8) -> *mut Response {
+    let Some(resource) = FortressResourceType::from_usize(resource as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::FortressGather {
-            resource: FortressResourceType::from_usize(resource as usize).unwrap_or(FortressResourceType::Wood),
+            resource,
         },
     )
 }
 
+/// Returns null, if `resource` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_UnderworldCollect(session: *mut Session, resource: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_UnderworldCollect(session: *mut GameSession, resource: u8) -> *mut Response {
+    let Some(resource) = UnderWorldResourceType::from_usize(resource as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::UnderworldCollect {
-            resource: UnderWorldResourceType::from_usize(resource as usize).unwrap_or(UnderWorldResourceType::Souls),
+            resource,
         },
     )
 }
 
+/// Returns null, if `unit` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_UnderworldUnitUpgrade(session: *mut Session, unit: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_UnderworldUnitUpgrade(session: *mut GameSession, unit: u8) -> *mut Response {
+    let Some(unit) = UnderworldUnitType::from_usize(unit as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::UnderworldUnitUpgrade {
-            unit: UnderworldUnitType::from_usize(unit as usize).unwrap_or(UnderworldUnitType::Goblin),
+            unit,
         },
     )
 }
 
+/// Returns null, if `building` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_UnderworldUpgradeCancel(session: *mut Session, building: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_UnderworldUpgradeCancel(session: *mut GameSession, building: u8) -> *mut Response {
+    let Some(building) = UnderworldUnitType::from_usize(building as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::UnderworldUpgradeCancel {
-            building: UnderworldUnitType::from_usize(building as usize).unwrap_or(UnderworldUnitType::Goblin),
+            building,
         },
     )
 }
 
+/// Returns null, if `building` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_UnderworldUpgradeFinish(session: *mut Session, building: u8, mushrooms: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_UnderworldUpgradeFinish(session: *mut GameSession, building: u8, mushrooms: u32) -> *mut Response {
+    let Some(building) = UnderworldBuildingType::from_usize(building as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::UnderworldUpgradeFinish {
-            building: UnderworldBuildingType::from_usize(building as usize).unwrap_or(UnderworldBuildingType::HeartOfDarkness),
+            building,
             mushrooms,
         },
     )
 }
 
+/// Returns null, if `payment` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_SpinWheelOfFortune(session: *mut Session, payment: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_SpinWheelOfFortune(session: *mut GameSession, payment: u8) -> *mut Response {
+    let Some(payment) = FortunePayment::from_usize(payment as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::SpinWheelOfFortune {
-            payment: FortunePayment::from_usize(payment as usize).unwrap_or(FortunePayment::LuckyCoins),
+            payment,
         },
     )
 }
 
 
 
+/// Returns null, if `mount` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_BuyMount(session: *mut Session, mount: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_BuyMount(session: *mut GameSession, mount: u8) -> *mut Response {
+    let Some(mount) = Mount::from_usize(mount as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::BuyMount {
-            mount: Mount::from_usize(mount as usize).unwrap_or(Mount::Cow),
+            mount,
         },
     )
 }
 
+/// Returns null, if `attribute` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_IncreaseAttribute(session: *mut Session, attribute: u8, increase_to: u32) -> *mut Response {
+pub unsafe extern "C" fn exec_IncreaseAttribute(session: *mut GameSession, attribute: u8, increase_to: u32) -> *mut Response {
+    let Some(attribute) = AttributeType::from_usize(attribute as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::IncreaseAttribute {
-            attribute: AttributeType::from_usize(attribute as usize).unwrap_or(AttributeType::Strength),
+            attribute,
             increase_to,
         },
     )
 }
 
+/// Returns null, if `skill` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_GuildIncreaseSkill(session: *mut Session, skill: u8, current: u16) -> *mut Response {
+pub unsafe extern "C" fn exec_GuildIncreaseSkill(session: *mut GameSession, skill: u8, current: u16) -> *mut Response {
+    let Some(skill) = GuildSkill::from_usize(skill as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::GuildIncreaseSkill {
-            skill: GuildSkill::from_usize(skill as usize).unwrap_or(GuildSkill::Treasure),
+            skill,
             current,
         },
     )
 }
 
+/// Returns null, if `inventory` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ToiletDrop(session: *mut Session, inventory: u8, pos: usize) -> *mut Response {
+pub unsafe extern "C" fn exec_ToiletDrop(session: *mut GameSession, inventory: u8, pos: usize) -> *mut Response {
+    let Some(inventory) = PlayerItemPlace::from_usize(inventory as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::ToiletDrop {
-            inventory: PlayerItemPlace::from_usize(inventory as usize).unwrap_or(PlayerItemPlace::Equipment),
+            inventory,
             pos,
         },
     )
 }
 
+/// Returns null, if `typ` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_ExpeditionSkipWait(session: *mut Session, typ: u8) -> *mut Response {
+pub unsafe extern "C" fn exec_ExpeditionSkipWait(session: *mut GameSession, typ: u8) -> *mut Response {
+    let Some(typ) = TimeSkip::from_usize(typ as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::ExpeditionSkipWait {
-            typ: TimeSkip::from_usize(typ as usize).unwrap_or(TimeSkip::Mushroom),
+            typ,
         },
     )
 }
 
+/// Returns null, if `typ` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
 #[no_mangle]
-pub extern "C" fn exec_HellevatorBuy(session: *mut Session, position: usize, typ: u8, price: u32, use_mushroom: bool) -> *mut Response {
+pub unsafe extern "C" fn exec_HellevatorBuy(session: *mut GameSession, position: usize, typ: u8, price: u32, use_mushroom: bool) -> *mut Response {
+    let Some(typ) = HellevatorTreatType::from_usize(typ as usize) else {
+        return invalid_argument();
+    };
     execute_command(
         session,
         Command::HellevatorBuy {
             position,
-            typ: HellevatorTreatType::from_usize(typ as usize).unwrap_or(HellevatorTreatType::ChocolateChilliPepper),
+            typ,
             price,
             use_mushroom,
         },
     )
 }
 
+/// Returns null, if `gender`, `race` or `class` is invalid
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `username` has to be null, or a valid, nul terminated string
+/// - `password` has to be null, or a valid, nul terminated string
+#[no_mangle]
+#[allow(deprecated)]
+pub unsafe extern "C" fn exec_Register(session: *mut GameSession, username: *const i8, password: *const i8, gender: u8, race: u8, class: u8) -> *mut Response {
+    let Some(gender) = Gender::from_u8(gender) else {
+        return invalid_argument();
+    };
+    let Some(race) = Race::from_u8(race) else {
+        return invalid_argument();
+    };
+    let Some(class) = Class::from_u8(class) else {
+        return invalid_argument();
+    };
+    let (Some(username), Some(password)) = (from_c_str(username), from_c_str(password)) else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::Register {
+            username: username.to_string(),
+            password: password.to_string(),
+            gender,
+            race,
+            class,
+        },
+    )
+}
+
+/// Returns null, if `attribute` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_UpgradeSkill(session: *mut GameSession, attribute: u8, next_attribute: u32) -> *mut Response {
+    let Some(attribute) = AttributeType::from_usize(attribute as usize) else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::UpgradeSkill {
+            attribute,
+            next_attribute,
+        },
+    )
+}
+
+/// Returns null, if `building` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_UnderworldUpgradeStart(session: *mut GameSession, building: u8, mushrooms: u32) -> *mut Response {
+    let Some(building) = UnderworldBuildingType::from_usize(building as usize) else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::UnderworldUpgradeStart {
+            building,
+            mushrooms,
+        },
+    )
+}
+
+/// Returns null, if `value` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_SetQuestsInsteadOfExpeditions(session: *mut GameSession, value: u8) -> *mut Response {
+    let Some(value) = ExpeditionSetting::from_usize(value as usize) else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::SetQuestsInsteadOfExpeditions {
+            value,
+        },
+    )
+}
+
+/// Returns null, if `relation` is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_SetPlayerRelation(session: *mut GameSession, player_id: u32, relation: i8) -> *mut Response {
+    let Some(relation) = Relationship::from_i8(relation) else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::SetPlayerRelation {
+            player_id,
+            relation,
+        },
+    )
+}
+
+/// Returns null, if the building type is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_IdleUpgrade(session: *mut GameSession, typ: u8, amount: u64) -> *mut Response {
+    let Some(typ) = IdleBuildingType::from_u8(typ) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::IdleUpgrade { typ, amount })
+}
+
+/// Returns null, if the habitat is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_FightPetOpponent(session: *mut GameSession, habitat: u8, opponent_id: u32) -> *mut Response {
+    let Some(habitat) = HabitatType::from_u8(habitat) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::FightPetOpponent { habitat, opponent_id })
+}
+
+/// Returns null, if the habitat is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_FightPetDungeon(session: *mut GameSession, use_mush: bool, habitat: u8, enemy_pos: u32, player_pet_id: u32) -> *mut Response {
+    let Some(habitat) = HabitatType::from_u8(habitat) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::FightPetDungeon { use_mush, habitat, enemy_pos, player_pet_id })
+}
+
+/// Moves an item from the inventory (2, or 5) onto a companion (0-2). Returns
+/// null, if any of the values are invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_EquipCompanion(session: *mut GameSession, from_inventory: u8, from_pos: u8, to_companion: u8, to_slot: u8) -> *mut Response {
+    let (Some(from_inventory), Some(to_companion), Some(to_slot)) = (
+        InventoryType::from_u8(from_inventory),
+        CompanionClass::from_u8(to_companion),
+        EquipmentSlot::from_u8(to_slot),
+    ) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::EquipCompanion { from_inventory, from_pos, to_companion, to_slot })
+}
+
+/// Returns null, if one of the places is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_ItemMove(session: *mut GameSession, from: u8, from_pos: usize, to: u8, to_pos: usize) -> *mut Response {
+    let (Some(from), Some(to)) = (ItemPlace::from_u8(from), ItemPlace::from_u8(to)) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::ItemMove { from, from_pos, to, to_pos })
+}
+
+/// Returns null, if one of the places is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_SwapRunes(session: *mut GameSession, from: u8, from_pos: usize, to: u8, to_pos: usize) -> *mut Response {
+    let (Some(from), Some(to)) = (ItemPlace::from_u8(from), ItemPlace::from_u8(to)) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::SwapRunes { from, from_pos, to, to_pos })
+}
+
+/// Returns null, if the place is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_UsePotion(session: *mut GameSession, from: u8, from_pos: usize) -> *mut Response {
+    let Some(from) = ItemPlace::from_u8(from) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::UsePotion { from, from_pos })
+}
+
+/// Returns null, if the place is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_ChangeItemLook(session: *mut GameSession, inv: u8, pos: usize, raw_model_id: u16) -> *mut Response {
+    let Some(inv) = ItemPlace::from_u8(inv) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::ChangeItemLook { inv, pos, raw_model_id })
+}
+
+/// Enchants the matching item with the given `Enchantment`. The character
+/// specific `EnchantmentIdent` is taken from the witch in the game state, so
+/// this returns null, if the enchantment has not been unlocked yet
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_WitchEnchant(session: *mut GameSession, enchantment: u8) -> *mut Response {
+    if !session.is_null() && busy_flag(session).load(Ordering::Acquire) {
+        return session_busy();
+    }
+    let enchantment = Enchantment::from_u8(enchantment).and_then(|enchantment| {
+        let witch = idle_session(session)?.gamestate.as_ref()?.witch.as_ref()?;
+        *witch.enchantments.get(enchantment)
+    });
+    let Some(enchantment) = enchantment else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::WitchEnchant { enchantment })
+}
+
+/// `inventory_from` and `inventory_to` are `CPlayerItemPlace` values. Returns
+/// null, if one of them is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_InventoryMove(session: *mut GameSession, inventory_from: u8, inventory_from_pos: usize, inventory_to: u8, inventory_to_pos: usize) -> *mut Response {
+    let (Some(inventory_from), Some(inventory_to)) = (
+        from_raw::<CPlayerItemPlace, _>(inventory_from),
+        from_raw::<CPlayerItemPlace, _>(inventory_to),
+    ) else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::InventoryMove {
+            inventory_from,
+            inventory_from_pos,
+            inventory_to,
+            inventory_to_pos,
+        },
+    )
+}
+
+/// `shop_type` is a `CShopType` and `inventory` a `CPlayerItemPlace` value.
+/// Returns null, if one of them is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_BuyShop(session: *mut GameSession, shop_type: u8, shop_pos: usize, inventory: u8, inventory_pos: usize) -> *mut Response {
+    let (Some(shop_type), Some(inventory)) = (
+        from_raw::<CShopType, _>(shop_type),
+        from_raw::<CPlayerItemPlace, _>(inventory),
+    ) else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::BuyShop {
+            shop_type,
+            shop_pos,
+            inventory,
+            inventory_pos,
+        },
+    )
+}
+
+/// `inventory` is a `CPlayerItemPlace` value. Returns null, if it is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_SellShop(session: *mut GameSession, inventory: u8, inventory_pos: usize) -> *mut Response {
+    let Some(inventory) = from_raw::<CPlayerItemPlace, _>(inventory) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::SellShop { inventory, inventory_pos })
+}
+
+/// `shop` is a `CShopType` value. Returns null, if it is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_RefreshShop(session: *mut GameSession, shop: u8) -> *mut Response {
+    let Some(shop) = from_raw::<CShopType, _>(shop) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::RefreshShop { shop })
+}
+
+/// `inventory_t` is a `CPlayerItemPlace` value. Returns null, if it is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_WitchDropCauldron(session: *mut GameSession, inventory_t: u8, position: usize) -> *mut Response {
+    let Some(inventory_t) = from_raw::<CPlayerItemPlace, _>(inventory_t) else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::WitchDropCauldron { inventory_t, position })
+}
+
+/// `inventory_t` is a `CPlayerItemPlace` and `action` a `CBlacksmithAction`
+/// value. Returns null, if one of them is invalid
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_Blacksmith(session: *mut GameSession, inventory_t: u8, position: u8, action: u8) -> *mut Response {
+    let (Some(inventory_t), Some(action)) = (
+        from_raw::<CPlayerItemPlace, _>(inventory_t),
+        from_raw::<CBlacksmithAction, _>(action),
+    ) else {
+        return invalid_argument();
+    };
+    execute_command(
+        session,
+        Command::Blacksmith {
+            inventory_t,
+            position,
+            action,
+        },
+    )
+}
+
+/// Returns null, if the dungeon does not exist
+///
+/// # Safety
+/// `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+#[no_mangle]
+pub unsafe extern "C" fn exec_FightDungeon(session: *mut GameSession, dungeon: CDungeon, use_mushroom: bool) -> *mut Response {
+    let Some(dungeon) = dungeon.to_dungeon() else {
+        return invalid_argument();
+    };
+    execute_command(session, Command::FightDungeon { dungeon, use_mushroom })
+}
+
+/// `payment` is a `CRollDicePrice` value and `dices` has to point to the 5
+/// raw `DiceType` values. Returns null, if one of them is invalid
+///
+/// # Safety
+/// - `session` has to be null, or a valid `GameSession`, that is not used anywhere else during this call
+/// - `dices` has to be null, or point to 5 readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn exec_RollDice(session: *mut GameSession, payment: u8, dices: *const u8) -> *mut Response {
+    let Some(payment) = from_raw::<CRollDicePrice, _>(payment) else {
+        return invalid_argument();
+    };
+    if dices.is_null() {
+        return invalid_argument();
+    }
+    let raw_dices = unsafe { &*dices.cast::<[u8; 5]>() };
+    let mut dice_types = [DiceType::ReRoll; 5];
+    for (dice, raw) in dice_types.iter_mut().zip(raw_dices) {
+        let Some(typ) = DiceType::from_u8(*raw) else {
+            return invalid_argument();
+        };
+        *dice = typ;
+    }
+    execute_command(session, Command::RollDice { payment, dices: dice_types })
+}
+
 
+// ########################################################
+// #                 FFI BINDING COVERAGE                 #
+// ########################################################
+
+/// Every `Command` variant has to be reachable from C++. This match fails to
+/// compile, once a new variant is added without an `exec_` function for it
+#[allow(dead_code, deprecated)]
+fn ffi_binding(command: &Command) -> *const () {
+    match command {
+        Command::Custom { .. } => exec_Custom as *const (),
+        Command::Login { .. } => exec_Login as *const (),
+        #[cfg(feature = "sso")]
+        Command::SSOLogin { .. } => exec_SSOLogin as *const (),
+        Command::Register { .. } => exec_Register as *const (),
+        Command::Update => exec_Update as *const (),
+        Command::HallOfFamePage { .. } => exec_HallOfFamePage as *const (),
+        Command::HallOfFameFortressPage { .. } => exec_HallOfFameFortressPage as *const (),
+        Command::ViewPlayer { .. } => exec_ViewPlayer as *const (),
+        Command::BuyBeer => exec_BuyBeer as *const (),
+        Command::StartQuest { .. } => exec_StartQuest as *const (),
+        Command::CancelQuest => exec_CancelQuest as *const (),
+        Command::FinishQuest { .. } => exec_FinishQuest as *const (),
+        Command::StartWork { .. } => exec_StartWork as *const (),
+        Command::CancelWork => exec_CancelWork as *const (),
+        Command::FinishWork => exec_FinishWork as *const (),
+        Command::CheckNameAvailable { .. } => exec_CheckNameAvailable as *const (),
+        Command::BuyMount { .. } => exec_BuyMount as *const (),
+        Command::IncreaseAttribute { .. } => exec_IncreaseAttribute as *const (),
+        Command::RemovePotion { .. } => exec_RemovePotion as *const (),
+        Command::CheckArena => exec_CheckArena as *const (),
+        Command::Fight { .. } => exec_Fight as *const (),
+        Command::CollectCalendar => exec_CollectCalendar as *const (),
+        Command::ViewGuild { .. } => exec_ViewGuild as *const (),
+        Command::GuildFound { .. } => exec_GuildFound as *const (),
+        Command::GuildInvitePlayer { .. } => exec_GuildInvitePlayer as *const (),
+        Command::GuildKickPlayer { .. } => exec_GuildKickPlayer as *const (),
+        Command::GuildSetLeader { .. } => exec_GuildSetLeader as *const (),
+        Command::GuildToggleOfficer { .. } => exec_GuildToggleOfficer as *const (),
+        Command::GuildLoadMushrooms => exec_GuildLoadMushrooms as *const (),
+        Command::GuildIncreaseSkill { .. } => exec_GuildIncreaseSkill as *const (),
+        Command::GuildJoinAttack => exec_GuildJoinAttack as *const (),
+        Command::GuildJoinDefense => exec_GuildJoinDefense as *const (),
+        Command::GuildAttack { .. } => exec_GuildAttack as *const (),
+        Command::GuildRaid => exec_GuildRaid as *const (),
+        Command::GuildPortalBattle => exec_GuildPortalBattle as *const (),
+        Command::GuildGetFightableTargets => exec_GuildGetFightableTargets as *const (),
+        Command::ToiletFlush => exec_ToiletFlush as *const (),
+        Command::ToiletOpen => exec_ToiletOpen as *const (),
+        Command::ToiletDrop { .. } => exec_ToiletDrop as *const (),
+        Command::BuyShop { .. } => exec_BuyShop as *const (),
+        Command::SellShop { .. } => exec_SellShop as *const (),
+        Command::InventoryMove { .. } => exec_InventoryMove as *const (),
+        Command::ItemMove { .. } => exec_ItemMove as *const (),
+        Command::UsePotion { .. } => exec_UsePotion as *const (),
+        Command::MessageOpen { .. } => exec_MessageOpen as *const (),
+        Command::MessageDelete { .. } => exec_MessageDelete as *const (),
+        Command::ViewScrapbook => exec_ViewScrapbook as *const (),
+        Command::ViewPet { .. } => exec_ViewPet as *const (),
+        Command::UnlockFeature { .. } => exec_UnlockFeature as *const (),
+        Command::FightPortal => exec_FightPortal as *const (),
+        Command::FightDungeon { .. } => exec_FightDungeon as *const (),
+        Command::FightTower { .. } => exec_FightTower as *const (),
+        Command::FightPetOpponent { .. } => exec_FightPetOpponent as *const (),
+        Command::FightPetDungeon { .. } => exec_FightPetDungeon as *const (),
+        Command::GuildSetInfo { .. } => exec_GuildSetInfo as *const (),
+        Command::GambleSilver { .. } => exec_GambleSilver as *const (),
+        Command::GambleMushrooms { .. } => exec_GambleMushrooms as *const (),
+        Command::SendMessage { .. } => exec_SendMessage as *const (),
+        Command::SetDescription { .. } => exec_SetDescription as *const (),
+        Command::WitchDropCauldron { .. } => exec_WitchDropCauldron as *const (),
+        Command::Blacksmith { .. } => exec_Blacksmith as *const (),
+        Command::GuildSendChat { .. } => exec_GuildSendChat as *const (),
+        Command::WitchEnchant { .. } => exec_WitchEnchant as *const (),
+        Command::SpinWheelOfFortune { .. } => exec_SpinWheelOfFortune as *const (),
+        Command::CollectEventTaskReward { .. } => exec_CollectEventTaskReward as *const (),
+        Command::CollectDailyQuestReward { .. } => exec_CollectDailyQuestReward as *const (),
+        Command::EquipCompanion { .. } => exec_EquipCompanion as *const (),
+        Command::FortressGather { .. } => exec_FortressGather as *const (),
+        Command::FortressGatherSecretStorage { .. } => exec_FortressGatherSecretStorage as *const (),
+        Command::FortressBuild { .. } => exec_FortressBuild as *const (),
+        Command::FortressBuildCancel { .. } => exec_FortressBuildCancel as *const (),
+        Command::FortressBuildFinish { .. } => exec_FortressBuildFinish as *const (),
+        Command::FortressBuildUnit { .. } => exec_FortressBuildUnit as *const (),
+        Command::FortressGemStoneSearch => exec_FortressGemStoneSearch as *const (),
+        Command::FortressGemStoneSearchCancel => exec_FortressGemStoneSearchCancel as *const (),
+        Command::FortressGemStoneSearchFinish { .. } => exec_FortressGemStoneSearchFinish as *const (),
+        Command::FortressAttack { .. } => exec_FortressAttack as *const (),
+        Command::FortressNewEnemy { .. } => exec_FortressNewEnemy as *const (),
+        Command::FortressSetCAEnemy { .. } => exec_FortressSetCAEnemy as *const (),
+        Command::FortressUpgradeHallOfKnights => exec_FortressUpgradeHallOfKnights as *const (),
+        Command::Whisper { .. } => exec_Whisper as *const (),
+        Command::UnderworldCollect { .. } => exec_UnderworldCollect as *const (),
+        Command::UnderworldUnitUpgrade { .. } => exec_UnderworldUnitUpgrade as *const (),
+        Command::UnderworldUpgradeStart { .. } => exec_UnderworldUpgradeStart as *const (),
+        Command::UnderworldUpgradeCancel { .. } => exec_UnderworldUpgradeCancel as *const (),
+        Command::UnderworldUpgradeFinish { .. } => exec_UnderworldUpgradeFinish as *const (),
+        Command::UnderworldAttack { .. } => exec_UnderworldAttack as *const (),
+        Command::RollDice { .. } => exec_RollDice as *const (),
+        Command::PetFeed { .. } => exec_PetFeed as *const (),
+        Command::GuildPetBattle { .. } => exec_GuildPetBattle as *const (),
+        Command::IdleUpgrade { .. } => exec_IdleUpgrade as *const (),
+        Command::IdleSacrifice => exec_IdleSacrifice as *const (),
+        Command::UpgradeSkill { .. } => exec_UpgradeSkill as *const (),
+        Command::RefreshShop { .. } => exec_RefreshShop as *const (),
+        Command::HallOfFameGroupPage { .. } => exec_HallOfFameGroupPage as *const (),
+        Command::HallOfFameUnderworldPage { .. } => exec_HallOfFameUnderworldPage as *const (),
+        Command::HallOfFamePetsPage { .. } => exec_HallOfFamePetsPage as *const (),
+        Command::SwapManequin => exec_SwapManequin as *const (),
+        Command::UpdateFlag { .. } => exec_UpdateFlag as *const (),
+        Command::BlockGuildInvites { .. } => exec_BlockGuildInvites as *const (),
+        Command::ShowTips { .. } => exec_ShowTips as *const (),
+        Command::ChangePassword { .. } => exec_ChangePassword as *const (),
+        Command::ChangeMailAddress { .. } => exec_ChangeMailAddress as *const (),
+        Command::SetLanguage { .. } => exec_SetLanguage as *const (),
+        Command::SetPlayerRelation { .. } => exec_SetPlayerRelation as *const (),
+        Command::SetPortraitFrame { .. } => exec_SetPortraitFrame as *const (),
+        Command::SwapRunes { .. } => exec_SwapRunes as *const (),
+        Command::ChangeItemLook { .. } => exec_ChangeItemLook as *const (),
+        Command::ExpeditionPickEncounter { .. } => exec_ExpeditionPickEncounter as *const (),
+        Command::ExpeditionContinue => exec_ExpeditionContinue as *const (),
+        Command::ExpeditionPickReward { .. } => exec_ExpeditionPickReward as *const (),
+        Command::ExpeditionStart { .. } => exec_ExpeditionStart as *const (),
+        Command::ExpeditionSkipWait { .. } => exec_ExpeditionSkipWait as *const (),
+        Command::SetQuestsInsteadOfExpeditions { .. } => exec_SetQuestsInsteadOfExpeditions as *const (),
+        Command::HellevatorEnter => exec_HellevatorEnter as *const (),
+        Command::HellevatorViewGuildRanking => exec_HellevatorViewGuildRanking as *const (),
+        Command::HellevatorFight { .. } => exec_HellevatorFight as *const (),
+        Command::HellevatorBuy { .. } => exec_HellevatorBuy as *const (),
+        Command::HellevatorRefreshShop => exec_HellevatorRefreshShop as *const (),
+        Command::HellevatorJoinHellAttack { .. } => exec_HellevatorJoinHellAttack as *const (),
+        Command::HellevatorClaimDaily => exec_HellevatorClaimDaily as *const (),
+        Command::HellevatorClaimDailyYesterday => exec_HellevatorClaimDailyYesterday as *const (),
+        Command::HellevatorClaimFinal => exec_HellevatorClaimFinal as *const (),
+        Command::HellevatorPreviewRewards => exec_HellevatorPreviewRewards as *const (),
+        Command::HallOfFameHellevatorPage { .. } => exec_HallOfFameHellevatorPage as *const (),
+        Command::ClaimablePreview { .. } => exec_ClaimablePreview as *const (),
+        Command::ClaimableClaim { .. } => exec_ClaimableClaim as *const (),
+        Command::BuyGoldFrame => exec_BuyGoldFrame as *const (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_read_only_commands_are_resent() {
+        let policy = CRecoveryPolicy::default();
+        let lost = SFError::ConnectionError;
+        let expired = SFError::ServerError("sessionid invalid".to_string());
+        let changing = [
+            Command::BuyBeer,
+            Command::GambleMushrooms { amount: 1 },
+            Command::Fight { name: "a".to_string(), use_mushroom: true },
+        ];
+        for command in &changing {
+            assert_eq!(policy.recovery_for(command, &lost), None);
+            assert_eq!(policy.recovery_for(command, &SFError::EmptyResponse), None);
+            assert_eq!(policy.recovery_for(command, &expired), None);
+        }
+        let view = Command::ViewPlayer { ident: "a".to_string() };
+        assert_eq!(policy.recovery_for(&Command::Update, &lost), Some(false));
+        assert_eq!(policy.recovery_for(&view, &SFError::EmptyResponse), Some(true));
+        assert_eq!(policy.recovery_for(&view, &expired), Some(true));
+        let no_relogin = CRecoveryPolicy { relogin: false, ..policy };
+        assert_eq!(no_relogin.recovery_for(&view, &expired), None);
+    }
+
+    #[test]
+    fn session_expiry_needs_the_exact_error() {
+        assert!(is_session_expired("sessionid invalid"));
+        assert!(is_session_expired("Session expired"));
+        assert!(!is_session_expired("sessionid invalid for guild"));
+        assert!(!is_session_expired("need more silver"));
+    }
+}
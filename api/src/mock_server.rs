@@ -0,0 +1,953 @@
+use std::{
+    collections::HashSet,
+    fmt::Write,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use enum_map::EnumMap;
+use log::{trace, warn};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{
+    command::{AttributeType, ShopType},
+    gamestate::{
+        character::{Class, Race},
+        items::{EquipmentSlot, PlayerItemPlace},
+        unlockables::{EquipmentIdent, ScrapBook, ScrapBookLayout},
+    },
+    misc::{
+        decrypt_server_request, sha1_hash, DEFAULT_CRYPTO_ID,
+        DEFAULT_CRYPTO_KEY, DEFAULT_SESSION_ID,
+    },
+    session::PWHash,
+    transport::{Transport, TransportFuture, TransportRequest},
+    PlayerId,
+};
+
+/// The length of the player save the mock server sends for the own character
+const PLAYER_SAVE_LEN: usize = 760;
+/// The length of the player save the mock server sends for other players
+const OTHER_PLAYER_LEN: usize = 260;
+/// The amount of players the server returns for one `HallOfFamePage`
+const HOF_PAGE_SIZE: usize = 51;
+/// The honor a fight in the arena is worth
+const FIGHT_HONOR: u32 = 100;
+/// The amount of values every fighter has in the fight header
+const FIGHTER_LEN: usize = 47;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An item in the world of the `MockServer`
+pub struct MockItem {
+    pub ident: EquipmentIdent,
+    /// The price in silver
+    pub price: u32,
+    /// The price in mushrooms
+    pub mushroom_price: u32,
+}
+
+impl MockItem {
+    #[must_use]
+    pub fn new(ident: EquipmentIdent, price: u32) -> Self {
+        Self {
+            ident,
+            price,
+            mushroom_price: 0,
+        }
+    }
+
+    /// A cheap ring, that is used for empty shop slots
+    #[must_use]
+    pub fn filler() -> Self {
+        let ident = EquipmentIdent {
+            class: None,
+            typ: EquipmentSlot::Ring,
+            model_id: 1,
+            color: 1,
+        };
+        Self::new(ident, 10)
+    }
+
+    /// The item, that is stored at this position in the current scrapbook
+    /// layout
+    #[must_use]
+    pub fn from_scrapbook_index(index: usize, price: u32) -> Option<Self> {
+        let ident = ScrapBookLayout::current().item_ident(index)?;
+        Some(Self::new(ident, price))
+    }
+
+    /// Encodes the item the same way the server does, so that `Item::parse`
+    /// gives us the same `EquipmentIdent` back
+    fn encode(&self) -> [i64; 12] {
+        let ident = &self.ident;
+        let typ = match ident.typ {
+            EquipmentSlot::Weapon => 1,
+            EquipmentSlot::Shield => 2,
+            EquipmentSlot::BreastPlate => 3,
+            EquipmentSlot::FootWear => 4,
+            EquipmentSlot::Gloves => 5,
+            EquipmentSlot::Hat => 6,
+            EquipmentSlot::Belt => 7,
+            EquipmentSlot::Amulet => 8,
+            EquipmentSlot::Ring => 9,
+            EquipmentSlot::Talisman => 10,
+        };
+        let class = ident.class.map_or(0, |a| a as i64);
+        let mut res = [0; 12];
+        res[0] = typ;
+        res[1] = class * 1000 + i64::from(ident.model_id);
+        // Fixed stats, so that the simulator has something to work with
+        match ident.typ {
+            EquipmentSlot::Weapon => {
+                res[2] = 10;
+                res[3] = 20;
+            }
+            EquipmentSlot::Shield => res[2] = 25,
+            _ => {}
+        }
+        // The color is derived from the sum of these values, so we nudge the
+        // max damage (unused for everything else) to get the right one
+        let sum: i64 = res[2..=9].iter().sum();
+        res[3] +=
+            (i64::from(ident.color.saturating_sub(1)) - sum).rem_euclid(5);
+        res[10] = i64::from(self.price);
+        res[11] = i64::from(self.mushroom_price);
+        res
+    }
+}
+
+fn encode_items<'a>(
+    items: impl Iterator<Item = Option<&'a MockItem>>,
+    data: &mut [i64],
+) {
+    for (item, target) in items.zip(data.chunks_mut(12)) {
+        let encoded = item.map(MockItem::encode).unwrap_or_default();
+        for (target, value) in target.iter_mut().zip(encoded) {
+            *target = value;
+        }
+    }
+}
+
+fn encode_attributes(
+    attributes: &EnumMap<AttributeType, u32>,
+    data: &mut [i64],
+) {
+    for (target, value) in data.iter_mut().zip(attributes.values()) {
+        *target = i64::from(*value);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A character in the world of the `MockServer`
+pub struct MockPlayer {
+    pub player_id: PlayerId,
+    pub name: String,
+    pub level: u16,
+    pub class: Class,
+    pub race: Race,
+    pub honor: u32,
+    pub guild: Option<String>,
+    pub attributes: EnumMap<AttributeType, u32>,
+    pub equipment: EnumMap<EquipmentSlot, Option<MockItem>>,
+}
+
+impl MockPlayer {
+    #[must_use]
+    pub fn new(
+        player_id: PlayerId,
+        name: &str,
+        level: u16,
+        class: Class,
+    ) -> Self {
+        Self {
+            player_id,
+            name: name.to_string(),
+            level,
+            class,
+            race: Race::Human,
+            honor: 100,
+            guild: None,
+            attributes: EnumMap::from_fn(|_| u32::from(level) * 10),
+            equipment: EnumMap::default(),
+        }
+    }
+
+    /// Equips the item in the slot, that it belongs to
+    pub fn equip(&mut self, item: MockItem) {
+        self.equipment[item.ident.typ] = Some(item);
+    }
+
+    fn encode_other(&self, rank: u32) -> [i64; OTHER_PLAYER_LEN] {
+        let mut data = [0; OTHER_PLAYER_LEN];
+        data[0] = i64::from(self.player_id);
+        data[2] = i64::from(self.level);
+        data[5] = i64::from(self.honor);
+        data[6] = i64::from(rank);
+        data[18] = self.race as i64;
+        data[20] = self.class as i64 + 1;
+        encode_attributes(&self.attributes, &mut data[21..]);
+        encode_items(
+            self.equipment.values().map(Option::as_ref),
+            &mut data[39..],
+        );
+        data
+    }
+
+    /// The life this player starts fights with
+    fn life(&self) -> i64 {
+        let constitution = self.attributes[AttributeType::Constitution];
+        (i64::from(constitution) * (i64::from(self.level) + 1)).max(1)
+    }
+
+    fn encode_fighter(&self) -> String {
+        let mut data = [0; FIGHTER_LEN];
+        data[5] = i64::from(self.player_id);
+        data[7] = i64::from(self.level);
+        data[8] = self.life();
+        encode_attributes(&self.attributes, &mut data[10..15]);
+        data[27] = self.class as i64 + 1;
+
+        let mut res = String::new();
+        for (pos, value) in data.iter().enumerate() {
+            if pos > 0 {
+                res.push('/');
+            }
+            if pos == 6 {
+                res.push_str(&self.name);
+            } else {
+                _ = write!(res, "{value}");
+            }
+        }
+        res
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The character, that logs into the `MockServer`
+pub struct MockCharacter {
+    pub player: MockPlayer,
+    pub password: String,
+    pub silver: u64,
+    pub mushrooms: u32,
+    pub inventory: [Option<MockItem>; 5],
+    /// The server never sends empty shop slots, so every slot has an item
+    pub shops: EnumMap<ShopType, [MockItem; 6]>,
+    /// The items, that replace bought items in the shops. If there are none
+    /// left, the shop just offers the bought item again
+    pub restock: Vec<MockItem>,
+    pub scrapbook: ScrapBook,
+}
+
+impl MockCharacter {
+    /// Creates a character with an empty inventory. The shops only sell
+    /// `MockItem::filler()`. The scrapbook only contains the first monster,
+    /// because the server does not send empty scrapbooks
+    #[must_use]
+    pub fn new(player: MockPlayer, password: &str) -> Self {
+        Self {
+            player,
+            password: password.to_string(),
+            silver: 0,
+            mushrooms: 0,
+            inventory: Default::default(),
+            shops: EnumMap::from_fn(|_| [MockItem::filler(); 6]),
+            restock: Vec::new(),
+            scrapbook: ScrapBook {
+                items: HashSet::new(),
+                monster: HashSet::from([1]),
+                layout_version: ScrapBookLayout::current().version,
+                unknown: Vec::new(),
+            },
+        }
+    }
+
+    fn encode_save(&self, rank: u32) -> [i64; PLAYER_SAVE_LEN] {
+        let p = &self.player;
+        let mut data = [0; PLAYER_SAVE_LEN];
+        data[1] = i64::from(p.player_id);
+        data[7] = i64::from(p.level);
+        data[10] = i64::from(p.honor);
+        data[11] = i64::from(rank);
+        data[27] = p.race as i64;
+        data[29] = p.class as i64 + 1;
+        encode_attributes(&p.attributes, &mut data[30..]);
+        encode_items(p.equipment.values().map(Option::as_ref), &mut data[48..]);
+        encode_items(
+            self.inventory.iter().map(Option::as_ref),
+            &mut data[168..],
+        );
+        encode_items(
+            self.shops[ShopType::Weapon].iter().map(Some),
+            &mut data[288..],
+        );
+        encode_items(
+            self.shops[ShopType::Magic].iter().map(Some),
+            &mut data[361..],
+        );
+        data
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Everything the `MockServer` knows about. You can change this at any time
+/// through `MockServer::world()` to script the behaviour of the server
+pub struct MockWorld {
+    pub character: MockCharacter,
+    /// Every other player on the server
+    pub players: Vec<MockPlayer>,
+}
+
+impl MockWorld {
+    #[must_use]
+    pub fn new(character: MockCharacter) -> Self {
+        Self {
+            character,
+            players: Vec::new(),
+        }
+    }
+
+    /// A world with `player_count` other players, which all have a few
+    /// items equipped, and shops, that sell items the character does not
+    /// have yet. The character is called `mock` and has the password `mock`.
+    /// Everything is derived from the player count, so the same count always
+    /// gives the same world
+    #[must_use]
+    pub fn sample(player_count: u32) -> Self {
+        let layout = ScrapBookLayout::current();
+        let mut items = (layout.monsters.end..=layout.len())
+            .filter_map(|a| MockItem::from_scrapbook_index(a, 0))
+            .step_by(37);
+
+        let player = MockPlayer::new(1, "mock", 100, Class::Warrior);
+        let mut character = MockCharacter::new(player, "mock");
+        character.silver = 10_000;
+        character.scrapbook.items.insert(MockItem::filler().ident);
+        for shop in character.shops.values_mut() {
+            for slot in shop.iter_mut().take(3) {
+                if let Some(item) = items.next() {
+                    *slot = MockItem { price: 500, ..item };
+                }
+            }
+        }
+
+        let mut world = Self::new(character);
+        for idx in 0..player_count {
+            let class = Class::Mage;
+            let level = 50 + u16::try_from(idx * 3 % 100).unwrap_or(0);
+            let mut player =
+                MockPlayer::new(idx + 2, &format!("player{idx}"), level, class);
+            player.honor = 10_000 - idx * 10;
+            for _ in 0..idx % 4 {
+                if let Some(item) = items.next() {
+                    player.equip(item);
+                }
+            }
+            world.players.push(player);
+        }
+        world
+    }
+
+    /// All players, including the own character, sorted by their rank in
+    /// the Hall of Fame
+    fn ranked(&self) -> Vec<&MockPlayer> {
+        let mut res: Vec<_> = self
+            .players
+            .iter()
+            .chain([&self.character.player])
+            .collect();
+        res.sort_by(|a, b| {
+            b.honor.cmp(&a.honor).then(a.player_id.cmp(&b.player_id))
+        });
+        res
+    }
+
+    fn rank(&self, player_id: PlayerId) -> u32 {
+        self.ranked()
+            .iter()
+            .position(|a| a.player_id == player_id)
+            .and_then(|a| u32::try_from(a + 1).ok())
+            .unwrap_or(0)
+    }
+
+    /// Finds a player by their name, or id. This includes the own character
+    fn player_by_ident(&self, ident: &str) -> Option<&MockPlayer> {
+        self.players
+            .iter()
+            .chain([&self.character.player])
+            .find(|a| {
+                a.name.eq_ignore_ascii_case(ident)
+                    || a.player_id.to_string() == ident
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MockLogin {
+    session_id: String,
+    crypto_id: String,
+    crypto_key: String,
+    login_count: u32,
+}
+
+#[derive(Debug)]
+/// A stand in for the S&F server, that only knows about a `MockWorld` in
+/// memory. It speaks the real protocol, so sessions have to log in and send
+/// encrypted commands, but only `Login`, `Update`, `ViewPlayer`,
+/// `HallOfFamePage`, `ViewScrapbook`, `Fight` and the shop commands are
+/// understood. Everything else gets a server error.
+///
+/// You can either hand this to a session directly as its `Transport`, or
+/// serve it over HTTP with `serve()` to test things, that create their own
+/// connections
+pub struct MockServer {
+    world: Mutex<MockWorld>,
+    login: Mutex<Option<MockLogin>>,
+}
+
+impl MockServer {
+    #[must_use]
+    pub fn new(world: MockWorld) -> Self {
+        Self {
+            world: Mutex::new(world),
+            login: Mutex::new(None),
+        }
+    }
+
+    /// The world of this server. Changes are visible to the next command
+    #[allow(clippy::missing_panics_doc)]
+    pub fn world(&self) -> MutexGuard<'_, MockWorld> {
+        self.world
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn login_state(&self) -> MutexGuard<'_, Option<MockLogin>> {
+        self.login
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Invalidates the current session, like a server restart, or someone
+    /// else logging in would. Every further command, except a login, fails
+    /// with a `sessionid invalid` server error
+    pub fn expire_session(&self) {
+        // The crypto id & key stay valid, so that we can still read the
+        // commands and respond with the proper error
+        if let Some(login) = self.login_state().as_mut() {
+            login.session_id = random_string(DEFAULT_SESSION_ID.len());
+        }
+    }
+
+    /// Accepts connections on the listener and answers every request to
+    /// `req.php` forever. Use `http://<addr>/` as the server url
+    ///
+    /// # Errors
+    /// If the listener could not accept a connection
+    pub async fn serve(
+        self: Arc<Self>,
+        listener: TcpListener,
+    ) -> std::io::Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.handle_connection(stream).await {
+                    warn!("Mock server connection failed: {e}");
+                }
+            });
+        }
+    }
+
+    async fn handle_connection(
+        &self,
+        stream: TcpStream,
+    ) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).await? == 0 {
+                return Ok(());
+            }
+            // We do not care about any of the headers
+            loop {
+                let mut header = String::new();
+                if reader.read_line(&mut header).await? == 0 {
+                    return Ok(());
+                }
+                if header.trim().is_empty() {
+                    break;
+                }
+            }
+
+            let target = request_line.split_whitespace().nth(1).unwrap_or("");
+            let body = self.handle_url(target);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\
+                 Content-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            reader.get_mut().write_all(response.as_bytes()).await?;
+        }
+    }
+
+    /// Answers a request to `req.php` and returns the body of the response.
+    /// The url can either be the full url, or just the path & query
+    #[must_use]
+    pub fn handle_url(&self, url: &str) -> String {
+        let req = url
+            .split_once('?')
+            .map_or("", |a| a.1)
+            .split('&')
+            .find_map(|a| a.strip_prefix("req="))
+            .unwrap_or("");
+
+        match self.decrypt(req) {
+            Some((session_id, command)) => {
+                trace!("Mock server received {command}");
+                let (name, args) =
+                    command.split_once(':').unwrap_or((&command, ""));
+                self.handle_command(&session_id, name, args)
+            }
+            None => "error:invalid request".to_string(),
+        }
+    }
+
+    /// Decrypts the request into the session id and the command
+    fn decrypt(&self, req: &str) -> Option<(String, String)> {
+        let crypto_id = req.get(..DEFAULT_CRYPTO_ID.len())?;
+        let encrypted = req.get(DEFAULT_CRYPTO_ID.len()..)?;
+        let key = match self.login_state().as_ref() {
+            Some(login) if login.crypto_id == crypto_id => {
+                login.crypto_key.clone()
+            }
+            _ if crypto_id == DEFAULT_CRYPTO_ID => {
+                DEFAULT_CRYPTO_KEY.to_string()
+            }
+            _ => return None,
+        };
+        let decrypted = decrypt_server_request(encrypted, &key).ok()?;
+        let (session_id, command) = decrypted.split_once('|')?;
+        Some((
+            session_id.to_string(),
+            command.trim_end_matches('|').to_string(),
+        ))
+    }
+
+    fn handle_command(
+        &self,
+        session_id: &str,
+        name: &str,
+        args: &str,
+    ) -> String {
+        let args: Vec<_> = args.split('/').collect();
+        if name == "AccountLogin" {
+            return self.login(&args);
+        }
+
+        let logged_in = self
+            .login_state()
+            .as_ref()
+            .is_some_and(|a| a.session_id == session_id);
+        if !logged_in {
+            return "error:sessionid invalid".to_string();
+        }
+
+        let mut world = self.world();
+        let world = &mut *world;
+        let arg = |pos: usize| args.get(pos).copied().unwrap_or("");
+        let num = |pos: usize| arg(pos).parse::<usize>().unwrap_or(0);
+        let res = match name {
+            // The scrapbook is part of every response anyways
+            "Poll" | "PlayerPollScrapbook" => Ok(String::new()),
+            "PlayerLookAt" => view_player(world, arg(0)),
+            "PlayerGetHallOfFame" => Ok(hall_of_fame(world, num(0))),
+            "PlayerArenaFight" => fight(world, arg(0)),
+            "PlayerItemMove" => {
+                move_item(world, num(0), num(1), num(2), num(3))
+            }
+            "PlayerNewWares" => new_wares(world, num(0) + 2),
+            _ => Err("request not supported by the mock server"),
+        };
+        match res {
+            Ok(body) => format!("{body}{}", own_state(world)),
+            Err(e) => format!("error:{e}"),
+        }
+    }
+
+    fn login(&self, args: &[&str]) -> String {
+        let world = self.world();
+        let character = &world.character;
+        let (Some(name), Some(hash), Some(login_count)) = (
+            args.first(),
+            args.get(1),
+            args.get(2).and_then(|a| a.parse::<u32>().ok()),
+        ) else {
+            return "error:invalid login".to_string();
+        };
+        let pw_hash = PWHash::new(&character.password);
+        let expected = sha1_hash(&format!("{}{login_count}", pw_hash.get()));
+        if !name.eq_ignore_ascii_case(&character.player.name)
+            || *hash != expected
+        {
+            return "error:wrong pass".to_string();
+        }
+
+        let login = MockLogin {
+            session_id: random_string(DEFAULT_SESSION_ID.len()),
+            crypto_id: random_string(DEFAULT_CRYPTO_ID.len()),
+            crypto_key: random_string(DEFAULT_CRYPTO_KEY.len()),
+            login_count: login_count + 1,
+        };
+        let res = format!(
+            "serverversion:2005&sessionid:{}&cryptoid:{}&cryptokey:{}&login \
+             count:{}&ownplayername.r:{}{}",
+            login.session_id,
+            login.crypto_id,
+            login.crypto_key,
+            login.login_count,
+            character.player.name,
+            own_state(&world),
+        );
+        *self.login_state() = Some(login);
+        res
+    }
+}
+
+impl Transport for MockServer {
+    fn send<'a>(
+        &'a self,
+        request: TransportRequest<'a>,
+    ) -> TransportFuture<'a> {
+        Box::pin(std::future::ready(Ok(self.handle_url(request.url))))
+    }
+}
+
+fn random_string(len: usize) -> String {
+    std::iter::repeat_with(fastrand::alphanumeric)
+        .take(len)
+        .collect()
+}
+
+fn join(data: &[i64]) -> String {
+    let mut res = String::new();
+    for value in data {
+        res.push_str(&value.to_string());
+        res.push('/');
+    }
+    res
+}
+
+/// The part of the response, that the server sends after every command
+fn own_state(world: &MockWorld) -> String {
+    let character = &world.character;
+    let rank = world.rank(character.player.player_id);
+    let mut resources = [0; 17];
+    resources[1] = i64::from(character.mushrooms);
+    resources[2] = i64::try_from(character.silver).unwrap_or(i64::MAX);
+    format!(
+        "&ownplayersave.playerSave:{}&resources:{}&scrapbook.r:{}&maxrank:{}\
+         &timestamp:{}",
+        join(&character.encode_save(rank)),
+        join(&resources),
+        character.scrapbook.to_server_string(),
+        world.ranked().len(),
+        chrono::Local::now().naive_local().and_utc().timestamp(),
+    )
+}
+
+fn view_player(world: &MockWorld, ident: &str) -> Result<String, &'static str> {
+    let player = world.player_by_ident(ident).ok_or("player not found")?;
+    let rank = world.rank(player.player_id);
+    let mut res = format!(
+        "otherplayername.r:{}&otherplayer.playerlookat:{}",
+        player.name,
+        join(&player.encode_other(rank))
+    );
+    if let Some(guild) = &player.guild {
+        res.push_str("&otherplayergroupname.r:");
+        res.push_str(guild);
+    }
+    Ok(res)
+}
+
+/// The page of the Hall of Fame, that is centered around `pos`
+fn hall_of_fame(world: &MockWorld, pos: usize) -> String {
+    let start = pos.saturating_sub(HOF_PAGE_SIZE / 2 + 1);
+    let mut res = String::from("Ranklistplayer.r:");
+    let ranked = world.ranked();
+    let page = ranked.iter().skip(start).take(HOF_PAGE_SIZE);
+    for (rank, player) in (start + 1..).zip(page) {
+        _ = write!(
+            res,
+            "{},{},{},{},{},{},;",
+            rank,
+            player.name,
+            player.guild.as_deref().unwrap_or(""),
+            player.level,
+            player.honor,
+            player.class as i64 + 1,
+        );
+    }
+    res
+}
+
+fn fight(world: &mut MockWorld, name: &str) -> Result<String, &'static str> {
+    let own_id = world.character.player.player_id;
+    let rank_pre = world.rank(own_id);
+    let own_level = world.character.player.level;
+    let player = world
+        .players
+        .iter_mut()
+        .find(|a| a.name.eq_ignore_ascii_case(name))
+        .ok_or("player not found")?;
+
+    // We do not simulate anything here. The higher level always wins
+    let won = own_level >= player.level;
+    let winner_id = if won { own_id } else { player.player_id };
+    let header = format!(
+        "{}/{}",
+        world.character.player.encode_fighter(),
+        player.encode_fighter()
+    );
+    let rounds = fight_rounds(&world.character.player, player, won);
+    let honor = if won {
+        let honor = player.honor.min(FIGHT_HONOR);
+        player.honor -= honor;
+        world.character.player.honor += honor;
+        world
+            .character
+            .scrapbook
+            .items
+            .extend(player.equipment.values().flatten().map(|a| a.ident));
+        i64::from(honor)
+    } else {
+        0
+    };
+
+    let mut result = [0; 21];
+    result[0] = i64::from(won);
+    result[5] = honor;
+    result[7] = i64::from(rank_pre);
+    result[8] = i64::from(world.rank(own_id));
+    let rounds: Vec<_> = rounds.iter().map(ToString::to_string).collect();
+    Ok(format!(
+        "fightheader:{header}&fight:{}&fightresult.battlereward:{}\
+         &winnerid:{winner_id}&fightversion:2",
+        rounds.join(","),
+        join(&result)
+    ))
+}
+
+/// Lets both players hit each other in turns, starting with `own`, until the
+/// loser has no life left. The rounds are in the layout of fight version 2
+fn fight_rounds(own: &MockPlayer, other: &MockPlayer, won: bool) -> Vec<i64> {
+    let (winner, loser) = if won { (own, other) } else { (other, own) };
+    let winner_id = i64::from(winner.player_id);
+    let loser_id = i64::from(loser.player_id);
+    let mut winner_life = winner.life();
+    let mut loser_life = loser.life();
+    let mut winner_turn = won;
+    let mut rounds = Vec::new();
+
+    for turn in 1.. {
+        if loser_life <= 0 {
+            break;
+        }
+        if winner_turn {
+            // Every third attack of the winner is a critical hit. It never
+            // needs more than four hits
+            let critical = turn % 3 == 0;
+            let mut damage = loser.life() / 4 + 1;
+            if critical {
+                damage *= 2;
+            }
+            loser_life = (loser_life - damage).max(0);
+            rounds.extend([
+                winner_id,
+                loser_id,
+                i64::from(critical),
+                i64::from(critical),
+                damage,
+                loser_life,
+                winner_life,
+                0,
+            ]);
+        } else if turn % 4 == 0 {
+            rounds.extend([
+                loser_id,
+                winner_id,
+                4,
+                0,
+                0,
+                winner_life,
+                loser_life,
+                0,
+            ]);
+        } else {
+            let damage = winner.life() / 10 + 1;
+            winner_life = (winner_life - damage).max(1);
+            rounds.extend([
+                loser_id,
+                winner_id,
+                0,
+                0,
+                damage,
+                winner_life,
+                loser_life,
+                0,
+            ]);
+        }
+        winner_turn = !winner_turn;
+    }
+    rounds
+}
+
+fn shop(raw: usize) -> Option<ShopType> {
+    match raw {
+        3 => Some(ShopType::Weapon),
+        4 => Some(ShopType::Magic),
+        _ => None,
+    }
+}
+
+/// Replaces the items in the shop with the next items from the restock list.
+/// Slots, for which there is nothing left to restock, keep their item
+fn new_wares(
+    world: &mut MockWorld,
+    raw: usize,
+) -> Result<String, &'static str> {
+    let character = &mut world.character;
+    let shop_type = shop(raw).ok_or("invalid shop")?;
+    for slot in &mut character.shops[shop_type] {
+        if character.restock.is_empty() {
+            break;
+        }
+        *slot = character.restock.remove(0);
+    }
+    Ok(String::new())
+}
+
+/// Moves an item between a shop and the inventory. These are the raw
+/// positions from the command, so they start at 1
+fn move_item(
+    world: &mut MockWorld,
+    from: usize,
+    from_pos: usize,
+    to: usize,
+    to_pos: usize,
+) -> Result<String, &'static str> {
+    let character = &mut world.character;
+    let is_inventory =
+        |raw: usize| raw == PlayerItemPlace::MainInventory as usize;
+    match (shop(from), is_inventory(to), is_inventory(from), shop(to)) {
+        // Buying an item
+        (Some(shop_type), true, _, _) => {
+            let slot = to_pos
+                .checked_sub(1)
+                .and_then(|a| character.inventory.get_mut(a))
+                .ok_or("invalid inventory pos")?;
+            if slot.is_some() {
+                return Err("inventory slot is not empty");
+            }
+            let shop_slot = from_pos
+                .checked_sub(1)
+                .and_then(|a| character.shops[shop_type].get_mut(a))
+                .ok_or("invalid shop pos")?;
+            let item = *shop_slot;
+            if character.silver < u64::from(item.price)
+                || character.mushrooms < item.mushroom_price
+            {
+                return Err("need more gold");
+            }
+            character.silver -= u64::from(item.price);
+            character.mushrooms -= item.mushroom_price;
+            if !character.restock.is_empty() {
+                *shop_slot = character.restock.remove(0);
+            }
+            *slot = Some(item);
+            character.scrapbook.items.insert(item.ident);
+        }
+        // Selling an item. The shop position does not matter
+        (_, _, true, Some(_)) => {
+            let item = from_pos
+                .checked_sub(1)
+                .and_then(|a| character.inventory.get_mut(a))
+                .ok_or("invalid inventory pos")?
+                .take()
+                .ok_or("no item to sell")?;
+            character.silver += u64::from(item.price);
+        }
+        _ => return Err("item move not supported by the mock server"),
+    }
+    Ok(String::new())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        error::SFError,
+        session::{ServerConnection, Session, SimpleSession},
+    };
+
+    /// A session of the `mock` character, that talks to a server with the
+    /// given world. It logs in with the first command
+    pub(crate) fn mock_session(
+        world: MockWorld,
+    ) -> (Arc<MockServer>, SimpleSession) {
+        let server = Arc::new(MockServer::new(world));
+        let connection = ServerConnection::new("http://mock.local/").unwrap();
+        let mut session = Session::new("mock", "mock", connection);
+        session.set_transport(server.clone());
+        (server, SimpleSession::new(session))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn won_fight() {
+        let (server, mut session) = mock_session(MockWorld::sample(10));
+        let target = server.world().players[3].clone();
+        let outcome = session.fight(&target.name, false).await.unwrap();
+        assert!(outcome.won);
+        assert_eq!(outcome.honor_change, i64::from(FIGHT_HONOR));
+        assert_eq!(outcome.rank_before, 11);
+        assert_eq!(outcome.rank_after, 11);
+
+        let gs = session.game_state().unwrap();
+        let fight = &gs.last_fight.as_ref().unwrap().fights[0];
+        assert_eq!(fight.winner_id, 1);
+        let other = fight.fighter_b.as_ref().unwrap();
+        assert_eq!(other.name.as_deref(), Some(target.name.as_str()));
+        assert!(!fight.actions.is_empty());
+        // Winning gives us the items of the other player
+        let scrapbook = gs.character.scrapbook.as_ref().unwrap();
+        for item in target.equipment.values().flatten() {
+            assert!(scrapbook.items.contains(&item.ident));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lost_fight() {
+        let mut world = MockWorld::sample(0);
+        world
+            .players
+            .push(MockPlayer::new(2, "strong", 300, Class::Mage));
+        let (server, mut session) = mock_session(world);
+        let outcome = session.fight("strong", true).await.unwrap();
+        assert!(!outcome.won);
+        assert_eq!(outcome.honor_change, 0);
+
+        let gs = session.game_state().unwrap();
+        let fight = &gs.last_fight.as_ref().unwrap().fights[0];
+        assert_eq!(fight.winner_id, 2);
+        assert_eq!(server.world().character.player.honor, 100);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fight_unknown_player() {
+        let (_, mut session) = mock_session(MockWorld::sample(3));
+        let res = session.fight("nobody", false).await;
+        assert!(matches!(res, Err(SFError::ServerError(_))));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{
+    command::Command,
+    gamestate::{
+        arena::Fight,
+        items::Item,
+        social::{HallOfFamePlayer, OtherPlayer},
+        tavern::GambleResult,
+        GameState,
+    },
+    PlayerId,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The result of a fight from the perspective of the own character
+pub struct FightOutcome {
+    pub won: bool,
+    /// The honor won, or lost in this fight
+    pub honor_change: i64,
+    /// The silver won, or lost in this fight
+    pub silver: i64,
+    pub experience: u64,
+    pub mushrooms: u8,
+    /// The rank in the Hall of Fame before this fight
+    pub rank_before: u32,
+    /// The rank in the Hall of Fame after this fight
+    pub rank_after: u32,
+    /// The item this fight gave the character, if any
+    pub item: Option<Item>,
+}
+
+impl From<&Fight> for FightOutcome {
+    fn from(fight: &Fight) -> Self {
+        Self {
+            won: fight.has_player_won,
+            honor_change: fight.honor_change,
+            silver: fight.silver_change,
+            experience: fight.xp_change,
+            mushrooms: fight.mushroom_change,
+            rank_before: fight.rank_pre_fight,
+            rank_after: fight.rank_post_fight,
+            item: fight.item_won.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The direct result of a command. Everything in here is also somewhere in
+/// the `GameState`, but you would have to know where to look for it
+pub enum CommandOutcome {
+    /// The result of any kind of fight, like `Fight`, `FightDungeon`, or
+    /// `FightTower`
+    Fight(FightOutcome),
+    /// The player looked at with `ViewPlayer`
+    Player(Box<OtherPlayer>),
+    /// The result of `GambleSilver`, or `GambleMushrooms`
+    Gamble(GambleResult),
+    /// The players on the page requested with `HallOfFamePage`
+    HallOfFame(Vec<HallOfFamePlayer>),
+}
+
+impl CommandOutcome {
+    /// Picks the result of the command out of the game state. The game state
+    /// has to be updated with the response to exactly this command
+    /// beforehand. Returns None, if the command has no dedicated outcome, or
+    /// the response did not contain it
+    #[must_use]
+    pub fn from_state(command: &Command, gs: &GameState) -> Option<Self> {
+        Some(match command {
+            Command::Fight { .. }
+            | Command::FightPortal
+            | Command::FightDungeon { .. }
+            | Command::FightTower { .. }
+            | Command::FightPetOpponent { .. }
+            | Command::FightPetDungeon { .. }
+            | Command::GuildPetBattle { .. }
+            | Command::UnderworldAttack { .. }
+            | Command::FortressAttack { .. }
+            | Command::HellevatorFight { .. } => {
+                CommandOutcome::Fight(gs.last_fight.as_ref()?.into())
+            }
+            Command::ViewPlayer { ident } => {
+                let player = gs.lookup.lookup_name(ident).or_else(|| {
+                    gs.lookup.lookup_pid(ident.parse::<PlayerId>().ok()?)
+                })?;
+                CommandOutcome::Player(Box::new(player.clone()))
+            }
+            Command::GambleSilver { .. } | Command::GambleMushrooms { .. } => {
+                CommandOutcome::Gamble(gs.tavern.gamble_result?)
+            }
+            Command::HallOfFamePage { .. } => {
+                CommandOutcome::HallOfFame(gs.hall_of_fames.players.clone())
+            }
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gamestate::character::Class, response::Response};
+
+    fn outcome(command: &Command, body: &str) -> Option<CommandOutcome> {
+        let now = chrono::Local::now().naive_local();
+        let resp = Response::parse(body.to_string(), now).unwrap();
+        let mut gs = GameState::default();
+        gs.update(&resp).unwrap();
+        CommandOutcome::from_state(command, &gs)
+    }
+
+    fn join(values: &[i64]) -> String {
+        let values: Vec<_> = values.iter().map(|a| a.to_string()).collect();
+        values.join("/")
+    }
+
+    #[test]
+    fn fight() {
+        // Won, silver, xp, mushrooms, honor, _, rank before, rank after and
+        // no item
+        let mut result = [0; 21];
+        result[..9].copy_from_slice(&[1, 0, 250, 1200, 1, 100, 0, 42, 40]);
+        let body = format!("fightresult.battlereward:{}", join(&result));
+        let command = Command::Fight {
+            name: "a".to_string(),
+            use_mushroom: false,
+        };
+        let Some(CommandOutcome::Fight(fight)) = outcome(&command, &body)
+        else {
+            panic!("No fight outcome");
+        };
+        assert_eq!(
+            fight,
+            FightOutcome {
+                won: true,
+                honor_change: 100,
+                silver: 250,
+                experience: 1200,
+                mushrooms: 1,
+                rank_before: 42,
+                rank_after: 40,
+                item: None,
+            }
+        );
+
+        // The underworld only sends the winner and the silver
+        let body = "fightresult.battlereward:0/0/-30";
+        let command = Command::UnderworldAttack { player_id: 1 };
+        let Some(CommandOutcome::Fight(fight)) = outcome(&command, body) else {
+            panic!("No fight outcome");
+        };
+        assert!(!fight.won);
+        assert_eq!(fight.silver, -30);
+        assert_eq!(fight.honor_change, 0);
+    }
+
+    #[test]
+    fn player_lookup() {
+        let mut player = [0; 256];
+        // Id, level, honor, rank, race & class (Mage)
+        for (pos, val) in [(0, 1337), (2, 85), (5, 4200), (6, 17)] {
+            player[pos] = val;
+        }
+        player[18] = 1;
+        player[20] = 2;
+        let body = format!(
+            "otherplayername.r:Somebody&otherplayer.playerlookat:{}",
+            join(&player)
+        );
+        for ident in ["Somebody", "1337"] {
+            let command = Command::ViewPlayer {
+                ident: ident.to_string(),
+            };
+            let Some(CommandOutcome::Player(player)) = outcome(&command, &body)
+            else {
+                panic!("No player outcome for {ident}");
+            };
+            assert_eq!(player.player_id, 1337);
+            assert_eq!(player.name, "Somebody");
+            assert_eq!(player.level, 85);
+            assert_eq!(player.honor, 4200);
+            assert_eq!(player.rank, 17);
+            assert_eq!(player.class, Class::Mage);
+        }
+
+        let command = Command::ViewPlayer {
+            ident: "Nobody".to_string(),
+        };
+        assert!(outcome(&command, &body).is_none());
+    }
+
+    #[test]
+    fn gambling() {
+        let command = Command::GambleSilver { amount: 100 };
+        assert!(matches!(
+            outcome(&command, "gamblegoldvalue:-100"),
+            Some(CommandOutcome::Gamble(GambleResult::SilverChange(-100)))
+        ));
+        let command = Command::GambleMushrooms { amount: 1 };
+        assert!(matches!(
+            outcome(&command, "gamblecoinvalue:2"),
+            Some(CommandOutcome::Gamble(GambleResult::MushroomChange(2)))
+        ));
+        // Nothing to pick, if the response is missing the result
+        assert!(outcome(&command, "timestamp:0").is_none());
+    }
+
+    #[test]
+    fn hall_of_fame_page() {
+        // Rank, name, guild, level, honor, class & flag. The empty player
+        // ends the page
+        let body = "Ranklistplayer.r:1,First,Guild,300,9000,1,de;\
+                    2,Second,,250,8000,3,;,,,0,0,0,;";
+        let command = Command::HallOfFamePage { page: 0 };
+        let Some(CommandOutcome::HallOfFame(players)) = outcome(&command, body)
+        else {
+            panic!("No Hall of Fame outcome");
+        };
+        assert_eq!(players.len(), 2);
+        assert_eq!(players[0].rank, 1);
+        assert_eq!(players[0].name, "First");
+        assert_eq!(players[0].guild.as_deref(), Some("Guild"));
+        assert_eq!(players[0].class, Class::Warrior);
+        assert_eq!(players[1].name, "Second");
+        assert_eq!(players[1].guild, None);
+        assert_eq!(players[1].level, 250);
+        assert_eq!(players[1].honor, 8000);
+        assert_eq!(players[1].class, Class::Scout);
+    }
+
+    #[test]
+    fn no_outcome() {
+        assert!(outcome(&Command::Update, "timestamp:0").is_none());
+    }
+}
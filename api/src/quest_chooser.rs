@@ -0,0 +1,338 @@
+use crate::{
+    command::Command,
+    gamestate::{
+        tavern::{AvailableTasks, Quest},
+        unlockables::{ScrapBook, ScrapBookLayout},
+        GameState,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Decides how much the different parts of a quest are worth, when rating
+/// them. The xp & silver per second are compared to the best of the three
+/// quests, so a value of 1.0 for `experience` means, that the quest with the
+/// most xp per second gets a full point for that. New scrapbook entries just
+/// get their weight added to the score
+pub struct QuestWeights {
+    pub experience: f64,
+    pub silver: f64,
+    /// Added, if the monster of the quest is not yet in the scrapbook
+    pub new_monster: f64,
+    /// Added, if the item reward of the quest is not yet in the scrapbook
+    pub new_item: f64,
+}
+
+impl Default for QuestWeights {
+    fn default() -> Self {
+        Self {
+            experience: 1.0,
+            silver: 1.0,
+            new_monster: 1.0,
+            new_item: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// How good one of the tavern quests is according to some `QuestWeights`
+pub struct QuestRating {
+    /// The position of the quest in the tavern (0-2)
+    pub quest_pos: usize,
+    pub experience_per_sec: f64,
+    pub silver_per_sec: f64,
+    /// The monster of this quest is not yet in the scrapbook
+    pub new_monster: bool,
+    /// The item reward of this quest is not yet in the scrapbook. This is
+    /// false, if the inventory is full, because the item would be lost
+    pub new_item: bool,
+    /// The character has enough thirst for adventure left to start this
+    /// quest
+    pub startable: bool,
+    /// The final score of the quest. Higher is better
+    pub score: f64,
+}
+
+/// Rates the three tavern quests by their xp & silver per second and by the
+/// new scrapbook entries they would give you. The best quest is at the front.
+/// Returns an empty list, if the tavern currently offers expeditions instead
+/// of quests
+#[must_use]
+pub fn rate_quests(gs: &GameState, weights: &QuestWeights) -> Vec<QuestRating> {
+    let tavern = &gs.tavern;
+    let AvailableTasks::Quests(quests) = tavern.available_tasks() else {
+        return Vec::new();
+    };
+    let scrapbook = gs.character.scrapbook.as_ref();
+    let has_free_slot = gs.character.inventory.free_slot().is_some();
+
+    let mut ratings: Vec<_> = quests
+        .iter()
+        .enumerate()
+        .map(|(quest_pos, quest)| {
+            let length = f64::from(quest.base_length.max(1));
+            QuestRating {
+                quest_pos,
+                experience_per_sec: f64::from(quest.base_experience) / length,
+                silver_per_sec: f64::from(quest.base_silver) / length,
+                new_monster: scrapbook
+                    .is_some_and(|a| is_new_monster(a, quest)),
+                new_item: has_free_slot
+                    && scrapbook.is_some_and(|a| is_new_item(a, quest)),
+                startable: quest.base_length > 0
+                    && quest.base_length <= tavern.thirst_for_adventure_sec,
+                score: 0.0,
+            }
+        })
+        .collect();
+
+    let best =
+        |f: fn(&QuestRating) -> f64| ratings.iter().map(f).fold(0.0, f64::max);
+    let best_xp = best(|a| a.experience_per_sec);
+    let best_silver = best(|a| a.silver_per_sec);
+    let relative = |val: f64, best: f64| {
+        if best > 0.0 {
+            val / best
+        } else {
+            0.0
+        }
+    };
+
+    for rating in &mut ratings {
+        let mut score = weights.experience
+            * relative(rating.experience_per_sec, best_xp)
+            + weights.silver * relative(rating.silver_per_sec, best_silver);
+        if rating.new_monster {
+            score += weights.new_monster;
+        }
+        if rating.new_item {
+            score += weights.new_item;
+        }
+        rating.score = score;
+    }
+    ratings.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ratings
+}
+
+/// Picks the best quest according to `rate_quests()` and gives you the
+/// command to start it. Returns None, if the character is busy, or can not
+/// start any of the quests
+#[must_use]
+pub fn recommend_quest(
+    gs: &GameState,
+    weights: &QuestWeights,
+) -> Option<Command> {
+    if !gs.tavern.is_idle() {
+        return None;
+    }
+    let best = rate_quests(gs, weights).into_iter().find(|a| a.startable)?;
+    Some(Command::StartQuest {
+        quest_pos: best.quest_pos,
+        overwrite_inv: false,
+    })
+}
+
+fn is_new_monster(scrapbook: &ScrapBook, quest: &Quest) -> bool {
+    ScrapBookLayout::current()
+        .monsters
+        .contains(&usize::from(quest.monster_id))
+        && !scrapbook.monster.contains(&quest.monster_id)
+}
+
+fn is_new_item(scrapbook: &ScrapBook, quest: &Quest) -> bool {
+    quest
+        .item
+        .as_ref()
+        .and_then(|a| a.equipment_ident())
+        .is_some_and(|a| !scrapbook.items.contains(&a))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::{Duration, Local};
+    use enum_map::EnumMap;
+
+    use super::*;
+    use crate::{
+        command::ExpeditionSetting,
+        gamestate::{
+            character::Class,
+            items::{Item, ItemType},
+            tavern::CurrentAction,
+        },
+    };
+
+    fn hat(model_id: u16) -> Item {
+        Item {
+            typ: ItemType::Hat,
+            price: 0,
+            mushroom_price: 0,
+            model_id,
+            class: Some(Class::Warrior),
+            type_specific_val: 0,
+            attributes: EnumMap::default(),
+            gem_slot: None,
+            rune: None,
+            enchantment: None,
+            color: 1,
+        }
+    }
+
+    fn quest(length: u32, xp: u32, silver: u32, monster_id: u16) -> Quest {
+        Quest {
+            base_length: length,
+            base_silver: silver,
+            base_experience: xp,
+            monster_id,
+            ..Default::default()
+        }
+    }
+
+    /// Three quests with 10, 5 & 5 xp and 1, 1.5 & 0.5 silver per second.
+    /// Only the first one has an item, all of the monsters are known
+    fn state() -> GameState {
+        let mut gs = GameState::default();
+        gs.tavern.thirst_for_adventure_sec = 6000;
+        gs.tavern.quests = [
+            Quest {
+                item: Some(hat(3)),
+                ..quest(100, 1000, 100, 5)
+            },
+            quest(100, 500, 150, 6),
+            quest(200, 1000, 100, 7),
+        ];
+        gs.character.scrapbook = Some(ScrapBook {
+            items: HashSet::new(),
+            monster: HashSet::from([5, 6, 7]),
+            layout_version: 1,
+            unknown: Vec::new(),
+        });
+        gs
+    }
+
+    const NO_SCRAPBOOK: QuestWeights = QuestWeights {
+        experience: 1.0,
+        silver: 1.0,
+        new_monster: 0.0,
+        new_item: 0.0,
+    };
+
+    fn order(ratings: &[QuestRating]) -> Vec<usize> {
+        ratings.iter().map(|a| a.quest_pos).collect()
+    }
+
+    fn assert_scores(ratings: &[QuestRating], expected: [f64; 3]) {
+        for (rating, expected) in ratings.iter().zip(expected) {
+            assert!((rating.score - expected).abs() < 1e-9, "{rating:?}");
+        }
+    }
+
+    #[test]
+    fn score_ordering() {
+        let ratings = rate_quests(&state(), &NO_SCRAPBOOK);
+        assert_eq!(order(&ratings), [0, 1, 2]);
+        assert_scores(&ratings, [1.0 + 1.0 / 1.5, 1.5, 0.5 + 0.5 / 1.5]);
+        assert!((ratings[0].experience_per_sec - 10.0).abs() < f64::EPSILON);
+        assert!((ratings[1].silver_per_sec - 1.5).abs() < f64::EPSILON);
+
+        // Only silver counts now
+        let silver = QuestWeights {
+            experience: 0.0,
+            ..NO_SCRAPBOOK
+        };
+        let ratings = rate_quests(&state(), &silver);
+        assert_eq!(order(&ratings), [1, 0, 2]);
+    }
+
+    #[test]
+    fn new_scrapbook_entries() {
+        let mut gs = state();
+        if let Some(scrapbook) = &mut gs.character.scrapbook {
+            scrapbook.monster.remove(&7);
+        }
+        let weights = QuestWeights {
+            new_monster: 2.0,
+            new_item: 0.5,
+            ..NO_SCRAPBOOK
+        };
+        let ratings = rate_quests(&gs, &weights);
+        assert_eq!(order(&ratings), [2, 0, 1]);
+        let flags: Vec<_> = ratings
+            .iter()
+            .map(|a| (a.new_monster, a.new_item))
+            .collect();
+        assert_eq!(flags, [(true, false), (false, true), (false, false)]);
+        assert_scores(
+            &ratings,
+            [2.5 + 0.5 / 1.5, 1.0 + 1.0 / 1.5 + 0.5, 0.5 + 1.0],
+        );
+
+        // Once the item is in the scrapbook, there is no bonus anymore
+        let item = hat(3).equipment_ident().unwrap();
+        if let Some(scrapbook) = &mut gs.character.scrapbook {
+            scrapbook.items.insert(item);
+        }
+        let ratings = rate_quests(&gs, &weights);
+        assert!(ratings.iter().all(|a| !a.new_item));
+    }
+
+    #[test]
+    fn no_new_item_with_full_inventory() {
+        let mut gs = state();
+        assert!(rate_quests(&gs, &QuestWeights::default())
+            .iter()
+            .any(|a| a.new_item));
+        gs.character.inventory.bag = core::array::from_fn(|_| Some(hat(1)));
+        assert!(rate_quests(&gs, &QuestWeights::default())
+            .iter()
+            .all(|a| !a.new_item));
+    }
+
+    #[test]
+    fn skip_quests_that_can_not_be_started() {
+        let mut gs = state();
+        assert_eq!(
+            recommend_quest(&gs, &NO_SCRAPBOOK),
+            Some(Command::StartQuest {
+                quest_pos: 0,
+                overwrite_inv: false
+            })
+        );
+
+        // Not enough thirst for the first two and an invalid third one
+        gs.tavern.thirst_for_adventure_sec = 99;
+        gs.tavern.quests[2].base_length = 0;
+        assert!(rate_quests(&gs, &NO_SCRAPBOOK).iter().all(|a| !a.startable));
+        assert_eq!(recommend_quest(&gs, &NO_SCRAPBOOK), None);
+
+        gs.tavern.thirst_for_adventure_sec = 100;
+        gs.tavern.quests[0].base_length = 101;
+        assert_eq!(
+            recommend_quest(&gs, &NO_SCRAPBOOK),
+            Some(Command::StartQuest {
+                quest_pos: 1,
+                overwrite_inv: false
+            })
+        );
+
+        gs.tavern.current_action = CurrentAction::Quest {
+            quest_idx: 1,
+            busy_until: Local::now(),
+        };
+        assert_eq!(recommend_quest(&gs, &NO_SCRAPBOOK), None);
+    }
+
+    #[test]
+    fn expeditions_have_no_quests() {
+        let mut gs = state();
+        let now = Local::now();
+        gs.tavern.questing_preference = ExpeditionSetting::PreferExpeditions;
+        gs.tavern.expeditions.start = Some(now - Duration::hours(1));
+        gs.tavern.expeditions.end = Some(now + Duration::hours(1));
+        assert!(rate_quests(&gs, &QuestWeights::default()).is_empty());
+        assert_eq!(recommend_quest(&gs, &QuestWeights::default()), None);
+    }
+}
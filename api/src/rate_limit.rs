@@ -0,0 +1,233 @@
+use std::{sync::Mutex, time::Duration};
+
+use log::trace;
+// The tokio version follows the clock of the runtime, which can be paused in
+// tests
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The amount of requests, that can be sent in some time frame
+pub struct RateBudget {
+    /// The amount of requests, that can be sent per `interval` on average. 0
+    /// disables the budget
+    pub requests: u32,
+    pub interval: Duration,
+    /// The amount of requests, that can be sent right after each other, after
+    /// nothing has been sent for a while. A burst of 1 spreads all requests
+    /// evenly over the interval
+    pub burst: u32,
+}
+
+impl RateBudget {
+    /// The amount of requests, that can be sent per second
+    fn per_sec(&self) -> f64 {
+        f64::from(self.requests) / self.interval.as_secs_f64()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Decides how fast sessions send their requests to the server. By default,
+/// nothing is limited, so requests are sent as fast as you send them
+pub struct RateLimitOptions {
+    /// The budget of every single session. Clones of a session share this
+    pub per_session: Option<RateBudget>,
+    /// The budget shared by all sessions, that have been created from the
+    /// same `ServerConnection`
+    pub per_server: Option<RateBudget>,
+    /// A random delay between 0 and this is added before every request, so
+    /// that the requests do not arrive at a suspiciously regular pace
+    pub jitter: Duration,
+}
+
+impl RateLimitOptions {
+    /// Limits that should look like a human clicking through the game. Use
+    /// these, if you are about to send thousands of requests, like when
+    /// crawling the Hall of Fame
+    #[must_use]
+    pub fn conservative() -> Self {
+        Self {
+            per_session: Some(RateBudget {
+                requests: 1,
+                interval: Duration::from_secs(3),
+                burst: 5,
+            }),
+            per_server: Some(RateBudget {
+                requests: 1,
+                interval: Duration::from_secs(1),
+                burst: 10,
+            }),
+            jitter: Duration::from_millis(1500),
+        }
+    }
+
+    /// Waits for a random time between 0 and `jitter`
+    pub(crate) async fn wait_jitter(&self) {
+        if self.jitter.is_zero() {
+            return;
+        }
+        let max = u64::try_from(self.jitter.as_millis()).unwrap_or(u64::MAX);
+        let jitter = Duration::from_millis(fastrand::u64(0..=max));
+        tokio::time::sleep(jitter).await;
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// The amount of requests, that can be sent right now. This goes below
+    /// zero, if requests are waiting for their turn
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[derive(Debug)]
+/// Makes sure, that the requests of everyone using this stay within a
+/// `RateBudget`. Requests, that would exceed the budget, have to wait for
+/// their turn in the order they have arrived in. You can share one limiter
+/// between multiple `ServerConnection`s with
+/// `ServerConnection::with_rate_limiter()`, if you want their sessions to
+/// share a budget
+pub struct RateLimiter {
+    budget: RateBudget,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter, that has its full burst available
+    #[must_use]
+    pub fn new(budget: RateBudget) -> Self {
+        Self {
+            budget,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(budget.burst.max(1)),
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// The budget this limiter enforces
+    #[must_use]
+    pub fn budget(&self) -> RateBudget {
+        self.budget
+    }
+
+    /// Reserves the next request and returns the time to wait, before it can
+    /// be sent
+    fn reserve(&self) -> Duration {
+        let per_sec = self.budget.per_sec();
+        if !per_sec.is_finite() || per_sec <= 0.0 {
+            return Duration::ZERO;
+        }
+        let mut bucket = self
+            .bucket
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        let burst = f64::from(self.budget.burst.max(1));
+        bucket.tokens = (bucket.tokens + elapsed * per_sec).min(burst);
+        bucket.last_refill = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / per_sec)
+        }
+    }
+
+    /// Waits until the next request can be sent within the budget. The
+    /// request is counted against the budget, even if this future gets
+    /// dropped before it has finished
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            trace!("Waiting {wait:?} for the rate limit");
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Waits for the budget of the session, before a request of the server
+/// budget is reserved. The other way around, a session, that has used up its
+/// own budget, would hold on to server requests, that other sessions could
+/// have used in the meantime
+pub(crate) async fn acquire_in_order(
+    session: Option<&RateLimiter>,
+    server: Option<&RateLimiter>,
+) {
+    if let Some(limiter) = session {
+        limiter.acquire().await;
+    }
+    if let Some(limiter) = server {
+        limiter.acquire().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn limiter(requests: u32, secs: u64, burst: u32) -> RateLimiter {
+        RateLimiter::new(RateBudget {
+            requests,
+            interval: Duration::from_secs(secs),
+            burst,
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn burst_then_pace() {
+        let limiter = limiter(1, 1, 3);
+        let start = Instant::now();
+        let mut sent = Vec::new();
+        for _ in 0..6 {
+            limiter.acquire().await;
+            sent.push(start.elapsed().as_secs());
+        }
+        assert_eq!(sent, [0, 0, 0, 1, 2, 3]);
+
+        // After a long break, the full burst is available again
+        tokio::time::sleep(Duration::from_mins(1)).await;
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn disabled_budget() {
+        let limiter = limiter(0, 1, 1);
+        let start = Instant::now();
+        for _ in 0..100 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn session_budget_before_server_budget() {
+        let slow_session = limiter(1, 10, 1);
+        let server = Arc::new(limiter(1, 1, 1));
+        let start = Instant::now();
+
+        // Uses up the server request of the first second and then has to
+        // wait for its own budget
+        acquire_in_order(Some(&slow_session), Some(&server)).await;
+        let other_server = server.clone();
+        let other = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            acquire_in_order(None, Some(&other_server)).await;
+            start.elapsed()
+        });
+        acquire_in_order(Some(&slow_session), Some(&server)).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+
+        // The other session got the server request of the next second, even
+        // though the slow one was already waiting
+        assert_eq!(other.await.unwrap(), Duration::from_secs(1));
+    }
+}
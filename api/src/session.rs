@@ -6,7 +6,7 @@ use std::{
 };
 
 use log::{error, trace, warn};
-use reqwest::{header::*, Client};
+use reqwest::header::*;
 use url::Url;
 
 use crate::{
@@ -14,12 +14,17 @@ use crate::{
     error::SFError,
     gamestate::{
         character::{Class, Gender, Race},
+        social::{HallOfFamePlayer, OtherPlayer},
+        tavern::GambleResult,
         GameState,
     },
     misc::{
         encrypt_server_request, sha1_hash, DEFAULT_CRYPTO_ID,
         DEFAULT_CRYPTO_KEY, DEFAULT_SESSION_ID, HASH_CONST,
     },
+    outcome::{CommandOutcome, FightOutcome},
+    rate_limit::{acquire_in_order, RateLimitOptions, RateLimiter},
+    transport::{HttpTransport, Transport, TransportRequest},
 };
 pub use crate::{misc::decrypt_url, response::*};
 
@@ -38,10 +43,12 @@ pub struct Session {
     login_count: u32,
     crypto_id: String,
     crypto_key: String,
-    // We keep this instead of creating a new one, because as per the reqwest
-    // docs: "The Client holds a connection pool internally, so it is advised
-    // that you create one and reuse it."
-    client: reqwest::Client,
+    /// The thing, that actually sends our commands to the server
+    transport: Arc<dyn Transport>,
+    /// Limits the requests of this session (and its clones)
+    limiter: Option<Arc<RateLimiter>>,
+    /// Limits the requests of all sessions from the same `ServerConnection`
+    server_limiter: Option<Arc<RateLimiter>>,
     options: ConnectionOptions,
 }
 
@@ -96,19 +103,32 @@ impl Session {
             username: username.to_string(),
             pw_hash,
         };
-        Self::new_full(ld, server.client, server.options, server.url)
+        Self::new_full(
+            ld,
+            server.transport,
+            server.server_limiter,
+            server.options,
+            server.url,
+        )
     }
 
     fn new_full(
         ld: LoginData,
-        client: Client,
+        transport: Arc<dyn Transport>,
+        server_limiter: Option<Arc<RateLimiter>>,
         options: ConnectionOptions,
         url: Url,
     ) -> Self {
+        let limiter = options
+            .rate_limit
+            .per_session
+            .map(|a| Arc::new(RateLimiter::new(a)));
         Self {
             login_data: ld,
             server_url: url,
-            client,
+            transport,
+            limiter,
+            server_limiter,
             session_id: DEFAULT_SESSION_ID.to_string(),
             crypto_id: DEFAULT_CRYPTO_ID.to_string(),
             crypto_key: DEFAULT_CRYPTO_KEY.to_string(),
@@ -145,6 +165,31 @@ impl Session {
         self.session_id.chars().any(|a| a != '0')
     }
 
+    /// The transport, that sends the commands of this session to the server
+    #[must_use]
+    pub fn transport(&self) -> &Arc<dyn Transport> {
+        &self.transport
+    }
+
+    /// Replaces the transport, that sends the commands of this session to
+    /// the server. This lets you record the communication with the server
+    /// with a `RecordingTransport`, or replay it offline with a
+    /// `ReplayTransport`
+    pub fn set_transport(&mut self, transport: Arc<dyn Transport>) {
+        self.transport = transport;
+    }
+
+    /// Checks if this session belongs to a character of an SSO account. Only
+    /// these can renew their credentials via `renew_sso_creds()`
+    #[must_use]
+    pub fn is_sso(&self) -> bool {
+        #[cfg(feature = "sso")]
+        if let LoginData::SSO { .. } = self.login_data {
+            return true;
+        }
+        false
+    }
+
     /// Logges in the session by sending a login response to the server and
     /// updating the internal cryptography values. If the session is currently
     /// logged in, this also clears the existing state beforehand.
@@ -243,8 +288,9 @@ impl Session {
         let command = command.borrow();
         trace!("Sending a {command:?} command");
 
+        let request_string = command.request_string()?;
         let mut command_str =
-            format!("{}|{}", self.session_id, command.request_string()?);
+            format!("{}|{}", self.session_id, request_string);
 
         while command_str.len() % 16 > 0 {
             command_str.push('|');
@@ -267,25 +313,29 @@ impl Session {
             SFError::InvalidRequest("Could not parse command url")
         })?;
 
-        #[allow(unused_mut)]
-        let mut req = self
-            .client
-            .get(&url)
-            .header(REFERER, &self.server_url.to_string());
+        self.options.rate_limit.wait_jitter().await;
+        acquire_in_order(
+            self.limiter.as_deref(),
+            self.server_limiter.as_deref(),
+        )
+        .await;
 
+        #[allow(unused_mut)]
+        let mut bearer_token = None;
         #[cfg(feature = "sso")]
         if let LoginData::SSO { session, .. } = &self.login_data {
-            req = req.bearer_auth(&session.bearer_token);
-        }
-
-        let resp = req.send().await.map_err(|_| SFError::ConnectionError)?;
-
-        if !resp.status().is_success() {
-            return Err(SFError::ConnectionError);
+            bearer_token = Some(session.bearer_token.as_str());
         }
 
-        let response_body =
-            resp.text().await.map_err(|_| SFError::ConnectionError)?;
+        let response_body = self
+            .transport
+            .send(TransportRequest {
+                url: &url,
+                command: &request_string,
+                server_url: &self.server_url,
+                bearer_token,
+            })
+            .await?;
 
         match response_body {
             body if body.is_empty() => Err(SFError::EmptyResponse),
@@ -362,6 +412,11 @@ impl Session {
         let session = account.lock().await.session.clone();
         let client = account.lock().await.client.clone();
         let options = account.lock().await.options.clone();
+        let transport = Arc::new(HttpTransport::new(client));
+        let server_limiter = options
+            .rate_limit
+            .per_server
+            .map(|a| Arc::new(RateLimiter::new(a)));
 
         let ld = LoginData::SSO {
             username: character.name,
@@ -369,7 +424,13 @@ impl Session {
             account,
             session,
         };
-        Ok(Session::new_full(ld, client, options, url))
+        Ok(Session::new_full(
+            ld,
+            transport,
+            server_limiter,
+            options,
+            url,
+        ))
     }
 
     #[must_use]
@@ -451,7 +512,8 @@ enum LoginData {
 /// benefits to performance
 pub struct ServerConnection {
     url: url::Url,
-    client: Client,
+    transport: Arc<dyn Transport>,
+    server_limiter: Option<Arc<RateLimiter>>,
     options: ConnectionOptions,
 }
 
@@ -482,10 +544,38 @@ impl ServerConnection {
 
         Some(ServerConnection {
             url,
-            client: reqwest_client(&options)?,
+            transport: Arc::new(HttpTransport::new(reqwest_client(&options)?)),
+            server_limiter: options
+                .rate_limit
+                .per_server
+                .map(|a| Arc::new(RateLimiter::new(a))),
             options,
         })
     }
+
+    /// Makes all sessions created with this connection share the given
+    /// limiter instead of the one created from `RateLimitOptions::per_server`.
+    /// Use this to share one budget across multiple servers
+    #[must_use]
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.server_limiter = Some(limiter);
+        self
+    }
+
+    /// The limiter, that is shared by all sessions created with this
+    /// connection. None, if there is no per server budget
+    #[must_use]
+    pub fn rate_limiter(&self) -> Option<&Arc<RateLimiter>> {
+        self.server_limiter.as_ref()
+    }
+
+    /// Makes all sessions created with this connection use the given
+    /// transport instead of talking to the server directly
+    #[must_use]
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
 }
 
 pub(crate) fn reqwest_client(
@@ -515,6 +605,9 @@ pub struct ConnectionOptions {
     /// if you want to make sure you never get surprised by unexpected changes
     /// on the server
     pub error_on_unsupported_version: bool,
+    /// Limits how fast requests are sent to the server. Nothing is limited
+    /// by default
+    pub rate_limit: RateLimitOptions,
 }
 
 impl Default for ConnectionOptions {
@@ -527,6 +620,7 @@ impl Default for ConnectionOptions {
             ),
             expected_server_version: 2005,
             error_on_unsupported_version: false,
+            rate_limit: RateLimitOptions::default(),
         }
     }
 }
@@ -544,6 +638,17 @@ impl SimpleSession {
             .await;
     }
 
+    /// Wraps a session, that you have set up yourself, e.g. with a custom
+    /// `Transport`. The session will be logged in, once the first command is
+    /// sent
+    #[must_use]
+    pub fn new(session: Session) -> Self {
+        Self {
+            session,
+            gamestate: None,
+        }
+    }
+
     /// Creates a new `SimpleSession`, by logging in a normal S&F character
     ///
     /// # Errors
@@ -654,4 +759,122 @@ impl SimpleSession {
 
         Ok(self.gamestate.as_mut().unwrap())
     }
+
+    /// Sends the command just like `send_command` and returns its direct
+    /// result. Have a look at `CommandOutcome::from_state` for the commands,
+    /// that have one. For every other command this is None
+    ///
+    /// # Errors
+    /// Have a look at `send_command` for a full list of possible errors
+    pub async fn send_command_outcome<T: Borrow<Command>>(
+        &mut self,
+        cmd: T,
+    ) -> Result<Option<CommandOutcome>, SFError> {
+        let cmd = cmd.borrow();
+        let gs = self.send_command(cmd).await?;
+        Ok(CommandOutcome::from_state(cmd, gs))
+    }
+
+    /// Sends the command and picks the expected outcome out of the result.
+    /// Errors, if the response did not contain it
+    async fn expect_outcome<T>(
+        &mut self,
+        cmd: &Command,
+        name: &'static str,
+        pick: fn(CommandOutcome) -> Option<T>,
+    ) -> Result<T, SFError> {
+        self.send_command_outcome(cmd)
+            .await?
+            .and_then(pick)
+            .ok_or_else(|| SFError::ParsingError(name, format!("{cmd:?}")))
+    }
+
+    /// Fights the player in the arena and returns the result of the fight
+    ///
+    /// # Errors
+    /// Have a look at `send_command` for a full list of possible errors
+    pub async fn fight(
+        &mut self,
+        name: &str,
+        use_mushroom: bool,
+    ) -> Result<FightOutcome, SFError> {
+        let cmd = Command::Fight {
+            name: name.to_string(),
+            use_mushroom,
+        };
+        self.expect_outcome(&cmd, "fight outcome", |a| match a {
+            CommandOutcome::Fight(fight) => Some(fight),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Looks at a player by their name, or id and returns everything the
+    /// server has sent about them
+    ///
+    /// # Errors
+    /// Have a look at `send_command` for a full list of possible errors
+    pub async fn view_player(
+        &mut self,
+        ident: &str,
+    ) -> Result<OtherPlayer, SFError> {
+        let cmd = Command::ViewPlayer {
+            ident: ident.to_string(),
+        };
+        self.expect_outcome(&cmd, "view player outcome", |a| match a {
+            CommandOutcome::Player(player) => Some(*player),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Gambles the silver in the tavern and returns how much was won, or lost
+    ///
+    /// # Errors
+    /// Have a look at `send_command` for a full list of possible errors
+    pub async fn gamble_silver(
+        &mut self,
+        amount: u64,
+    ) -> Result<GambleResult, SFError> {
+        let cmd = Command::GambleSilver { amount };
+        self.expect_outcome(&cmd, "gamble outcome", |a| match a {
+            CommandOutcome::Gamble(result) => Some(result),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Gambles the mushrooms in the tavern and returns how much was won, or
+    /// lost
+    ///
+    /// # Errors
+    /// Have a look at `send_command` for a full list of possible errors
+    pub async fn gamble_mushrooms(
+        &mut self,
+        amount: u64,
+    ) -> Result<GambleResult, SFError> {
+        let cmd = Command::GambleMushrooms { amount };
+        self.expect_outcome(&cmd, "gamble outcome", |a| match a {
+            CommandOutcome::Gamble(result) => Some(result),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Fetches one page of the Hall of Fame. Have a look at
+    /// `Command::HallOfFamePage` for the ranks on each page
+    ///
+    /// # Errors
+    /// Have a look at `send_command` for a full list of possible errors
+    pub async fn hall_of_fame_page(
+        &mut self,
+        page: usize,
+    ) -> Result<Vec<HallOfFamePlayer>, SFError> {
+        let cmd = Command::HallOfFamePage { page };
+        self.expect_outcome(&cmd, "hall of fame outcome", |a| match a {
+            CommandOutcome::HallOfFame(players) => Some(players),
+            _ => None,
+        })
+        .await
+    }
 }
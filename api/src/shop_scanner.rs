@@ -0,0 +1,316 @@
+use log::{trace, warn};
+use strum::IntoEnumIterator;
+
+use crate::{
+    command::{Command, ShopType},
+    error::SFError,
+    gamestate::{
+        items::{InventoryType, Item, PlayerItemPlace},
+        unlockables::{EquipmentIdent, ScrapBook},
+        GameState,
+    },
+    misc::EnumMapGet,
+    session::SimpleSession,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The place, where an item, that is missing in the scrapbook, was found
+pub enum ScrapBookItemSource {
+    /// The item can be bought in this shop
+    Shop(ShopType),
+    /// The item is already in the inventory of the character
+    Inventory(InventoryType),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An item, that is not yet in the scrapbook and can be gotten without
+/// fighting anyone
+pub struct ScrapBookOffer {
+    /// Where the item is
+    pub source: ScrapBookItemSource,
+    /// The position of the item in the shop, or inventory
+    pub pos: usize,
+    pub ident: EquipmentIdent,
+    /// The price in silver to buy this item. 0 for items in the inventory
+    pub silver: u64,
+    /// The price in mushrooms to buy this item. 0 for items in the inventory
+    pub mushrooms: u32,
+}
+
+/// Checks the weapon & magic shop, as well as the inventory and fortress chest
+/// for items, that are not yet in the scrapbook. The witch does not store any
+/// items, so there is nothing to check there. Returns an empty list, if the
+/// scrapbook has not been unlocked yet
+#[must_use]
+pub fn scan_missing_items(gs: &GameState) -> Vec<ScrapBookOffer> {
+    let Some(scrapbook) = &gs.character.scrapbook else {
+        return Vec::new();
+    };
+
+    let mut offers = Vec::new();
+    for shop_type in ShopType::iter() {
+        let shop = gs.shops.get(shop_type);
+        add_offers(
+            scrapbook,
+            shop.items.iter().map(Some),
+            ScrapBookItemSource::Shop(shop_type),
+            &mut offers,
+        );
+    }
+
+    let inventory = &gs.character.inventory;
+    add_offers(
+        scrapbook,
+        inventory.bag.iter().map(Option::as_ref),
+        ScrapBookItemSource::Inventory(InventoryType::MainInventory),
+        &mut offers,
+    );
+    if let Some(chest) = &inventory.fortress_chest {
+        add_offers(
+            scrapbook,
+            chest.iter().map(Option::as_ref),
+            ScrapBookItemSource::Inventory(InventoryType::ExtendedInventory),
+            &mut offers,
+        );
+    }
+    offers
+}
+
+fn add_offers<'a>(
+    scrapbook: &ScrapBook,
+    items: impl Iterator<Item = Option<&'a Item>>,
+    source: ScrapBookItemSource,
+    offers: &mut Vec<ScrapBookOffer>,
+) {
+    for (pos, item) in items.enumerate() {
+        let Some(item) = item else {
+            continue;
+        };
+        let Some(ident) = item.equipment_ident() else {
+            continue;
+        };
+        if scrapbook.items.contains(&ident)
+            || offers.iter().any(|a| a.ident == ident)
+        {
+            continue;
+        }
+        let (silver, mushrooms) = match source {
+            ScrapBookItemSource::Shop(_) => {
+                (u64::from(item.price), item.mushroom_price)
+            }
+            ScrapBookItemSource::Inventory(_) => (0, 0),
+        };
+        offers.push(ScrapBookOffer {
+            source,
+            pos,
+            ident,
+            silver,
+            mushrooms,
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Decides which of the missing shop items get bought
+pub struct ShopBuyOptions {
+    /// The most silver you want to spend on a single item
+    pub max_silver: u64,
+    /// The most mushrooms you want to spend on a single item. Mushrooms are
+    /// normally too valuable to spend on the scrapbook, so this is 0 by
+    /// default
+    pub max_mushrooms: u32,
+    /// Sell the item right after buying it. The scrapbook entry stays and you
+    /// get some of the silver back
+    pub sell_back: bool,
+}
+
+impl Default for ShopBuyOptions {
+    fn default() -> Self {
+        Self {
+            max_silver: 0,
+            max_mushrooms: 0,
+            sell_back: true,
+        }
+    }
+}
+
+/// Buys every item from the shops, that is missing in the scrapbook and
+/// cheap enough according to the options. Stops, once there is no free
+/// inventory slot left. Returns the items, that have been bought. The
+/// requests are sent as fast as the rate limit of the session allows
+///
+/// # Errors
+/// Have a look at `SimpleSession::send_command` for a full list of possible
+/// errors
+pub async fn buy_missing_items(
+    session: &mut SimpleSession,
+    options: &ShopBuyOptions,
+) -> Result<Vec<EquipmentIdent>, SFError> {
+    let mut bought = Vec::new();
+    while let Some(gs) = session.game_state() {
+        let character = &gs.character;
+        let offer = scan_missing_items(gs).into_iter().find(|a| {
+            matches!(a.source, ScrapBookItemSource::Shop(_))
+                && a.silver <= options.max_silver.min(character.silver)
+                && a.mushrooms <= options.max_mushrooms.min(character.mushrooms)
+        });
+        let Some(offer) = offer else {
+            break;
+        };
+        let ScrapBookItemSource::Shop(shop_type) = offer.source else {
+            break;
+        };
+        let Some((inv, inventory_pos)) = character.inventory.free_slot() else {
+            warn!("No free inventory slot to buy scrapbook items into");
+            break;
+        };
+        let inventory = match inv {
+            InventoryType::MainInventory => PlayerItemPlace::MainInventory,
+            InventoryType::ExtendedInventory => {
+                PlayerItemPlace::ExtendedInventory
+            }
+        };
+
+        trace!("Buying {:?} for the scrapbook", offer.ident);
+        session
+            .send_command(Command::BuyShop {
+                shop_type,
+                shop_pos: offer.pos,
+                inventory,
+                inventory_pos,
+            })
+            .await?;
+        bought.push(offer.ident);
+        // The server does not send us the scrapbook after buying, so we add
+        // the item ourselves to not buy the same thing twice
+        if let Some(scrapbook) = session
+            .game_state_mut()
+            .and_then(|a| a.character.scrapbook.as_mut())
+        {
+            scrapbook.items.insert(offer.ident);
+        }
+
+        if options.sell_back {
+            session
+                .send_command(Command::SellShop {
+                    inventory,
+                    inventory_pos,
+                })
+                .await?;
+        }
+    }
+    Ok(bought)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc, time::Duration};
+
+    use super::*;
+    use crate::{
+        mock_server::{tests::mock_session, MockServer, MockWorld},
+        rate_limit::{RateBudget, RateLimitOptions},
+        session::{ConnectionOptions, ServerConnection, Session},
+    };
+
+    fn options(max_silver: u64, sell_back: bool) -> ShopBuyOptions {
+        ShopBuyOptions {
+            max_silver,
+            sell_back,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn buy_and_sell_back() {
+        let (server, mut session) = mock_session(MockWorld::sample(0));
+        let expected: HashSet<_> = {
+            let world = server.world();
+            let character = &world.character;
+            character
+                .shops
+                .values()
+                .flatten()
+                .map(|a| a.ident)
+                .filter(|a| !character.scrapbook.items.contains(a))
+                .collect()
+        };
+        assert_eq!(expected.len(), 6);
+
+        session.send_command(Command::Update).await.unwrap();
+        let bought = buy_missing_items(&mut session, &options(1000, true))
+            .await
+            .unwrap();
+        assert_eq!(bought.iter().copied().collect::<HashSet<_>>(), expected);
+        assert_eq!(bought.len(), expected.len());
+
+        let world = server.world();
+        let character = &world.character;
+        // The mock pays the full price back
+        assert_eq!(character.silver, 10_000);
+        assert!(character.inventory.iter().all(Option::is_none));
+        assert!(expected.is_subset(&character.scrapbook.items));
+        let gs = session.game_state().unwrap();
+        assert!(scan_missing_items(gs).is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn too_expensive() {
+        let (server, mut session) = mock_session(MockWorld::sample(0));
+        session.send_command(Command::Update).await.unwrap();
+        let bought = buy_missing_items(&mut session, &options(499, true))
+            .await
+            .unwrap();
+        assert!(bought.is_empty());
+        assert_eq!(server.world().character.silver, 10_000);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stop_with_full_inventory() {
+        let (server, mut session) = mock_session(MockWorld::sample(0));
+        session.send_command(Command::Update).await.unwrap();
+        let bought = buy_missing_items(&mut session, &options(1000, false))
+            .await
+            .unwrap();
+        let world = server.world();
+        let inventory = &world.character.inventory;
+        assert_eq!(bought.len(), inventory.len());
+        assert!(inventory.iter().all(Option::is_some));
+        assert_eq!(world.character.silver, 10_000 - 500 * 5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn paced_by_the_session() {
+        let server = Arc::new(MockServer::new(MockWorld::sample(0)));
+        let limited = ConnectionOptions {
+            rate_limit: RateLimitOptions {
+                per_session: Some(RateBudget {
+                    requests: 1,
+                    interval: Duration::from_secs(1),
+                    burst: 1,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let connection =
+            ServerConnection::new_with_options("http://mock.local/", limited)
+                .unwrap();
+        let mut session = Session::new("mock", "mock", connection);
+        session.set_transport(server);
+        let mut session = SimpleSession::new(session);
+        session.send_command(Command::Update).await.unwrap();
+
+        // The budget is used up by now, so every buy and every sell has to
+        // wait one interval. Nothing else waits on top of that
+        let start = tokio::time::Instant::now();
+        let bought = buy_missing_items(&mut session, &options(1000, true))
+            .await
+            .unwrap();
+        assert_eq!(bought.len(), 6);
+        assert_eq!(start.elapsed().as_secs(), 12);
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+
+use enum_map::EnumMap;
+
+use super::{
+    AttackType, Battle, BattleEvent, BattleFighter, BattleLogger, BattleSide,
+    PlayerFighterSquad, UpgradeableFighter,
+};
+use crate::gamestate::{
+    arena::{Fight, FightActionType, SingleFight},
+    character::Class,
+    social::OtherPlayer,
+    GameState,
+};
+
+#[derive(Debug, Clone)]
+/// A fight, that has been fought on the server, together with both fighters,
+/// so that it can be fought again in the simulator
+pub struct RecordedFight {
+    /// The fighter, that has started the fight. This is the left side in the
+    /// simulator
+    pub attacker: UpgradeableFighter,
+    /// The id of the attacker in the fight log
+    pub attacker_id: i64,
+    pub defender: UpgradeableFighter,
+    /// The id of the defender in the fight log
+    pub defender_id: i64,
+    /// The fight log the server has sent
+    pub fight: SingleFight,
+}
+
+impl RecordedFight {
+    /// Pairs a 1v1 fight of the own character with the player, that it was
+    /// fought against. The game state and the player have to be from the
+    /// time of the fight. Returns None, if `fight` is not a 1v1 fight between
+    /// these two
+    #[must_use]
+    pub fn new(
+        gs: &GameState,
+        other: &OtherPlayer,
+        fight: &Fight,
+    ) -> Option<Self> {
+        let [single] = fight.fights.as_slice() else {
+            return None;
+        };
+        let attacker_id = i64::from(gs.character.player_id);
+        let defender_id = i64::from(other.player_id);
+        let ids =
+            [single.fighter_a.as_ref()?.id, single.fighter_b.as_ref()?.id];
+        if !ids.contains(&attacker_id) || !ids.contains(&defender_id) {
+            return None;
+        }
+        Some(Self {
+            attacker: PlayerFighterSquad::new(gs).character,
+            attacker_id,
+            defender: UpgradeableFighter::from_other(other),
+            defender_id,
+            fight: single.clone(),
+        })
+    }
+
+    /// Whether or not the attacker has won this fight on the server
+    #[must_use]
+    pub fn attacker_won(&self) -> bool {
+        i64::from(self.fight.winner_id) == self.attacker_id
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The lowest and highest damage, that has been dealt
+pub struct DamageRange {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl DamageRange {
+    fn include(range: &mut Option<DamageRange>, damage: i64) {
+        let range = range.get_or_insert(DamageRange {
+            min: damage,
+            max: damage,
+        });
+        range.min = range.min.min(damage);
+        range.max = range.max.max(damage);
+    }
+
+    #[must_use]
+    pub fn contains(&self, damage: i64) -> bool {
+        (self.min..=self.max).contains(&damage)
+    }
+
+    /// How far the damage is away from this range, relative to the closest
+    /// end of it. 0.1 means 10% outside of the range
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn deviation(&self, damage: i64) -> f64 {
+        let (distance, bound) = if damage < self.min {
+            (self.min - damage, self.min)
+        } else if damage > self.max {
+            (damage - self.max, self.max)
+        } else {
+            return 0.0;
+        };
+        distance as f64 / bound.max(1) as f64
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// The weapon damage one side has dealt in the simulator
+struct SimulatedHits {
+    normal: Option<DamageRange>,
+    crit: Option<DamageRange>,
+}
+
+/// Remembers the damage range of every weapon hit in the simulator
+#[derive(Debug, Default)]
+struct HitRecorder {
+    /// The side of the current attack, if it is a weapon attack
+    weapon_attack: Option<BattleSide>,
+    crit: bool,
+    hits: EnumMap<BattleSide, SimulatedHits>,
+}
+
+impl BattleLogger for HitRecorder {
+    fn log(&mut self, event: BattleEvent<'_, '_>) {
+        match event {
+            BattleEvent::Attack(
+                attacker,
+                _,
+                AttackType::Weapon | AttackType::Offhand,
+            ) => {
+                self.weapon_attack = Some(attacker.side);
+                self.crit = false;
+            }
+            BattleEvent::Attack(..) | BattleEvent::CometAttack(..) => {
+                self.weapon_attack = None;
+            }
+            BattleEvent::Crit(..) => self.crit = true,
+            BattleEvent::DamageReceived(attacker, _, damage) => {
+                let side = attacker.side;
+                if self.weapon_attack.take() != Some(side) {
+                    return;
+                }
+                let hits = &mut self.hits[side];
+                let range = if self.crit {
+                    &mut hits.crit
+                } else {
+                    &mut hits.normal
+                };
+                DamageRange::include(range, damage);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+/// How the simulator compares to the server for one class fighting against
+/// another class
+pub struct PairingReport {
+    /// The amount of fights between the two classes
+    pub fights: u32,
+    /// The amount of these fights, that the first class has won on the
+    /// server
+    pub server_wins: u32,
+    /// The amount of fights, that the simulator expected the first class to
+    /// win. This is the sum of the simulated win probabilities
+    pub expected_wins: f64,
+    /// The amount of normal weapon hits of the first class on the server
+    pub hits: u32,
+    /// The amount of normal weapon hits on the server, that the simulator
+    /// never dealt in the same fight
+    pub hits_out_of_range: u32,
+    /// The amount of critical weapon hits of the first class on the server
+    pub crits: u32,
+    /// The amount of critical weapon hits on the server, that the simulator
+    /// never dealt in the same fight
+    pub crits_out_of_range: u32,
+    /// The largest relative distance between a hit on the server and the
+    /// damage range of the simulator. Have a look at
+    /// `DamageRange::deviation()`
+    pub max_deviation: f64,
+}
+
+impl PairingReport {
+    /// The share of fights the first class has won on the server
+    #[must_use]
+    pub fn server_win_rate(&self) -> f64 {
+        f64::from(self.server_wins) / f64::from(self.fights.max(1))
+    }
+
+    /// The share of fights the simulator expected the first class to win
+    #[must_use]
+    pub fn simulated_win_rate(&self) -> f64 {
+        self.expected_wins / f64::from(self.fights.max(1))
+    }
+
+    /// Whether or not the simulator and the server disagree. They do, if a
+    /// hit on the server is outside of the simulated range, or the win rates
+    /// are more than `tolerance` (0.0..=1.0) apart
+    #[must_use]
+    pub fn disagrees(&self, tolerance: f64) -> bool {
+        self.hits_out_of_range > 0
+            || self.crits_out_of_range > 0
+            || (self.server_win_rate() - self.simulated_win_rate()).abs()
+                > tolerance
+    }
+
+    fn add_hits(
+        &mut self,
+        fight: &SingleFight,
+        fighter_id: i64,
+        simulated: SimulatedHits,
+    ) {
+        for (action, damage) in fight.actions.iter().zip(fight.damages()) {
+            let Some(damage) = damage else {
+                continue;
+            };
+            if action.acting_id != fighter_id
+                || action.action != FightActionType::Attack
+                || action.skill.is_some()
+                || damage <= 0
+            {
+                continue;
+            }
+
+            let (count, out_of_range, range) = if action.critical {
+                (
+                    &mut self.crits,
+                    &mut self.crits_out_of_range,
+                    simulated.crit,
+                )
+            } else {
+                (
+                    &mut self.hits,
+                    &mut self.hits_out_of_range,
+                    simulated.normal,
+                )
+            };
+            *count += 1;
+            let deviation = range.map_or(1.0, |a| a.deviation(damage));
+            if deviation > 0.0 {
+                *out_of_range += 1;
+                self.max_deviation = self.max_deviation.max(deviation);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+/// Compares recorded fights with the simulator for every pairing of classes.
+/// Every fight is counted for both classes, so (Mage, Scout) contains the
+/// hits of the mage and the fights from the perspective of the mage
+pub struct CalibrationReport {
+    pub pairings: HashMap<(Class, Class), PairingReport>,
+}
+
+impl CalibrationReport {
+    /// Fights the recorded fight `iterations` times in the simulator and adds
+    /// the comparison with the server to the report
+    pub fn add_fight(&mut self, recorded: &RecordedFight, iterations: u32) {
+        if iterations == 0 {
+            return;
+        }
+        let mut left = [BattleFighter::from_upgradeable(&recorded.attacker)];
+        let mut right = [BattleFighter::from_upgradeable(&recorded.defender)];
+        let mut battle = Battle::new(&mut left, &mut right);
+        let mut hit_recorder = HitRecorder::default();
+        let won = (0..iterations)
+            .filter(|_| battle.simulate(&mut hit_recorder) == BattleSide::Left)
+            .count();
+        let win_probability = won as f64 / f64::from(iterations);
+        let attacker_won = recorded.attacker_won();
+
+        let attacker = recorded.attacker.class;
+        let defender = recorded.defender.class;
+        let sides = [
+            (
+                (attacker, defender),
+                recorded.attacker_id,
+                BattleSide::Left,
+                attacker_won,
+                win_probability,
+            ),
+            (
+                (defender, attacker),
+                recorded.defender_id,
+                BattleSide::Right,
+                !attacker_won,
+                1.0 - win_probability,
+            ),
+        ];
+        for (pairing, fighter_id, side, won, probability) in sides {
+            let report = self.pairings.entry(pairing).or_default();
+            report.fights += 1;
+            report.server_wins += u32::from(won);
+            report.expected_wins += probability;
+            report.add_hits(
+                &recorded.fight,
+                fighter_id,
+                hit_recorder.hits[side],
+            );
+        }
+    }
+
+    /// The pairings, where the simulator and the server disagree. Have a look
+    /// at `PairingReport::disagrees()`
+    #[must_use]
+    pub fn disagreements(
+        &self,
+        tolerance: f64,
+    ) -> Vec<(Class, Class, &PairingReport)> {
+        let mut res: Vec<_> = self
+            .pairings
+            .iter()
+            .filter(|a| a.1.disagrees(tolerance))
+            .map(|((a, b), report)| (*a, *b, report))
+            .collect();
+        res.sort_by_key(|a| (a.0 as u8, a.1 as u8));
+        res
+    }
+}
+
+/// Fights every recorded fight `iterations` times in the simulator and
+/// compares the damage of every hit and the outcome with the server
+#[must_use]
+pub fn calibrate(
+    fights: &[RecordedFight],
+    iterations: u32,
+) -> CalibrationReport {
+    let mut report = CalibrationReport::default();
+    for fight in fights {
+        report.add_fight(fight, iterations);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairing(
+        fights: u32,
+        server_wins: u32,
+        expected_wins: f64,
+    ) -> PairingReport {
+        PairingReport {
+            fights,
+            server_wins,
+            expected_wins,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn damage_deviation() {
+        let range = DamageRange { min: 100, max: 200 };
+        assert!(range.contains(100) && range.contains(200));
+        assert!(range.deviation(150).abs() < f64::EPSILON);
+        assert!((range.deviation(90) - 0.1).abs() < f64::EPSILON);
+        assert!((range.deviation(300) - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn disagreements() {
+        let mut report = CalibrationReport::default();
+        // Same win rate, every hit in range
+        report
+            .pairings
+            .insert((Class::Warrior, Class::Mage), pairing(10, 5, 5.0));
+        // 50% on the server, 20% in the simulator
+        report
+            .pairings
+            .insert((Class::Mage, Class::Warrior), pairing(10, 5, 2.0));
+        // Same win rate, but a hit the simulator never dealt
+        report.pairings.insert(
+            (Class::Scout, Class::Mage),
+            PairingReport {
+                hits: 3,
+                hits_out_of_range: 1,
+                max_deviation: 0.2,
+                ..pairing(4, 2, 2.0)
+            },
+        );
+        // Same win rate, but a crit the simulator never dealt
+        report.pairings.insert(
+            (Class::Mage, Class::Scout),
+            PairingReport {
+                crits: 1,
+                crits_out_of_range: 1,
+                ..pairing(4, 2, 2.0)
+            },
+        );
+
+        let found: Vec<_> = report
+            .disagreements(0.1)
+            .into_iter()
+            .map(|(a, b, _)| (a, b))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (Class::Mage, Class::Warrior),
+                (Class::Mage, Class::Scout),
+                (Class::Scout, Class::Mage),
+            ]
+        );
+
+        // The win rates are within the tolerance now
+        let found: Vec<_> = report
+            .disagreements(0.3)
+            .into_iter()
+            .map(|(a, b, _)| (a, b))
+            .collect();
+        assert_eq!(
+            found,
+            [(Class::Mage, Class::Scout), (Class::Scout, Class::Mage)]
+        );
+    }
+}
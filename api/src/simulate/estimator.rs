@@ -0,0 +1,413 @@
+use std::num::NonZeroUsize;
+
+use enum_map::EnumMap;
+use fastrand::Rng;
+
+use super::{Battle, BattleEvent, BattleFighter, BattleLogger, BattleSide};
+
+/// The z-score of a 95% confidence interval
+const Z_95: f64 = 1.96;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Decides how many fights `estimate()` simulates and how
+pub struct EstimateOptions {
+    /// The amount of fights to simulate. If `target_margin` is set, this is
+    /// the maximum amount of fights
+    pub samples: u32,
+    /// Stops simulating, once the 95% confidence interval of the win rate is
+    /// at most this far away from it, like 0.02 for ±2%
+    pub target_margin: Option<f64>,
+    /// The amount of threads to simulate on. 0 uses all available cores
+    pub threads: usize,
+    /// Every thread gets its own seed derived from this, so the same options
+    /// always give the same estimate
+    pub seed: u64,
+}
+
+impl Default for EstimateOptions {
+    fn default() -> Self {
+        Self {
+            samples: 10_000,
+            target_margin: None,
+            threads: 0,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The total damage one side has dealt per fight
+pub struct DamageDistribution {
+    pub min: i64,
+    pub max: i64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The result of simulating many fights between the same two sides. Everything
+/// is from the perspective of the left side
+pub struct FightEstimate {
+    /// The amount of fights, that have been simulated
+    pub samples: u32,
+    pub wins: u32,
+    /// The share of fights the left side has won (0.0..=1.0)
+    pub win_rate: f64,
+    /// The lower end of the 95% confidence interval of `win_rate`
+    pub lower: f64,
+    /// The upper end of the 95% confidence interval of `win_rate`
+    pub upper: f64,
+    /// The average amount of turns a fight took
+    pub average_rounds: f64,
+    /// The damage the left side has dealt per fight
+    pub left_damage: DamageDistribution,
+    /// The damage the right side has dealt per fight
+    pub right_damage: DamageDistribution,
+}
+
+impl FightEstimate {
+    /// Half the width of the confidence interval. A win rate of 0.73 with a
+    /// margin of 0.02 would be shown as 73% ±2%
+    #[must_use]
+    pub fn margin(&self) -> f64 {
+        (self.upper - self.lower) / 2.0
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct DamageSums {
+    min: Option<i64>,
+    max: i64,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl DamageSums {
+    fn add(&mut self, damage: i64) {
+        self.min = Some(self.min.map_or(damage, |a| a.min(damage)));
+        self.max = self.max.max(damage);
+        let damage = damage as f64;
+        self.sum += damage;
+        self.sum_sq += damage * damage;
+    }
+
+    fn merge(&mut self, other: &DamageSums) {
+        if let Some(min) = other.min {
+            self.min = Some(self.min.map_or(min, |a| a.min(min)));
+        }
+        self.max = self.max.max(other.max);
+        self.sum += other.sum;
+        self.sum_sq += other.sum_sq;
+    }
+
+    fn distribution(&self, samples: u32) -> DamageDistribution {
+        let samples = f64::from(samples.max(1));
+        let mean = self.sum / samples;
+        let variance = (self.sum_sq / samples - mean * mean).max(0.0);
+        DamageDistribution {
+            min: self.min.unwrap_or_default(),
+            max: self.max,
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Tally {
+    samples: u32,
+    wins: u32,
+    rounds: u64,
+    damage: [DamageSums; 2],
+}
+
+impl Tally {
+    fn merge(&mut self, other: &Tally) {
+        self.samples += other.samples;
+        self.wins += other.wins;
+        self.rounds += other.rounds;
+        for (a, b) in self.damage.iter_mut().zip(&other.damage) {
+            a.merge(b);
+        }
+    }
+
+    fn estimate(&self) -> FightEstimate {
+        let (lower, upper) = wilson_interval(self.wins, self.samples);
+        let [left_damage, right_damage] =
+            self.damage.map(|a| a.distribution(self.samples));
+        FightEstimate {
+            samples: self.samples,
+            wins: self.wins,
+            win_rate: f64::from(self.wins) / f64::from(self.samples.max(1)),
+            lower,
+            upper,
+            average_rounds: self.rounds as f64 / f64::from(self.samples.max(1)),
+            left_damage,
+            right_damage,
+        }
+    }
+}
+
+/// The 95% Wilson score interval of a win rate. Unlike the normal
+/// approximation, this stays useful for win rates close to 0% or 100%
+fn wilson_interval(wins: u32, samples: u32) -> (f64, f64) {
+    if samples == 0 {
+        return (0.0, 1.0);
+    }
+    let n = f64::from(samples);
+    let p = f64::from(wins) / n;
+    let z2 = Z_95 * Z_95;
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let spread =
+        Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+    ((center - spread).max(0.0), (center + spread).min(1.0))
+}
+
+/// Sums up the damage both sides deal in a fight
+#[derive(Debug, Default)]
+struct DamageLogger {
+    damage: EnumMap<BattleSide, i64>,
+}
+
+impl BattleLogger for DamageLogger {
+    fn log(&mut self, event: BattleEvent<'_, '_>) {
+        if let BattleEvent::DamageReceived(from, _, damage) = event {
+            self.damage[from.side] += damage;
+        }
+    }
+}
+
+/// Simulates `samples` fights and adds them to the tally. The rng is
+/// continued from where the last batch has left it
+fn simulate_batch(
+    left: &[BattleFighter],
+    right: &[BattleFighter],
+    samples: u32,
+    rng: &mut Rng,
+) -> Tally {
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+    let mut battle = Battle::new(&mut left, &mut right);
+    battle.rng = rng.clone();
+
+    let mut tally = Tally::default();
+    for _ in 0..samples {
+        let mut logger = DamageLogger::default();
+        if battle.simulate(&mut logger) == BattleSide::Left {
+            tally.wins += 1;
+        }
+        tally.samples += 1;
+        tally.rounds += u64::from(battle.round);
+        for (sums, side) in tally
+            .damage
+            .iter_mut()
+            .zip([BattleSide::Left, BattleSide::Right])
+        {
+            sums.add(logger.damage[side]);
+        }
+    }
+    *rng = battle.rng;
+    tally
+}
+
+/// Simulates fights between the two lineups on multiple threads and estimates
+/// how likely the left side is to win. Have a look at `EstimateOptions` for
+/// how many fights are simulated
+#[must_use]
+pub fn estimate(
+    left: &[BattleFighter],
+    right: &[BattleFighter],
+    options: &EstimateOptions,
+) -> FightEstimate {
+    let threads = match options.threads {
+        0 => std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        x => x,
+    };
+    let threads = u32::try_from(threads).unwrap_or(u32::MAX).max(1);
+    // With a target margin, we check after every batch, if we are done
+    let batch = match options.target_margin {
+        Some(_) => 1_000u32.min(options.samples),
+        None => options.samples,
+    }
+    .max(1);
+
+    let mut rngs: Vec<Rng> = (0..u64::from(threads))
+        .map(|a| Rng::with_seed(options.seed ^ a.wrapping_mul(0x9E37_79B9)))
+        .collect();
+    let mut tally = Tally::default();
+
+    while tally.samples < options.samples {
+        let remaining = options.samples - tally.samples;
+        let round = batch.saturating_mul(threads).min(remaining);
+        let per_thread = round / threads;
+        let extra = round % threads;
+
+        let batches = std::thread::scope(|scope| {
+            let handles: Vec<_> = rngs
+                .iter_mut()
+                .zip(0..)
+                .filter_map(|(rng, pos)| {
+                    // With fewer samples than threads, some have nothing to do
+                    let samples = per_thread + u32::from(pos < extra);
+                    (samples > 0).then(|| {
+                        scope.spawn(move || {
+                            simulate_batch(left, right, samples, rng)
+                        })
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .filter_map(|a| a.join().ok())
+                .collect::<Vec<_>>()
+        });
+        for batch in &batches {
+            tally.merge(batch);
+        }
+        if batches.is_empty() {
+            break;
+        }
+
+        let margin = tally.estimate().margin();
+        if options.target_margin.is_some_and(|a| margin <= a) {
+            break;
+        }
+    }
+    tally.estimate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gamestate::character::Class, simulate::Monster};
+
+    fn sides() -> ([BattleFighter; 1], [BattleFighter; 1]) {
+        let left = BattleFighter::from_monster(&Monster::new(
+            100,
+            Class::Warrior,
+            [500, 200, 200, 800, 300],
+            80_000,
+            0,
+        ));
+        let right = BattleFighter::from_monster(&Monster::new(
+            100,
+            Class::Scout,
+            [200, 500, 200, 700, 300],
+            60_000,
+            0,
+        ));
+        ([left], [right])
+    }
+
+    fn options() -> EstimateOptions {
+        EstimateOptions {
+            samples: 3_000,
+            target_margin: None,
+            threads: 3,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn same_options_same_estimate() {
+        let (left, right) = sides();
+        let first = estimate(&left, &right, &options());
+        assert_eq!(first, estimate(&left, &right, &options()));
+        assert_eq!(first.samples, 3_000);
+        assert!(first.lower <= first.win_rate && first.win_rate <= first.upper);
+        assert!(first.average_rounds >= 1.0);
+
+        let other_seed = EstimateOptions {
+            seed: 43,
+            ..options()
+        };
+        assert_eq!(estimate(&left, &right, &other_seed).samples, 3_000);
+    }
+
+    #[test]
+    fn stop_at_target_margin() {
+        let (left, right) = sides();
+        let margin = EstimateOptions {
+            samples: 1_000_000,
+            target_margin: Some(0.05),
+            ..options()
+        };
+        let result = estimate(&left, &right, &margin);
+        assert!(result.margin() <= 0.05);
+        // Every thread simulates batches of 1000 fights
+        assert!(result.samples < 1_000_000);
+        assert_eq!(result.samples % 3_000, 0);
+        assert_eq!(result, estimate(&left, &right, &margin));
+    }
+
+    #[test]
+    fn more_threads_than_samples() {
+        let (left, right) = sides();
+        let few = EstimateOptions {
+            samples: 2,
+            threads: 8,
+            ..options()
+        };
+        let result = estimate(&left, &right, &few);
+        assert_eq!(result.samples, 2);
+        assert!(result.wins <= 2);
+    }
+
+    #[test]
+    fn wilson_interval_edges() {
+        assert_eq!(wilson_interval(0, 0), (0.0, 1.0));
+
+        let (lower, upper) = wilson_interval(0, 100);
+        assert!(lower.abs() < f64::EPSILON);
+        assert!((upper - 0.037).abs() < 0.001, "{upper}");
+
+        let (lower, upper) = wilson_interval(100, 100);
+        assert!((upper - 1.0).abs() < f64::EPSILON);
+        assert!((lower - 0.963).abs() < 0.001, "{lower}");
+
+        let (lower, upper) = wilson_interval(50, 100);
+        assert!((lower + upper - 1.0).abs() < 1e-9);
+        assert!((upper - lower) / 2.0 < 0.1);
+    }
+
+    #[test]
+    fn damage_statistics() {
+        let values = [2, 4, 4, 4, 5, 5, 7, 9];
+        let mut all = DamageSums::default();
+        let mut first = DamageSums::default();
+        let mut second = DamageSums::default();
+        for (pos, value) in values.into_iter().enumerate() {
+            all.add(value);
+            if pos < 3 {
+                first.add(value);
+            } else {
+                second.add(value);
+            }
+        }
+        first.merge(&second);
+        let distribution = first.distribution(8);
+        assert_eq!(distribution, all.distribution(8));
+        assert_eq!(distribution.min, 2);
+        assert_eq!(distribution.max, 9);
+        assert!((distribution.mean - 5.0).abs() < 1e-9);
+        assert!((distribution.std_dev - 2.0).abs() < 1e-9);
+
+        assert_eq!(
+            DamageSums::default().distribution(0),
+            DamageDistribution::default()
+        );
+
+        let (left, right) = sides();
+        let result = estimate(&left, &right, &options());
+        for damage in [result.left_damage, result.right_damage] {
+            // A side can lose, before it has dealt any damage
+            assert!(damage.min >= 0 && damage.max > 0);
+            assert!(damage.min as f64 <= damage.mean);
+            assert!(damage.mean <= damage.max as f64);
+        }
+    }
+}
@@ -17,8 +17,12 @@ use crate::{
     misc::EnumMapGet,
 };
 
+pub mod calibration;
 pub mod constants;
+pub mod estimator;
+pub mod timeline;
 
+use estimator::{EstimateOptions, FightEstimate};
 use BattleEvent as BE;
 
 #[derive(Debug, Clone)]
@@ -160,6 +164,7 @@ impl UpgradeableFighter {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Minion {
     Skeleton { revived: u8 },
     Hound,
@@ -182,6 +187,9 @@ pub struct BattleFighter {
     /// The amount of turns this player has been in the current 1v1 fight
     pub rounds_in_1v1: u32,
     pub class_effect: ClassEffect,
+    /// The side this fighter is fighting on. This is set, when the fighter
+    /// is put into a `Battle`
+    pub side: BattleSide,
 }
 
 impl std::hash::Hash for BattleFighter {
@@ -204,6 +212,7 @@ impl std::hash::Hash for BattleFighter {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HarpQuality {
     Bad,
     Medium,
@@ -256,6 +265,7 @@ pub enum ClassEffect {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttackType {
     Weapon,
     Offhand,
@@ -302,6 +312,7 @@ impl BattleFighter {
             rounds_started: 0,
             rounds_in_1v1: 0,
             class_effect: ClassEffect::Normal,
+            side: BattleSide::Left,
         }
     }
 
@@ -399,6 +410,7 @@ impl BattleFighter {
             portal_dmg_bonus,
             level: char.level,
             rounds_in_1v1: 0,
+            side: BattleSide::Left,
         }
     }
 
@@ -418,9 +430,16 @@ impl BattleFighter {
     }
 
     pub fn reset(&mut self) {
-        self.class_effect = ClassEffect::Normal;
+        // Demon hunters can only revive a limited amount of times per fight
+        self.class_effect = match self.class {
+            Class::DemonHunter => ClassEffect::DemonHunter { revived: 0 },
+            _ => ClassEffect::Normal,
+        };
         self.current_hp = self.max_hp;
         self.rounds_started = 0;
+        // Who attacks depends on this being odd, or even, so a fight must not
+        // continue the count of the last one
+        self.rounds_in_1v1 = 0;
     }
 }
 
@@ -489,6 +508,7 @@ impl<'a> BattleTeam<'_> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BattleSide {
     Left,
     Right,
@@ -508,6 +528,12 @@ impl<'a> Battle<'a> {
         left: &'a mut [BattleFighter],
         right: &'a mut [BattleFighter],
     ) -> Self {
+        for fighter in left.iter_mut() {
+            fighter.side = BattleSide::Left;
+        }
+        for fighter in right.iter_mut() {
+            fighter.side = BattleSide::Right;
+        }
         Self {
             round: 0,
             started: None,
@@ -523,6 +549,19 @@ impl<'a> Battle<'a> {
         }
     }
 
+    /// Does the same as `new()`, but the battle always plays out the same
+    /// for the same seed
+    #[must_use]
+    pub fn with_seed(
+        left: &'a mut [BattleFighter],
+        right: &'a mut [BattleFighter],
+        seed: u64,
+    ) -> Self {
+        let mut battle = Self::new(left, right);
+        battle.rng = Rng::with_seed(seed);
+        battle
+    }
+
     /// Simulates a battle between the two sides. Returns the winning side.
     pub fn simulate(&mut self, logger: &mut impl BattleLogger) -> BattleSide {
         self.reset();
@@ -1035,6 +1074,42 @@ impl PlayerFighterSquad {
             companions,
         }
     }
+
+    /// Simulates `iterations` fights of the character against `other` and
+    /// returns the share of fights, that the character has won (0.0..=1.0).
+    /// Fights against other players are always 1v1, so the companions are not
+    /// part of these fights
+    #[must_use]
+    pub fn win_probability(
+        &self,
+        other: &UpgradeableFighter,
+        iterations: u32,
+    ) -> f64 {
+        if iterations == 0 {
+            return 0.0;
+        }
+        let options = EstimateOptions {
+            samples: iterations,
+            target_margin: None,
+            threads: 1,
+            seed: fastrand::u64(..),
+        };
+        self.estimate(other, &options).win_rate
+    }
+
+    /// Same as `win_probability()`, but the fights are simulated according to
+    /// the options, so this can run on multiple threads, be seeded and stop
+    /// early. Have a look at `estimator::estimate()`
+    #[must_use]
+    pub fn estimate(
+        &self,
+        other: &UpgradeableFighter,
+        options: &EstimateOptions,
+    ) -> FightEstimate {
+        let left = [BattleFighter::from_upgradeable(&self.character)];
+        let right = [BattleFighter::from_upgradeable(other)];
+        estimator::estimate(&left, &right, options)
+    }
 }
 
 impl UpgradeableFighter {
@@ -1208,3 +1283,62 @@ impl BattleLogger for () {
     fn log(&mut self, _event: BattleEvent<'_, '_>) {
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fighter(class: Class, hp: u64) -> BattleFighter {
+        BattleFighter::from_monster(&Monster::new(
+            100,
+            class,
+            [500, 500, 500, 800, 300],
+            hp,
+            0,
+        ))
+    }
+
+    #[derive(Default)]
+    struct Revives(u32);
+
+    impl BattleLogger for Revives {
+        fn log(&mut self, event: BattleEvent<'_, '_>) {
+            if let BattleEvent::DemonHunterRevived(..) = event {
+                self.0 += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn demon_hunters_revive() {
+        let mut left = [fighter(Class::DemonHunter, 50_000)];
+        let mut right = [fighter(Class::Warrior, 500_000)];
+        let mut battle = Battle::with_seed(&mut left, &mut right, 1);
+        let mut total = 0;
+        for _ in 0..100 {
+            let mut revives = Revives::default();
+            battle.simulate(&mut revives);
+            assert!(revives.0 <= 4);
+            total += revives.0;
+        }
+        assert!(total > 0);
+    }
+
+    #[test]
+    fn repeated_battles_start_fresh() {
+        for seed in 0..20 {
+            let mut left = [fighter(Class::Warrior, 80_000)];
+            let mut right = [fighter(Class::Scout, 60_000)];
+            let mut battle = Battle::with_seed(&mut left, &mut right, seed);
+            battle.simulate(&mut ());
+            let rng = battle.rng.clone();
+            let second = (battle.simulate(&mut ()), battle.round);
+
+            let mut left = [fighter(Class::Warrior, 80_000)];
+            let mut right = [fighter(Class::Scout, 60_000)];
+            let mut fresh = Battle::new(&mut left, &mut right);
+            fresh.rng = rng;
+            assert_eq!((fresh.simulate(&mut ()), fresh.round), second);
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use super::{
+    AttackType, Battle, BattleEvent, BattleFighter, BattleLogger, BattleSide,
+    HarpQuality, Minion,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An owned version of a `BattleEvent`. `side` is always the side of the
+/// fighter, that does something, like attacking, or reviving
+pub enum TimelineEvent {
+    /// A new turn has started. Turns start at 1
+    Turn {
+        round: u32,
+        /// The life of the current fighter on the left side
+        left_hp: i64,
+        /// The life of the current fighter on the right side
+        right_hp: i64,
+    },
+    Attack {
+        side: BattleSide,
+        typ: AttackType,
+    },
+    /// The attack of `side` was dodged
+    Dodged {
+        side: BattleSide,
+    },
+    /// The attack of `side` was blocked
+    Blocked {
+        side: BattleSide,
+    },
+    Crit {
+        side: BattleSide,
+    },
+    /// `side` has dealt damage to the other side
+    Damage {
+        side: BattleSide,
+        damage: i64,
+        /// The life of the target after the damage
+        target_hp: i64,
+    },
+    DemonHunterRevived {
+        side: BattleSide,
+        hp: i64,
+    },
+    CometRepelled {
+        side: BattleSide,
+    },
+    CometAttack {
+        side: BattleSide,
+    },
+    MinionSpawned {
+        side: BattleSide,
+        minion: Minion,
+    },
+    MinionSkeletonRevived {
+        side: BattleSide,
+    },
+    BardPlay {
+        side: BattleSide,
+        quality: HarpQuality,
+    },
+    /// The current fighter of `side` has been defeated
+    FighterDefeat {
+        side: BattleSide,
+    },
+    BattleEnd {
+        winner: BattleSide,
+    },
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Everything, that has happened in a simulated battle, in the order it has
+/// happened in
+pub struct BattleTimeline {
+    pub events: Vec<TimelineEvent>,
+}
+
+impl BattleTimeline {
+    /// The side, that has won the battle, if it has ended
+    #[must_use]
+    pub fn winner(&self) -> Option<BattleSide> {
+        self.events.iter().rev().find_map(|a| match a {
+            TimelineEvent::BattleEnd { winner } => Some(*winner),
+            _ => None,
+        })
+    }
+
+    /// The amount of turns the battle took
+    #[must_use]
+    pub fn rounds(&self) -> u32 {
+        self.events
+            .iter()
+            .filter_map(|a| match a {
+                TimelineEvent::Turn { round, .. } => Some(*round),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// A `BattleLogger`, that records every event of a battle into a
+/// `BattleTimeline`. Use a new recorder for every battle, or call `take()`
+/// in between, as a recorder just keeps appending events
+#[derive(Debug, Default)]
+pub struct TimelineRecorder {
+    timeline: BattleTimeline,
+}
+
+impl TimelineRecorder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The events recorded so far
+    #[must_use]
+    pub fn timeline(&self) -> &BattleTimeline {
+        &self.timeline
+    }
+
+    /// Returns the events recorded so far and starts a new timeline
+    pub fn take(&mut self) -> BattleTimeline {
+        std::mem::take(&mut self.timeline)
+    }
+
+    #[must_use]
+    pub fn into_timeline(self) -> BattleTimeline {
+        self.timeline
+    }
+
+    fn turn(battle: &Battle<'_>) -> Option<TimelineEvent> {
+        let left = battle.left.current()?;
+        let right = battle.right.current()?;
+        Some(TimelineEvent::Turn {
+            round: battle.round + 1,
+            left_hp: left.current_hp,
+            right_hp: right.current_hp,
+        })
+    }
+}
+
+impl BattleLogger for TimelineRecorder {
+    fn log(&mut self, event: BattleEvent<'_, '_>) {
+        use TimelineEvent as TE;
+
+        let side = |fighter: &BattleFighter| fighter.side;
+        let event = match event {
+            BattleEvent::TurnUpdate(battle) => {
+                let Some(turn) = Self::turn(battle) else {
+                    return;
+                };
+                turn
+            }
+            BattleEvent::BattleEnd(_, winner) => TE::BattleEnd { winner },
+            BattleEvent::Attack(from, _, typ) => TE::Attack {
+                side: side(from),
+                typ,
+            },
+            BattleEvent::Dodged(from, _) => TE::Dodged { side: side(from) },
+            BattleEvent::Blocked(from, _) => TE::Blocked { side: side(from) },
+            BattleEvent::Crit(from, _) => TE::Crit { side: side(from) },
+            BattleEvent::DamageReceived(from, to, damage) => TE::Damage {
+                side: side(from),
+                damage,
+                target_hp: to.current_hp,
+            },
+            BattleEvent::DemonHunterRevived(_, to) => TE::DemonHunterRevived {
+                side: side(to),
+                hp: to.current_hp,
+            },
+            BattleEvent::CometRepelled(from, _) => {
+                TE::CometRepelled { side: side(from) }
+            }
+            BattleEvent::CometAttack(from, _) => {
+                TE::CometAttack { side: side(from) }
+            }
+            BattleEvent::MinionSpawned(from, _, minion) => TE::MinionSpawned {
+                side: side(from),
+                minion,
+            },
+            BattleEvent::MinionSkeletonRevived(from, _) => {
+                TE::MinionSkeletonRevived { side: side(from) }
+            }
+            BattleEvent::BardPlay(from, _, quality) => TE::BardPlay {
+                side: side(from),
+                quality,
+            },
+            BattleEvent::FighterDefeat(_, side) => TE::FighterDefeat { side },
+        };
+        self.timeline.events.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use enum_map::EnumMap;
+
+    use super::*;
+    use crate::{
+        gamestate::character::Class,
+        simulate::{BattleFighter, Monster},
+    };
+
+    fn fight(seed: u64) -> (BattleSide, BattleTimeline) {
+        let mut left = [BattleFighter::from_monster(&Monster::new(
+            100,
+            Class::Warrior,
+            [500, 200, 200, 800, 300],
+            80_000,
+            0,
+        ))];
+        let mut right = [BattleFighter::from_monster(&Monster::new(
+            100,
+            Class::Scout,
+            [200, 500, 200, 700, 300],
+            60_000,
+            0,
+        ))];
+        let mut battle = Battle::with_seed(&mut left, &mut right, seed);
+        let mut recorder = TimelineRecorder::new();
+        let winner = battle.simulate(&mut recorder);
+        (winner, recorder.into_timeline())
+    }
+
+    #[test]
+    fn seeded_battles_repeat() {
+        let (winner, timeline) = fight(42);
+        assert_eq!(fight(42), (winner, timeline.clone()));
+
+        assert_eq!(
+            timeline.events.last(),
+            Some(&TimelineEvent::BattleEnd { winner })
+        );
+        // The damage of one side has to show up in the life of the other
+        // side in the next turn
+        let mut hit = EnumMap::<BattleSide, Option<i64>>::default();
+        for event in &timeline.events {
+            match *event {
+                TimelineEvent::Damage {
+                    side, target_hp, ..
+                } => {
+                    let target = match side {
+                        BattleSide::Left => BattleSide::Right,
+                        BattleSide::Right => BattleSide::Left,
+                    };
+                    hit[target] = Some(target_hp);
+                }
+                TimelineEvent::Turn {
+                    left_hp, right_hp, ..
+                } => {
+                    for (side, hp) in [
+                        (BattleSide::Left, left_hp),
+                        (BattleSide::Right, right_hp),
+                    ] {
+                        if let Some(target_hp) = hit[side].take() {
+                            assert_eq!(target_hp, hp);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        assert!(timeline.events.iter().any(|a| matches!(
+            a,
+            TimelineEvent::Damage {
+                side: BattleSide::Right,
+                ..
+            }
+        )));
+    }
+}
@@ -209,11 +209,21 @@ impl SFAccount {
     pub async fn characters(
         self,
     ) -> Result<Vec<Result<Session, SFError>>, SFError> {
-        // This could be passed in as an argument in case of multiple SSO
-        // accounts to safe on requests, but I dont think people have multiple
-        // and this is way easier
         let server_lookup =
             ServerLookup::fetch_with_client(&self.client).await?;
+        self.characters_with_lookup(&server_lookup).await
+    }
+
+    /// Same as `characters()`, but uses the provided server lookup instead of
+    /// fetching the current one. Useful, if you have multiple accounts, or
+    /// want to use a server list stored on disk
+    ///
+    /// # Errors
+    /// Have a look at `characters()` for a full list of possible errors
+    pub async fn characters_with_lookup(
+        self,
+        server_lookup: &ServerLookup,
+    ) -> Result<Vec<Result<Session, SFError>>, SFError> {
         let mut res = self
             .send_api_request("json/client/characters", APIRequest::Get)
             .await?;
@@ -229,7 +239,7 @@ impl SFAccount {
         let mut chars = vec![];
         for char in characters {
             chars.push(
-                Session::from_sso_char(char, account.clone(), &server_lookup)
+                Session::from_sso_char(char, account.clone(), server_lookup)
                     .await,
             );
         }
@@ -338,7 +348,6 @@ impl ServerLookup {
     }
 
     /// Fetches the current mapping of server ids to server URLs.
-    #[allow(clippy::items_after_statements)]
     async fn fetch_with_client(
         client: &Client,
     ) -> Result<ServerLookup, SFError> {
@@ -350,7 +359,29 @@ impl ServerLookup {
             .text()
             .await
             .map_err(|_| SFError::ConnectionError)?;
+        Self::parse(&res)
+    }
+
+    /// Reads a server list, that has been stored in the same format as the
+    /// one `fetch()` downloads (<https://sfgame.net/config.json>)
+    ///
+    /// # Errors
+    /// Returns `IOError`, if the file could not be read, or `ParsingError`,
+    /// if the list could not be parsed
+    pub fn from_file(
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<ServerLookup, SFError> {
+        let res = std::fs::read_to_string(path)
+            .map_err(|e| SFError::IOError(e.to_string()))?;
+        Self::parse(&res)
+    }
 
+    /// Parses the server list, that `fetch()` downloads
+    ///
+    /// # Errors
+    /// Returns `ParsingError`, if the list could not be parsed
+    #[allow(clippy::items_after_statements)]
+    pub fn parse(res: &str) -> Result<ServerLookup, SFError> {
         #[derive(Debug, Deserialize, Serialize)]
         struct ServerResp {
             servers: Vec<ServerInfo>,
@@ -370,7 +401,7 @@ impl ServerLookup {
             merge_date_time: Option<String>,
         }
 
-        let resp: ServerResp = serde_json::from_str(&res).map_err(|_| {
+        let resp: ServerResp = serde_json::from_str(res).map_err(|_| {
             SFError::ParsingError("server response", res.to_string())
         })?;
 
@@ -396,7 +427,10 @@ impl ServerLookup {
             })
             .collect();
         if servers.is_empty() {
-            return Err(SFError::ParsingError("empty server list", res));
+            return Err(SFError::ParsingError(
+                "empty server list",
+                res.to_string(),
+            ));
         }
 
         Ok(ServerLookup(servers))
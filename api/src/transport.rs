@@ -0,0 +1,347 @@
+use std::{
+    fmt::Debug,
+    fs::{File, OpenOptions},
+    future::Future,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use log::{trace, warn};
+use reqwest::header::REFERER;
+
+use crate::error::SFError;
+
+/// The future a `Transport` returns. We do not want to pull in `async_trait`
+/// just for this
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<String, SFError>> + Send + 'a>>;
+
+#[derive(Debug, Clone, Copy)]
+/// Everything a `Transport` needs to know to send a command to the server
+pub struct TransportRequest<'a> {
+    /// The full url of the request, which contains the encrypted command
+    pub url: &'a str,
+    /// The command, before it was encrypted, without the session id. This is
+    /// the same for every login, so it can be used to identify a command
+    pub command: &'a str,
+    /// The url of the server the command is send to
+    pub server_url: &'a url::Url,
+    /// The bearer token of SSO characters
+    pub bearer_token: Option<&'a str>,
+}
+
+/// Sends commands to the server and returns the raw body of its response.
+/// Everything else, like encrypting the command and parsing the response, is
+/// done by the `Session`. By default, sessions use an `HttpTransport`, but you
+/// can replace that with `Session::set_transport()` to record, or replay the
+/// communication with the server
+pub trait Transport: Debug + Send + Sync {
+    /// Sends the request and returns the body of the response. An empty body
+    /// is not an error here
+    fn send<'a>(&'a self, request: TransportRequest<'a>)
+        -> TransportFuture<'a>;
+}
+
+#[derive(Debug, Clone)]
+/// Talks to the actual S&F servers
+pub struct HttpTransport {
+    // We keep this instead of creating a new one, because as per the reqwest
+    // docs: "The Client holds a connection pool internally, so it is advised
+    // that you create one and reuse it."
+    client: reqwest::Client,
+}
+
+impl HttpTransport {
+    #[must_use]
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for HttpTransport {
+    fn send<'a>(
+        &'a self,
+        request: TransportRequest<'a>,
+    ) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let mut req = self
+                .client
+                .get(request.url)
+                .header(REFERER, request.server_url.to_string());
+            if let Some(token) = request.bearer_token {
+                req = req.bearer_auth(token);
+            }
+
+            let resp =
+                req.send().await.map_err(|_| SFError::ConnectionError)?;
+            if !resp.status().is_success() {
+                return Err(SFError::ConnectionError);
+            }
+            resp.text().await.map_err(|_| SFError::ConnectionError)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// One command and the response the server has sent back for it
+pub struct CassetteEntry {
+    /// The unencrypted command without the session id. Have a look at
+    /// `TransportRequest::command`
+    pub command: String,
+    /// The raw body of the response
+    pub response: String,
+}
+
+impl CassetteEntry {
+    /// Every entry is stored as one line of JSON. This goes through
+    /// `serde_json::Value`, so it also works without the serde feature
+    fn to_line(&self) -> String {
+        serde_json::json!({
+            "command": self.command,
+            "response": self.response,
+        })
+        .to_string()
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        Some(Self {
+            command: value.get("command")?.as_str()?.to_string(),
+            response: value.get("response")?.as_str()?.to_string(),
+        })
+    }
+}
+
+/// Reads all the entries of a cassette file, that has been written by a
+/// `RecordingTransport`
+///
+/// # Errors
+/// - `IOError`: If the file could not be read
+/// - `ParsingError`: If one of the lines is not a valid entry
+pub fn read_cassette(
+    path: impl AsRef<Path>,
+) -> Result<Vec<CassetteEntry>, SFError> {
+    let file = File::open(path).map_err(|e| SFError::IOError(e.to_string()))?;
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| SFError::IOError(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = CassetteEntry::from_line(&line).ok_or_else(|| {
+            SFError::ParsingError("cassette entry", line.clone())
+        })?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+#[derive(Debug)]
+/// Sends everything through another transport and writes every command,
+/// together with the response to it, into a cassette file. Keep in mind, that
+/// the cassette contains the login command of the character, which contains
+/// the hash of the password
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    file: Mutex<File>,
+}
+
+impl RecordingTransport {
+    /// Records everything send through `inner` into the file at `path`. If
+    /// the file already exists, the new entries are appended to it
+    ///
+    /// # Errors
+    /// - `IOError`: If the file could not be opened
+    pub fn new(
+        inner: Arc<dyn Transport>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, SFError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| SFError::IOError(e.to_string()))?;
+        Ok(Self {
+            inner,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send<'a>(
+        &'a self,
+        request: TransportRequest<'a>,
+    ) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let response = self.inner.send(request).await?;
+            let entry = CassetteEntry {
+                command: request.command.to_string(),
+                response,
+            };
+            let mut file = self
+                .file
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if let Err(e) = writeln!(file, "{}", entry.to_line()) {
+                warn!("Could not record {}: {e}", entry.command);
+            }
+            Ok(entry.response)
+        })
+    }
+}
+
+#[derive(Debug)]
+/// Answers commands with the responses from a cassette, without ever talking
+/// to the server. Every entry is only used once. For each command, the oldest
+/// unused entry with the same command is used, so the order of the commands
+/// can change a bit between recording and replaying them
+pub struct ReplayTransport {
+    entries: Mutex<Vec<Option<CassetteEntry>>>,
+}
+
+impl ReplayTransport {
+    /// Replays the given entries
+    #[must_use]
+    pub fn new(entries: Vec<CassetteEntry>) -> Self {
+        Self {
+            entries: Mutex::new(entries.into_iter().map(Some).collect()),
+        }
+    }
+
+    /// Replays the cassette file at `path`
+    ///
+    /// # Errors
+    /// Have a look at `read_cassette()` for a full list of possible errors
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SFError> {
+        Ok(Self::new(read_cassette(path)?))
+    }
+
+    /// The amount of entries, that have not been used yet
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .flatten()
+            .count()
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send<'a>(
+        &'a self,
+        request: TransportRequest<'a>,
+    ) -> TransportFuture<'a> {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let entry = entries
+            .iter_mut()
+            .find(|a| a.as_ref().is_some_and(|a| a.command == request.command))
+            .and_then(Option::take);
+
+        let result = if let Some(entry) = entry {
+            trace!("Replaying {}", entry.command);
+            Ok(entry.response)
+        } else {
+            warn!("No recorded response for {}", request.command);
+            Err(SFError::InvalidRequest(
+                "The cassette has no response for this command",
+            ))
+        };
+        Box::pin(std::future::ready(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::Command,
+        gamestate::GameState,
+        mock_server::{MockServer, MockWorld},
+        session::{ServerConnection, Session},
+    };
+
+    const CASSETTE: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/data/cassettes/session.jsonl");
+
+    /// Logs in and sends a few commands, that change most of the game state
+    async fn play(transport: Arc<dyn Transport>) -> Box<GameState> {
+        let server = ServerConnection::new("http://mock.local/").unwrap();
+        let mut session = Session::new("mock", "mock", server);
+        session.set_transport(transport);
+        let login = session.login().await.unwrap();
+        let mut gs = Box::new(GameState::new(login).unwrap());
+        let commands = [
+            Command::Update,
+            Command::ViewPlayer {
+                ident: "player3".to_string(),
+            },
+            Command::HallOfFamePage { page: 0 },
+            Command::ViewScrapbook,
+        ];
+        for command in commands {
+            let resp = session.send_command(&command).await.unwrap();
+            gs.update(resp).unwrap();
+        }
+        gs
+    }
+
+    /// The state without the offset to the clock of the server. That offset
+    /// is measured against our own clock, when a response arrives, so it
+    /// can be a second off between two runs and is way off, when a cassette
+    /// is replayed days later. It is the last field of the state
+    fn comparable(gs: &GameState) -> String {
+        let state = format!("{gs:?}");
+        let (state, _) = state.rsplit_once(", server_time_diff").unwrap();
+        state.to_string()
+    }
+
+    #[tokio::test]
+    async fn replay_gives_the_recorded_state() {
+        let path = std::env::temp_dir()
+            .join(format!("sf_api_cassette_{}.jsonl", std::process::id()));
+        _ = std::fs::remove_file(&path);
+
+        let server = Arc::new(MockServer::new(MockWorld::sample(20)));
+        let recording = RecordingTransport::new(server, &path).unwrap();
+        let live = play(Arc::new(recording)).await;
+
+        let replay = Arc::new(ReplayTransport::load(&path).unwrap());
+        let replayed = play(replay.clone()).await;
+        _ = std::fs::remove_file(&path);
+
+        assert_eq!(replay.remaining(), 0);
+        assert_eq!(comparable(&live), comparable(&replayed));
+    }
+
+    #[tokio::test]
+    async fn replay_committed_cassette() {
+        let replay = Arc::new(ReplayTransport::load(CASSETTE).unwrap());
+        let gs = play(replay.clone()).await;
+        assert_eq!(replay.remaining(), 0);
+        assert_eq!(gs.character.name, "mock");
+        assert_eq!(gs.character.level, 100);
+        assert_eq!(gs.character.silver, 10_000);
+        assert!(gs.lookup.lookup_name("player3").is_some());
+
+        // The state only depends on the cassette, not on when it is replayed
+        let again = play(Arc::new(ReplayTransport::load(CASSETTE).unwrap()));
+        assert_eq!(comparable(&gs), comparable(&*again.await));
+    }
+
+    #[test]
+    fn cassette_entries_survive_a_round_trip() {
+        let entry = CassetteEntry {
+            command: "PlayerLookAt:a/b".to_string(),
+            response: "error:\"quoted\"\nline|".to_string(),
+        };
+        assert_eq!(CassetteEntry::from_line(&entry.to_line()), Some(entry));
+    }
+}
@@ -0,0 +1,77 @@
+#include "sf_catalogue.h"
+
+#include "sf_api.hpp"
+#include <stdexcept>
+
+namespace
+{
+
+using ffi_char_t = int8_t;
+
+const ffi_char_t* to_ffi_str(const std::string& str)
+{
+    return reinterpret_cast<const ffi_char_t*>(str.c_str());
+}
+
+ffi::CEquipmentIdent to_ffi_ident(const sf::EquipmentIdent& ident)
+{
+    ffi::CEquipmentIdent res;
+    res.class_id = ident.classType ? static_cast<uint8_t>(*ident.classType) + 1 : 0;
+    res.slot = static_cast<uint8_t>(ident.slot);
+    res.model_id = ident.modelId;
+    res.color = ident.color;
+    res.is_epic = ident.isEpic;
+    return res;
+}
+
+} // namespace
+
+
+namespace sf
+{
+
+const ItemCatalogue& ItemCatalogue::embedded()
+{
+    static const ItemCatalogue catalogue(ffi::item_catalogue_embedded());
+    return catalogue;
+}
+
+ItemCatalogue::ItemCatalogue(const ffi::ItemCatalogue* catalogue)
+    : m_catalogue(catalogue), m_isOwned(false)
+{
+}
+
+ItemCatalogue::ItemCatalogue(const std::string& path)
+    : m_isOwned(true)
+{
+    m_catalogue = ffi::item_catalogue_load(to_ffi_str(path));
+    if (!m_catalogue)
+        throw std::runtime_error("Failed to load item catalogue");
+}
+
+ItemCatalogue::~ItemCatalogue()
+{
+    if (m_isOwned && m_catalogue)
+        ffi::destr_item_catalogue(const_cast<ffi::ItemCatalogue*>(m_catalogue));
+}
+
+ItemInfo ItemCatalogue::lookup(const EquipmentIdent& ident) const
+{
+    ffi::CCatalogueEntry entry = ffi::item_catalogue_lookup(m_catalogue, to_ffi_ident(ident));
+
+    ItemInfo res;
+    if (entry.name)
+        res.name = reinterpret_cast<const char*>(entry.name);
+    if (entry.set)
+        res.set = reinterpret_cast<const char*>(entry.set);
+    res.isEpic = entry.is_epic;
+    res.isLegendary = entry.is_legendary;
+    if (entry.min_level != 0 || entry.max_level != 0)
+        res.levelRange = std::make_pair(entry.min_level, entry.max_level);
+    res.isKnown = entry.is_known;
+
+    ffi::destr_catalogue_entry(entry);
+    return res;
+}
+
+} // namespace sf
@@ -0,0 +1,55 @@
+#pragma once
+#include <cstdint>
+#include <optional>
+#include <string>
+#include <utility>
+#include "sf_gamestate.h"
+
+
+namespace ffi
+{
+    struct ItemCatalogue;
+}
+
+namespace sf
+{
+
+// Human readable information about an item
+struct ItemInfo
+{
+    // The english name. Items, that are not in the catalogue, get a generic name based on their ident
+    std::string name;
+    std::optional<std::string> set;
+    bool isEpic;
+    bool isLegendary;
+    // The lowest and highest level the item drops at, if known
+    std::optional<std::pair<uint16_t, uint16_t>> levelRange;
+    // Whether or not the item was found in the catalogue
+    bool isKnown;
+};
+
+
+// Maps the items of the scrapbook to their names and other metadata
+class ItemCatalogue
+{
+public:
+    // The catalogue, that is embedded into the library
+    static const ItemCatalogue& embedded();
+
+    // Loads the catalogue file at `path` on top of the embedded catalogue
+    explicit ItemCatalogue(const std::string& path);
+    ~ItemCatalogue();
+
+    ItemCatalogue(const ItemCatalogue&) = delete;
+    ItemCatalogue& operator=(const ItemCatalogue&) = delete;
+
+    ItemInfo lookup(const EquipmentIdent& ident) const;
+
+private:
+    explicit ItemCatalogue(const ffi::ItemCatalogue* catalogue);
+
+    const ffi::ItemCatalogue* m_catalogue;
+    bool m_isOwned;
+};
+
+} // namespace sf
@@ -145,4 +145,189 @@ enum class HellevatorTreatType
 }; // HellevatorTreatType
 
 
-} // namespace sf
\ No newline at end of file
+// The class of a character
+enum class ClassType
+{
+    Warrior,
+    Mage,
+    Scout,
+    Assassin,
+    BattleMage,
+    Berserker,
+    DemonHunter,
+    Druid,
+    Bard,
+    Necromancer,
+    Paladin
+}; // ClassType
+
+
+// The place, where an item is equipped
+enum class EquipmentSlotType
+{
+    Hat = 1,
+    BreastPlate,
+    Gloves,
+    FootWear,
+    Amulet,
+    Belt,
+    Ring,
+    Talisman,
+    Weapon,
+    Shield
+}; // EquipmentSlotType
+
+
+// The race of a character
+enum class RaceType
+{
+    Human = 1,
+    Elf,
+    Dwarf,
+    Gnome,
+    Orc,
+    DarkElf,
+    Goblin,
+    Demon
+}; // RaceType
+
+
+// A shop, that sells items
+enum class ShopType
+{
+    Weapon = 3,
+    Magic = 4
+}; // ShopType
+
+
+// All places, that items can be dragged to excluding companions
+enum class ItemPlaceType
+{
+    Equipment = 1,
+    MainInventory = 2,
+    WeaponShop = 3,
+    MageShop = 4,
+    FortressChest = 5
+}; // ItemPlace
+
+
+// The parts of the inventory, that are not equipment
+enum class InventoryType
+{
+    MainInventory = 2,
+    ExtendedInventory = 5
+}; // InventoryType
+
+
+enum class BlacksmithActionType
+{
+    Dismantle = 201,
+    SocketUpgrade = 202,
+    SocketUpgradeWithMushrooms = 212,
+    GemExtract = 203,
+    GemExtractWithMushrooms = 213,
+    Upgrade = 204
+}; // BlacksmithAction
+
+
+// The price you have to pay to roll the dice
+enum class RollDicePriceType
+{
+    Free,
+    Mushrooms,
+    Hourglass
+}; // RollDicePrice
+
+
+enum class DiceType
+{
+    ReRoll,
+    Silver,
+    Stone,
+    Wood,
+    Souls,
+    Arcane,
+    Hourglass
+}; // DiceType
+
+
+// Whether a dungeon id belongs to a light, or shadow dungeon
+enum class DungeonType
+{
+    Light,
+    Shadow
+}; // DungeonType
+
+
+enum class CompanionClassType
+{
+    Warrior,
+    Mage,
+    Scout
+}; // CompanionClass
+
+
+enum class HabitatType
+{
+    Shadow,
+    Light,
+    Earth,
+    Fire,
+    Water
+}; // HabitatType
+
+
+// The type of a building in the idle game
+enum class IdleBuildingType
+{
+    Seat = 1,
+    PopcornStand,
+    ParkingLot,
+    Trap,
+    Drinks,
+    DeadlyTrap,
+    VIPSeat,
+    Snacks,
+    StrayingMonsters,
+    Toilet
+}; // IdleBuildingType
+
+
+enum class GenderType
+{
+    Female,
+    Male
+}; // Gender
+
+
+enum class RelationshipType
+{
+    Ignored = -1,
+    Normal = 0,
+    Friend = 1
+}; // Relationship
+
+
+// The "Questing instead of expeditions" value in the settings
+enum class ExpeditionSettingType
+{
+    PreferExpeditions,
+    PreferQuests
+}; // ExpeditionSetting
+
+
+enum class EnchantmentType
+{
+    SwordOfVengeance = 11,
+    MariosBeard = 31,
+    ManyFeetBoots = 41,
+    ShadowOfTheCowboy = 51,
+    AdventurersArchaeologicalAura = 61,
+    ThirstyWanderer = 71,
+    UnholyAcquisitiveness = 81,
+    TheGraveRobbersPrayer = 91,
+    RobberBaronRitual = 101
+}; // Enchantment
+
+
+} // namespace sf
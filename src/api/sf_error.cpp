@@ -0,0 +1,53 @@
+#include "sf_error.h"
+
+#include "sf_api.hpp"
+
+namespace sf
+{
+
+Error::Error(ErrorKind kind, const std::string& message)
+    : std::runtime_error(message), m_kind(kind) { }
+
+ErrorKind Error::kind() const
+{
+    return m_kind;
+}
+
+InvalidRequestError::InvalidRequestError(const std::string& message) : Error(ErrorKind::InvalidRequest, message) { }
+EmptyResponseError::EmptyResponseError(const std::string& message) : Error(ErrorKind::EmptyResponse, message) { }
+ConnectionError::ConnectionError(const std::string& message) : Error(ErrorKind::ConnectionError, message) { }
+ParsingError::ParsingError(const std::string& message) : Error(ErrorKind::ParsingError, message) { }
+ServerError::ServerError(const std::string& message) : Error(ErrorKind::ServerError, message) { }
+UnsupportedVersionError::UnsupportedVersionError(const std::string& message) : Error(ErrorKind::UnsupportedVersion, message) { }
+TooShortResponseError::TooShortResponseError(const std::string& message) : Error(ErrorKind::TooShortResponse, message) { }
+InvalidArgumentError::InvalidArgumentError(const std::string& message) : Error(ErrorKind::InvalidArgument, message) { }
+SessionBusyError::SessionBusyError(const std::string& message) : Error(ErrorKind::SessionBusy, message) { }
+IOError::IOError(const std::string& message) : Error(ErrorKind::IOError, message) { }
+
+void throwLastError()
+{
+    ffi::CError error = ffi::take_last_error();
+    if (error.kind == ffi::CErrorKind::None)
+        return;
+
+    std::string message = error.message ? reinterpret_cast<const char*>(error.message) : "";
+    ffi::destr_error(error);
+
+    switch (error.kind)
+    {
+    case ffi::CErrorKind::InvalidRequest: throw InvalidRequestError(message);
+    case ffi::CErrorKind::EmptyResponse: throw EmptyResponseError(message);
+    case ffi::CErrorKind::ConnectionError: throw ConnectionError(message);
+    case ffi::CErrorKind::ParsingError: throw ParsingError(message);
+    case ffi::CErrorKind::ServerError: throw ServerError(message);
+    case ffi::CErrorKind::UnsupportedVersion: throw UnsupportedVersionError(message);
+    case ffi::CErrorKind::TooShortResponse: throw TooShortResponseError(message);
+    case ffi::CErrorKind::InvalidArgument: throw InvalidArgumentError(message);
+    case ffi::CErrorKind::SessionBusy: throw SessionBusyError(message);
+    case ffi::CErrorKind::IOError: throw IOError(message);
+    case ffi::CErrorKind::None: break;
+    }
+    throw Error(ErrorKind::None, message);
+}
+
+} // namespace sf
@@ -0,0 +1,62 @@
+#pragma once
+#include <stdexcept>
+#include <string>
+
+
+namespace sf
+{
+
+// The kind of error, that a call into the api has failed with. Mirrors SFError
+enum class ErrorKind
+{
+    None,
+    InvalidRequest,
+    EmptyResponse,
+    ConnectionError,
+    ParsingError,
+    ServerError,
+    UnsupportedVersion,
+    TooShortResponse,
+    InvalidArgument,
+    SessionBusy,
+    IOError
+}; // CErrorKind
+
+
+// The base of all the errors thrown by Session
+class Error : public std::runtime_error
+{
+public:
+    Error(ErrorKind kind, const std::string& message);
+
+    ErrorKind kind() const;
+
+private:
+    ErrorKind m_kind;
+};
+
+// The request could not be sent, because it was invalid
+class InvalidRequestError : public Error { public: explicit InvalidRequestError(const std::string& message); };
+// The server replied with an empty response
+class EmptyResponseError : public Error { public: explicit EmptyResponseError(const std::string& message); };
+// The server, or the connection to it is down
+class ConnectionError : public Error { public: explicit ConnectionError(const std::string& message); };
+// The response of the server could not be parsed
+class ParsingError : public Error { public: explicit ParsingError(const std::string& message); };
+// The server responded with an error, like not having enough silver. what() is the raw error of the server
+class ServerError : public Error { public: explicit ServerError(const std::string& message); };
+// The server version is newer, than what the api supports
+class UnsupportedVersionError : public Error { public: explicit UnsupportedVersionError(const std::string& message); };
+// The response of the server was too short
+class TooShortResponseError : public Error { public: explicit TooShortResponseError(const std::string& message); };
+// A value passed to the api was invalid
+class InvalidArgumentError : public Error { public: explicit InvalidArgumentError(const std::string& message); };
+// The session is still running a command in the background
+class SessionBusyError : public Error { public: explicit SessionBusyError(const std::string& message); };
+// A local file, like a server list, or a cassette, could not be read, or written
+class IOError : public Error { public: explicit IOError(const std::string& message); };
+
+// Throws the error of the last failed api call on this thread as the matching exception. Does nothing, if there is none
+void throwLastError();
+
+} // namespace sf
@@ -0,0 +1,250 @@
+#include "sf_gamestate.h"
+
+#include "sf_api.hpp"
+#include "sf_error.h"
+
+namespace
+{
+
+using ffi_char_t = int8_t;
+
+std::string take_ffi_str(ffi_char_t* ffiStr)
+{
+    if (!ffiStr)
+        return {};
+
+    std::string str = reinterpret_cast<const char*>(ffiStr);
+    ffi::destr_string(ffiStr);
+    return str;
+}
+
+size_t attribute_index(sf::AttributeType attribute)
+{
+    return static_cast<size_t>(attribute) - 1;
+}
+
+sf::Character character_from_ffi(const ffi::Character* character)
+{
+    sf::Character res;
+    res.playerId = ffi::character_player_id(character);
+    res.name = take_ffi_str(ffi::character_name(character));
+    res.level = ffi::character_level(character);
+    res.silver = ffi::character_silver(character);
+    res.mushrooms = ffi::character_mushrooms(character);
+    res.classType = static_cast<sf::ClassType>(ffi::character_class(character));
+    res.race = static_cast<sf::RaceType>(ffi::character_race(character));
+    res.experience = ffi::character_experience(character);
+    res.nextLevelXp = ffi::character_next_level_xp(character);
+    res.honor = ffi::character_honor(character);
+    res.rank = ffi::character_rank(character);
+    res.armor = ffi::character_armor(character);
+
+    for (uint8_t i = 0; i < res.attributeBasisValues.size(); ++i)
+    {
+        res.attributeBasisValues[i] = ffi::character_attribute_basis(character, i + 1);
+        res.attributeAdditionsValues[i] = ffi::character_attribute_additions(character, i + 1);
+    }
+    return res;
+}
+
+sf::Tavern tavern_from_ffi(const ffi::Tavern* tavern)
+{
+    sf::Tavern res;
+    res.thirstForAdventureSec = ffi::tavern_thirst_for_adventure_sec(tavern);
+    res.beerDrunk = ffi::tavern_beer_drunk(tavern);
+    res.quicksandGlasses = ffi::tavern_quicksand_glasses(tavern);
+    res.mushroomSkipAllowed = ffi::tavern_mushroom_skip_allowed(tavern);
+
+    for (size_t i = 0; i < res.quests.size(); ++i)
+    {
+        const ffi::Quest* quest = ffi::tavern_quest(tavern, i);
+        res.quests[i].baseLength = ffi::quest_base_length(quest);
+        res.quests[i].baseSilver = ffi::quest_base_silver(quest);
+        res.quests[i].baseExperience = ffi::quest_base_experience(quest);
+        res.quests[i].location = ffi::quest_location(quest);
+        res.quests[i].monsterId = ffi::quest_monster_id(quest);
+        res.quests[i].hasItem = ffi::quest_has_item(quest);
+    }
+    return res;
+}
+
+sf::Arena arena_from_ffi(const ffi::Arena* arena)
+{
+    sf::Arena res;
+    for (size_t i = 0; i < res.enemyIds.size(); ++i)
+        res.enemyIds[i] = ffi::arena_enemy_id(arena, i);
+
+    int64_t nextFreeFight = ffi::arena_next_free_fight(arena);
+    if (nextFreeFight != 0)
+        res.nextFreeFight = static_cast<std::time_t>(nextFreeFight);
+
+    res.fightsForXp = ffi::arena_fights_for_xp(arena);
+    return res;
+}
+
+sf::Guild guild_from_ffi(const ffi::Guild* guild)
+{
+    sf::Guild res;
+    res.id = ffi::guild_id(guild);
+    res.name = take_ffi_str(ffi::guild_name(guild));
+    res.honor = ffi::guild_honor(guild);
+    res.rank = ffi::guild_rank(guild);
+    res.memberCount = ffi::guild_member_count(guild);
+    res.finishedRaids = ffi::guild_finished_raids(guild);
+    return res;
+}
+
+sf::Fortress fortress_from_ffi(const ffi::Fortress* fortress)
+{
+    sf::Fortress res;
+    for (uint8_t i = 0; i < res.buildingLevels.size(); ++i)
+        res.buildingLevels[i] = ffi::fortress_building_level(fortress, i);
+
+    for (uint8_t i = 0; i < res.unitCounts.size(); ++i)
+        res.unitCounts[i] = ffi::fortress_unit_count(fortress, i);
+
+    for (uint8_t i = 0; i < res.resourcesCurrent.size(); ++i)
+    {
+        res.resourcesCurrent[i] = ffi::fortress_resource_current(fortress, i);
+        res.resourcesLimit[i] = ffi::fortress_resource_limit(fortress, i);
+    }
+
+    res.upgrades = ffi::fortress_upgrades(fortress);
+    res.honor = ffi::fortress_honor(fortress);
+
+    uint32_t rank = ffi::fortress_rank(fortress);
+    if (rank != 0)
+        res.rank = rank;
+
+    res.wallCombatLvl = ffi::fortress_wall_combat_lvl(fortress);
+    return res;
+}
+
+} // namespace
+
+
+namespace sf
+{
+
+uint32_t Character::attributeBasis(AttributeType attribute) const
+{
+    return attributeBasisValues.at(attribute_index(attribute));
+}
+
+uint32_t Character::attributeAdditions(AttributeType attribute) const
+{
+    return attributeAdditionsValues.at(attribute_index(attribute));
+}
+
+uint16_t Fortress::buildingLevel(FortressBuildingType building) const
+{
+    return buildingLevels.at(static_cast<size_t>(building));
+}
+
+uint16_t Fortress::unitCount(FortressUnitType unit) const
+{
+    return unitCounts.at(static_cast<size_t>(unit));
+}
+
+uint64_t Fortress::resourceCurrent(FortressResourceType resource) const
+{
+    return resourcesCurrent.at(static_cast<size_t>(resource));
+}
+
+uint64_t Fortress::resourceLimit(FortressResourceType resource) const
+{
+    return resourcesLimit.at(static_cast<size_t>(resource));
+}
+
+EquipmentIdent EquipmentIdent::fromFfi(const ffi::CEquipmentIdent& ident)
+{
+    EquipmentIdent item;
+    if (ident.class_id != 0)
+        item.classType = static_cast<ClassType>(ident.class_id - 1);
+    item.slot = static_cast<EquipmentSlotType>(ident.slot);
+    item.modelId = ident.model_id;
+    item.color = ident.color;
+    item.isEpic = ident.is_epic;
+    return item;
+}
+
+std::vector<EquipmentIdent> EquipmentIdent::fromFfi(ffi::CEquipmentIdent* idents, size_t len)
+{
+    std::vector<EquipmentIdent> res;
+    res.reserve(len);
+    for (size_t i = 0; i < len; ++i)
+        res.push_back(fromFfi(idents[i]));
+
+    ffi::destr_equipment_idents(idents, len);
+    return res;
+}
+
+FightOutcome FightOutcome::fromFfi(const ffi::CFightOutcome& outcome)
+{
+    FightOutcome res;
+    res.won = outcome.won;
+    res.honorChange = outcome.honor_change;
+    res.silver = outcome.silver;
+    res.experience = outcome.experience;
+    res.mushrooms = outcome.mushrooms;
+    res.rankBefore = outcome.rank_before;
+    res.rankAfter = outcome.rank_after;
+    if (outcome.has_item)
+        res.item = EquipmentIdent::fromFfi(outcome.item);
+    return res;
+}
+
+OtherPlayer OtherPlayer::fromFfi(const ffi::OtherPlayer* player)
+{
+    OtherPlayer res;
+    res.playerId = ffi::other_player_id(player);
+    res.name = take_ffi_str(ffi::other_player_name(player));
+    res.level = ffi::other_player_level(player);
+    res.classType = static_cast<ClassType>(ffi::other_player_class(player));
+    return res;
+}
+
+HallOfFamePlayer HallOfFamePlayer::fromFfi(const ffi::HallOfFamePlayer* player)
+{
+    HallOfFamePlayer res;
+    res.rank = ffi::hof_player_rank(player);
+    res.name = take_ffi_str(ffi::hof_player_name(player));
+    if (ffi_char_t* guild = ffi::hof_player_guild(player))
+        res.guild = take_ffi_str(guild);
+    res.level = ffi::hof_player_level(player);
+    res.honor = ffi::hof_player_honor(player);
+    res.classType = static_cast<ClassType>(ffi::hof_player_class(player));
+    return res;
+}
+
+GameState GameState::fromFfi(const ffi::GameState* gameState)
+{
+    GameState res;
+    res.character = character_from_ffi(ffi::game_state_character(gameState));
+    res.tavern = tavern_from_ffi(ffi::game_state_tavern(gameState));
+    res.arena = arena_from_ffi(ffi::game_state_arena(gameState));
+
+    if (const ffi::Guild* guild = ffi::game_state_guild(gameState))
+        res.guild = guild_from_ffi(guild);
+
+    if (const ffi::Fortress* fortress = ffi::game_state_fortress(gameState))
+        res.fortress = fortress_from_ffi(fortress);
+
+    return res;
+}
+
+GameState GameState::fromResponseJson(const std::string& json)
+{
+    ffi::GameState* gameState = ffi::game_state_from_response_json(reinterpret_cast<const ffi_char_t*>(json.c_str()));
+    if (!gameState)
+    {
+        throwLastError();
+        throw std::runtime_error("Could not load the game state");
+    }
+
+    GameState res = fromFfi(gameState);
+    ffi::destr_game_state(gameState);
+    return res;
+}
+
+} // namespace sf
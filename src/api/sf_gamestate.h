@@ -0,0 +1,182 @@
+#pragma once
+#include <array>
+#include <cstdint>
+#include <ctime>
+#include <optional>
+#include <string>
+#include <vector>
+#include "sf_enums.h"
+
+
+namespace ffi
+{
+    class GameState;
+    struct CEquipmentIdent;
+    struct CFightOutcome;
+    struct HallOfFamePlayer;
+    struct OtherPlayer;
+}
+
+namespace sf
+{
+
+struct Character
+{
+    uint32_t playerId;
+    std::string name;
+    uint16_t level;
+    uint64_t silver;
+    uint32_t mushrooms;
+    ClassType classType;
+    RaceType race;
+    uint64_t experience;
+    uint64_t nextLevelXp;
+    uint32_t honor;
+    uint32_t rank;
+    uint64_t armor;
+
+    uint32_t attributeBasis(AttributeType attribute) const;
+    uint32_t attributeAdditions(AttributeType attribute) const;
+
+    std::array<uint32_t, 5> attributeBasisValues;
+    std::array<uint32_t, 5> attributeAdditionsValues;
+};
+
+
+struct Quest
+{
+    uint32_t baseLength;
+    uint32_t baseSilver;
+    uint32_t baseExperience;
+    uint8_t location;
+    uint16_t monsterId;
+    bool hasItem;
+};
+
+
+struct Tavern
+{
+    uint32_t thirstForAdventureSec;
+    uint8_t beerDrunk;
+    uint32_t quicksandGlasses;
+    bool mushroomSkipAllowed;
+    std::array<Quest, 3> quests;
+};
+
+
+struct Arena
+{
+    std::array<uint32_t, 3> enemyIds;
+    std::optional<std::time_t> nextFreeFight;
+    uint8_t fightsForXp;
+};
+
+
+struct Guild
+{
+    uint32_t id;
+    std::string name;
+    uint32_t honor;
+    uint32_t rank;
+    size_t memberCount;
+    uint16_t finishedRaids;
+};
+
+
+struct Fortress
+{
+    uint16_t buildingLevel(FortressBuildingType building) const;
+    uint16_t unitCount(FortressUnitType unit) const;
+    uint64_t resourceCurrent(FortressResourceType resource) const;
+    uint64_t resourceLimit(FortressResourceType resource) const;
+
+    std::array<uint16_t, 12> buildingLevels;
+    std::array<uint16_t, 3> unitCounts;
+    std::array<uint64_t, 3> resourcesCurrent;
+    std::array<uint64_t, 3> resourcesLimit;
+    uint16_t upgrades;
+    uint32_t honor;
+    std::optional<uint32_t> rank;
+    uint16_t wallCombatLvl;
+};
+
+
+// The identification of an item in the scrapbook
+struct EquipmentIdent
+{
+    static EquipmentIdent fromFfi(const ffi::CEquipmentIdent& ident);
+    static std::vector<EquipmentIdent> fromFfi(ffi::CEquipmentIdent* idents, size_t len);
+
+    std::optional<ClassType> classType;
+    EquipmentSlotType slot;
+    uint16_t modelId;
+    uint8_t color;
+    bool isEpic;
+};
+
+
+// The result of a fight from the perspective of the own character
+struct FightOutcome
+{
+    static FightOutcome fromFfi(const ffi::CFightOutcome& outcome);
+
+    bool won;
+    int64_t honorChange;
+    int64_t silver;
+    uint64_t experience;
+    uint8_t mushrooms;
+    uint32_t rankBefore;
+    uint32_t rankAfter;
+    // The equipment this fight gave the character, if any
+    std::optional<EquipmentIdent> item;
+};
+
+
+// The silver, or mushrooms won (positive), or lost (negative) gambling
+struct GambleResult
+{
+    bool mushrooms;
+    int64_t change;
+};
+
+
+// A player, that has been looked at with Session::viewPlayer()
+struct OtherPlayer
+{
+    static OtherPlayer fromFfi(const ffi::OtherPlayer* player);
+
+    uint32_t playerId;
+    std::string name;
+    uint16_t level;
+    ClassType classType;
+};
+
+
+struct HallOfFamePlayer
+{
+    static HallOfFamePlayer fromFfi(const ffi::HallOfFamePlayer* player);
+
+    uint32_t rank;
+    std::string name;
+    std::optional<std::string> guild;
+    uint32_t level;
+    uint32_t honor;
+    ClassType classType;
+};
+
+
+// A copy of the parsed state of the game at the time of the last command
+struct GameState
+{
+    static GameState fromFfi(const ffi::GameState* gameState);
+    // The game state of a login response, that has been saved with CommandResponse::toJson()
+    static GameState fromResponseJson(const std::string& json);
+
+    Character character;
+    Tavern tavern;
+    Arena arena;
+    std::optional<Guild> guild;
+    std::optional<Fortress> fortress;
+};
+
+} // namespace sf
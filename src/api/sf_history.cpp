@@ -0,0 +1,56 @@
+#include "sf_history.h"
+
+#include "sf_api.hpp"
+#include <stdexcept>
+
+namespace
+{
+
+using ffi_char_t = int8_t;
+
+const ffi_char_t* to_ffi_str(const std::string& str)
+{
+    return reinterpret_cast<const ffi_char_t*>(str.c_str());
+}
+
+} // namespace
+
+
+namespace sf
+{
+
+ScrapbookHistory::ScrapbookHistory(const std::string& dir)
+{
+    m_history = ffi::init_scrapbook_history(to_ffi_str(dir));
+    if (!m_history)
+        throw std::runtime_error("Failed to open scrapbook history");
+}
+
+ScrapbookHistory::~ScrapbookHistory()
+{
+    if (m_history)
+        ffi::destr_scrapbook_history(m_history);
+}
+
+std::vector<EquipmentIdent> ScrapbookHistory::gainedSince(const std::string& server, uint32_t playerId, std::time_t since) const
+{
+    size_t len;
+    ffi::CEquipmentIdent* idents = ffi::scrapbook_history_gained_since(m_history, to_ffi_str(server), playerId, since, &len);
+    return EquipmentIdent::fromFfi(idents, len);
+}
+
+std::vector<ScrapbookCompletion> ScrapbookHistory::completionOverTime(const std::string& server, uint32_t playerId) const
+{
+    size_t len;
+    ffi::CScrapBookCompletion* completions = ffi::scrapbook_history_completion(m_history, to_ffi_str(server), playerId, &len);
+
+    std::vector<ScrapbookCompletion> res;
+    res.reserve(len);
+    for (size_t i = 0; i < len; ++i)
+        res.push_back({static_cast<std::time_t>(completions[i].timestamp), completions[i].percent});
+
+    ffi::destr_scrapbook_completions(completions, len);
+    return res;
+}
+
+} // namespace sf
@@ -0,0 +1,45 @@
+#pragma once
+#include <cstdint>
+#include <ctime>
+#include <string>
+#include <vector>
+#include "sf_gamestate.h"
+
+
+namespace ffi
+{
+    struct ScrapBookHistory;
+}
+
+namespace sf
+{
+
+// The completion of the scrapbook at one point in time
+struct ScrapbookCompletion
+{
+    std::time_t time;
+    double percent;
+};
+
+
+// The scrapbooks of characters, that have been recorded on disk over time. Use Session::setScrapbookHistory() to record them
+class ScrapbookHistory
+{
+public:
+    explicit ScrapbookHistory(const std::string& dir);
+    ~ScrapbookHistory();
+
+    ScrapbookHistory(const ScrapbookHistory&) = delete;
+    ScrapbookHistory& operator=(const ScrapbookHistory&) = delete;
+
+    // All the items the character has collected since the given time. For the items gained this week, use now - 7 days
+    std::vector<EquipmentIdent> gainedSince(const std::string& server, uint32_t playerId, std::time_t since) const;
+
+    // The completion of the scrapbook for every recorded day. The oldest one is at the front
+    std::vector<ScrapbookCompletion> completionOverTime(const std::string& server, uint32_t playerId) const;
+
+private:
+    ffi::ScrapBookHistory* m_history;
+};
+
+} // namespace sf
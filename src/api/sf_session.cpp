@@ -2,7 +2,10 @@
 
 #include "assert.h"
 #include "sf_api.hpp"
+#include "sf_error.h"
+#include <cstdio>
 #include <iostream>
+#include <limits>
 
 namespace
 {
@@ -19,6 +22,44 @@ std::string to_str(const ffi_char_t* ffiStr)
     return reinterpret_cast<const char*>(ffiStr);
 }
 
+// Creates a session, that has not been logged in yet, and makes it use a cassette
+ffi::GameSession* init_cassette_session(const std::string& username, const std::string& password,
+    const std::string& serverUrl, const std::string& cassettePath, bool (*useCassette)(ffi::GameSession*, const ffi_char_t*))
+{
+    ffi::GameSession* session = ffi::init_session(to_ffi_str(username), to_ffi_str(password), to_ffi_str(serverUrl));
+    if (!session)
+    {
+        sf::throwLastError();
+        throw std::runtime_error("Failed to create session");
+    }
+
+    if (!useCassette(session, to_ffi_str(cassettePath)))
+    {
+        ffi::destr_session(session);
+        sf::throwLastError();
+        throw std::runtime_error("Failed to use the cassette");
+    }
+    return session;
+}
+
+// Throws the reason, if a command has failed. Commands, that had nothing to do, return null without an error
+ffi::Response* checked(ffi::Response* response)
+{
+    if (!response)
+        sf::throwLastError();
+    return response;
+}
+
+ffi::CRateBudget to_ffi(const sf::RateBudget& budget)
+{
+    return {budget.requests, static_cast<uint64_t>(budget.interval.count()), budget.burst};
+}
+
+sf::RateBudget from_ffi(const ffi::CRateBudget& budget)
+{
+    return {budget.requests, std::chrono::milliseconds(budget.interval_ms), budget.burst};
+}
+
 } // namespace
 
 
@@ -29,11 +70,32 @@ Session::Session(const std::string& username, const std::string& password, const
 {
     m_session = ffi::init_session(to_ffi_str(username), to_ffi_str(password), to_ffi_str(serverUrl));
     if (!m_session)
+    {
+        throwLastError();
         throw std::runtime_error("Failed to create session");
+    }
 
     bool isLoginSuccessful = ffi::login(m_session);
     if (!isLoginSuccessful)
+    {
+        ffi::destr_session(m_session);
+        throwLastError();
         throw std::runtime_error("Invalid login credentials");
+    }
+}
+
+Session::Session(ffi::GameSession* session)
+    : m_session(session)
+{
+    if (!m_session)
+        throw InvalidArgumentError("The session is null");
+
+    if (!ffi::login(m_session))
+    {
+        ffi::destr_session(m_session);
+        throwLastError();
+        throw std::runtime_error("Invalid login credentials");
+    }
 }
 
 Session::~Session()
@@ -42,114 +104,613 @@ Session::~Session()
         ffi::destr_session(m_session);
 }
 
+std::optional<GameState> Session::gameState() const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    if (!gameState)
+        return std::nullopt;
+
+    return GameState::fromFfi(gameState);
+}
+
+namespace
+{
+
+std::vector<EquipmentIdent> missing_scrapbook_items(const ffi::GameState* gameState, const ffi::OtherPlayer* other)
+{
+    const ffi::ScrapBook* scrapbook = ffi::character_scrapbook(ffi::game_state_character(gameState));
+    if (!scrapbook || !other)
+        return {};
+
+    size_t len;
+    ffi::CEquipmentIdent* idents = ffi::scrapbook_missing_items(scrapbook, other, &len);
+    return EquipmentIdent::fromFfi(idents, len);
+}
+
+std::optional<double> win_probability(const ffi::GameState* gameState, const ffi::OtherPlayer* other, uint32_t iterations)
+{
+    if (!gameState || !other)
+        return std::nullopt;
+
+    return ffi::game_state_win_probability(gameState, other, iterations);
+}
+
+std::optional<FightEstimate> estimate_fight(const ffi::GameState* gameState, const ffi::OtherPlayer* other,
+    const EstimateOptions& options)
+{
+    if (!gameState || !other)
+        return std::nullopt;
+
+    ffi::CEstimateOptions ffiOptions{options.samples, options.targetMargin, options.threads, options.seed};
+    ffi::CFightEstimate estimate;
+    if (!ffi::game_state_estimate_fight(gameState, other, ffiOptions, &estimate))
+        return std::nullopt;
+
+    auto toDistribution = [](const ffi::CDamageDistribution& damage) {
+        return DamageDistribution{damage.min, damage.max, damage.mean, damage.std_dev};
+    };
+    return FightEstimate{estimate.samples, estimate.wins, estimate.win_rate, estimate.lower, estimate.upper,
+        estimate.average_rounds, toDistribution(estimate.own_damage), toDistribution(estimate.other_damage)};
+}
+
+std::optional<std::string> simulate_fight_json(const ffi::GameState* gameState, const ffi::OtherPlayer* other,
+    uint64_t seed)
+{
+    if (!gameState || !other)
+        return std::nullopt;
+
+    ffi_char_t* json = ffi::game_state_simulate_fight_json(gameState, other, seed);
+    if (!json)
+        throwLastError();
+
+    std::string res = to_str(json);
+    ffi::destr_string(json);
+    return res;
+}
+
+} // namespace
+
+std::vector<EquipmentIdent> Session::missingScrapbookItems(const std::string& playerName) const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    return missing_scrapbook_items(gameState, ffi::game_state_lookup_name(gameState, to_ffi_str(playerName)));
+}
+
+std::vector<EquipmentIdent> Session::missingScrapbookItems(uint32_t playerId) const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    return missing_scrapbook_items(gameState, ffi::game_state_lookup_pid(gameState, playerId));
+}
+
+std::optional<double> Session::winProbability(const std::string& playerName, uint32_t iterations) const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    return win_probability(gameState, ffi::game_state_lookup_name(gameState, to_ffi_str(playerName)), iterations);
+}
+
+std::optional<double> Session::winProbability(uint32_t playerId, uint32_t iterations) const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    return win_probability(gameState, ffi::game_state_lookup_pid(gameState, playerId), iterations);
+}
+
+std::optional<FightEstimate> Session::estimateFight(const std::string& playerName, const EstimateOptions& options) const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    return estimate_fight(gameState, ffi::game_state_lookup_name(gameState, to_ffi_str(playerName)), options);
+}
+
+std::optional<FightEstimate> Session::estimateFight(uint32_t playerId, const EstimateOptions& options) const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    return estimate_fight(gameState, ffi::game_state_lookup_pid(gameState, playerId), options);
+}
+
+std::optional<std::string> Session::simulateFightJson(const std::string& playerName, uint64_t seed) const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    return simulate_fight_json(gameState, ffi::game_state_lookup_name(gameState, to_ffi_str(playerName)), seed);
+}
+
+std::optional<std::string> Session::simulateFightJson(uint32_t playerId, uint64_t seed) const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    return simulate_fight_json(gameState, ffi::game_state_lookup_pid(gameState, playerId), seed);
+}
+
+double FightEstimate::margin() const
+{
+    return (upper - lower) / 2.0;
+}
+
+std::string FightEstimate::toString() const
+{
+    char buffer[32];
+    std::snprintf(buffer, sizeof(buffer), "%.0f%% \u00b1%.0f%%", winRate * 100.0, margin() * 100.0);
+    return buffer;
+}
+
+void Session::setScrapbookHistory(const std::string& dir)
+{
+    ffi::session_set_scrapbook_history(m_session, to_ffi_str(dir));
+}
+
+std::optional<std::string> Session::gameStateJson(const std::string& field) const
+{
+    const ffi::GameState* gameState = ffi::session_game_state(m_session);
+    if (!gameState)
+        return std::nullopt;
+
+    ffi_char_t* json = field.empty()
+        ? ffi::game_state_to_json(gameState)
+        : ffi::game_state_subtree_to_json(gameState, to_ffi_str(field));
+    if (!json)
+        throwLastError();
+
+    std::string res = to_str(json);
+    ffi::destr_string(json);
+    return res;
+}
+
+std::string Session::serverHost() const
+{
+    ffi_char_t* host = ffi::session_server_host(m_session);
+    if (!host)
+        return {};
+
+    std::string res = to_str(host);
+    ffi::destr_string(host);
+    return res;
+}
+
+namespace
+{
+
+ffi::CQuestWeights to_ffi_weights(const QuestWeights& weights)
+{
+    return { weights.experience, weights.silver, weights.newMonster, weights.newItem };
+}
+
+} // namespace
+
+std::vector<QuestRating> Session::rateQuests(const QuestWeights& weights) const
+{
+    size_t len;
+    ffi::CQuestRating* ratings = ffi::game_state_rate_quests(ffi::session_game_state(m_session), to_ffi_weights(weights), &len);
+    if (!ratings)
+        return {};
+
+    std::vector<QuestRating> res;
+    res.reserve(len);
+    for (size_t i = 0; i < len; ++i)
+    {
+        const ffi::CQuestRating& r = ratings[i];
+        res.push_back({ r.quest_pos, r.experience_per_sec, r.silver_per_sec, r.new_monster, r.new_item, r.startable, r.score });
+    }
+    ffi::destr_quest_ratings(ratings, len);
+    return res;
+}
+
+std::optional<size_t> Session::recommendedQuest(const QuestWeights& weights) const
+{
+    size_t questPos;
+    if (!ffi::game_state_recommended_quest(ffi::session_game_state(m_session), to_ffi_weights(weights), &questPos))
+        return std::nullopt;
+
+    return questPos;
+}
+
+CommandResponse Session::startRecommendedQuest(const QuestWeights& weights)
+{
+    return checked(ffi::exec_RecommendedQuest(m_session, to_ffi_weights(weights)));
+}
+
+namespace
+{
+
+// Invokes and frees the onFinished callback of runAsync()
+void on_command_finished(void* userData)
+{
+    auto* onFinished = static_cast<std::function<void()>*>(userData);
+    if (*onFinished)
+        (*onFinished)();
+    delete onFinished;
+}
+
+} // namespace
+
+PendingCommand Session::runAsync(const std::function<CommandResponse(Session&)>& command, std::function<void()> onFinished)
+{
+    auto* userData = new std::function<void()>(std::move(onFinished));
+    ffi::begin_async_command(on_command_finished, userData);
+    try
+    {
+        command(*this);
+    }
+    catch (...)
+    {
+        ffi::end_async_command();
+        delete userData;
+        throw;
+    }
+
+    ffi::CommandTicket* ticket = ffi::end_async_command();
+    if (!ticket)
+    {
+        delete userData;
+        throwLastError();
+        throw InvalidArgumentError("The command did not call the session");
+    }
+    return PendingCommand(ticket);
+}
+
+RateLimit RateLimit::conservative()
+{
+    ffi::CRateLimit limit = ffi::conservative_rate_limit();
+    return {from_ffi(limit.per_session), from_ffi(limit.per_server), std::chrono::milliseconds(limit.jitter_ms)};
+}
+
+ServerConnection::ServerConnection(const std::string& serverUrl, const RateLimit& limit)
+{
+    ffi::CRateLimit ffiLimit{
+        to_ffi(limit.perSession),
+        to_ffi(limit.perServer),
+        static_cast<uint64_t>(limit.jitter.count()),
+    };
+    m_connection = ffi::init_server_connection(to_ffi_str(serverUrl), ffiLimit);
+    if (!m_connection)
+    {
+        throwLastError();
+        throw InvalidArgumentError("Invalid server url");
+    }
+}
+
+ServerConnection::~ServerConnection()
+{
+    if (m_connection)
+        ffi::destr_server_connection(m_connection);
+}
+
+std::unique_ptr<Session> ServerConnection::login(const std::string& username, const std::string& password) const
+{
+    ffi::GameSession* session = ffi::init_session_on(m_connection, to_ffi_str(username), to_ffi_str(password));
+    if (!session)
+    {
+        throwLastError();
+        throw std::runtime_error("Failed to create session");
+    }
+    return std::make_unique<Session>(session);
+}
+
+PendingCommand::PendingCommand(ffi::CommandTicket* ticket)
+    : m_ticket(ticket) { }
+
+PendingCommand::~PendingCommand()
+{
+    if (m_ticket)
+        ffi::destr_ticket(m_ticket);
+}
+
+PendingCommand::PendingCommand(PendingCommand&& other) noexcept
+    : m_ticket(other.m_ticket)
+{
+    other.m_ticket = nullptr;
+}
+
+PendingCommand& PendingCommand::operator=(PendingCommand&& other) noexcept
+{
+    if (this != &other)
+    {
+        if (m_ticket)
+            ffi::destr_ticket(m_ticket);
+        m_ticket = other.m_ticket;
+        other.m_ticket = nullptr;
+    }
+    return *this;
+}
+
+bool PendingCommand::isReady() const
+{
+    return ffi::ticket_poll(m_ticket);
+}
+
+bool PendingCommand::waitFor(std::chrono::milliseconds timeout) const
+{
+    return ffi::ticket_wait_for(m_ticket, static_cast<uint64_t>(timeout.count()));
+}
+
+CommandResponse PendingCommand::wait()
+{
+    return checked(ffi::ticket_wait(m_ticket));
+}
+
+void Session::renewSsoCredentials()
+{
+    if (!ffi::session_renew_sso_creds(m_session))
+        throwLastError();
+}
+
+std::unique_ptr<Session> Session::recording(const std::string& username, const std::string& password,
+    const std::string& serverUrl, const std::string& cassettePath)
+{
+    return std::make_unique<Session>(
+        init_cassette_session(username, password, serverUrl, cassettePath, ffi::session_record_cassette));
+}
+
+std::unique_ptr<Session> Session::replaying(const std::string& username, const std::string& password,
+    const std::string& serverUrl, const std::string& cassettePath)
+{
+    return std::make_unique<Session>(
+        init_cassette_session(username, password, serverUrl, cassettePath, ffi::session_replay_cassette));
+}
+
+void Session::recordCassette(const std::string& path)
+{
+    if (!ffi::session_record_cassette(m_session, to_ffi_str(path)))
+        throwLastError();
+}
+
+void Session::replayCassette(const std::string& path)
+{
+    if (!ffi::session_replay_cassette(m_session, to_ffi_str(path)))
+        throwLastError();
+}
+
+RecoveryPolicy Session::recoveryPolicy() const
+{
+    ffi::CRecoveryPolicy policy = ffi::session_recovery_policy(m_session);
+    return {
+        policy.max_retries,
+        std::chrono::milliseconds(policy.backoff_ms),
+        std::chrono::milliseconds(policy.max_backoff_ms),
+        policy.relogin,
+        policy.renew_sso,
+    };
+}
+
+void Session::setRecoveryPolicy(const RecoveryPolicy& policy)
+{
+    ffi::CRecoveryPolicy ffiPolicy = {
+        policy.maxRetries,
+        static_cast<uint64_t>(policy.backoff.count()),
+        static_cast<uint64_t>(policy.maxBackoff.count()),
+        policy.relogin,
+        policy.renewSso,
+    };
+    if (!ffi::session_set_recovery_policy(m_session, ffiPolicy))
+        throwLastError();
+}
+
+namespace
+{
+
+void on_recovery_event(ffi::CRecoveryEvent event, void* userData)
+{
+    auto* callback = static_cast<std::function<void(const RecoveryEvent&)>*>(userData);
+    if (*callback)
+        (*callback)({ static_cast<RecoveryEventKind>(event.kind), event.attempt, static_cast<ErrorKind>(event.error) });
+}
+
+} // namespace
+
+void Session::onRecovery(std::function<void(const RecoveryEvent&)> callback)
+{
+    if (!ffi::session_set_recovery_callback(m_session, on_recovery_event, &m_onRecovery))
+        throwLastError();
+    m_onRecovery = std::move(callback);
+}
+
 // ###################  No-argument commands  ################### //
 
-CommandResponse Session::update() { return ffi::exec_Update(m_session); }
-CommandResponse Session::buyBeer() { return ffi::exec_BuyBeer(m_session); }
-CommandResponse Session::cancelQuest() { return ffi::exec_CancelQuest(m_session); }
-CommandResponse Session::finishWork() { return ffi::exec_FinishWork(m_session); }
-CommandResponse Session::checkArena() { return ffi::exec_CheckArena(m_session); }
-CommandResponse Session::collectCalendar() { return ffi::exec_CollectCalendar(m_session); }
-CommandResponse Session::toiletFlush() { return ffi::exec_ToiletFlush(m_session); }
-CommandResponse Session::toiletOpen() { return ffi::exec_ToiletOpen(m_session); }
-CommandResponse Session::cancelWork() { return ffi::exec_CancelWork(m_session); }
-CommandResponse Session::guildLoadMushrooms() { return ffi::exec_GuildLoadMushrooms(m_session); }
-CommandResponse Session::guildJoinAttack() { return ffi::exec_GuildJoinAttack(m_session); }
-CommandResponse Session::guildJoinDefense() { return ffi::exec_GuildJoinDefense(m_session); }
-CommandResponse Session::guildRaid() { return ffi::exec_GuildRaid(m_session); }
-CommandResponse Session::guildPortalBattle() { return ffi::exec_GuildPortalBattle(m_session); }
-CommandResponse Session::guildGetFightableTargets() { return ffi::exec_GuildGetFightableTargets(m_session); }
-CommandResponse Session::viewScrapbook() { return ffi::exec_ViewScrapbook(m_session); }
-CommandResponse Session::fightPortal() { return ffi::exec_FightPortal(m_session); }
-CommandResponse Session::swapManequin() { return ffi::exec_SwapManequin(m_session); }
-CommandResponse Session::idleSacrifice() { return ffi::exec_IdleSacrifice(m_session); }
-CommandResponse Session::hellevatorEnter() { return ffi::exec_HellevatorEnter(m_session); }
-CommandResponse Session::hellevatorViewGuildRanking() { return ffi::exec_HellevatorViewGuildRanking(m_session); }
-CommandResponse Session::hellevatorRefreshShop() { return ffi::exec_HellevatorRefreshShop(m_session); }
-CommandResponse Session::hellevatorClaimDaily() { return ffi::exec_HellevatorClaimDaily(m_session); }
-CommandResponse Session::hellevatorClaimDailyYesterday() { return ffi::exec_HellevatorClaimDailyYesterday(m_session); }
-CommandResponse Session::hellevatorClaimFinal() { return ffi::exec_HellevatorClaimFinal(m_session); }
-CommandResponse Session::hellevatorPreviewRewards() { return ffi::exec_HellevatorPreviewRewards(m_session); }
-CommandResponse Session::buyGoldFrame() { return ffi::exec_BuyGoldFrame(m_session); }
-CommandResponse Session::fortressGemStoneSearch() { return ffi::exec_FortressGemStoneSearch(m_session); }
-CommandResponse Session::fortressGemStoneSearchCancel() { return ffi::exec_FortressGemStoneSearchCancel(m_session); }
-CommandResponse Session::fortressUpgradeHallOfKnights() { return ffi::exec_FortressUpgradeHallOfKnights(m_session); }
-CommandResponse Session::expeditionContinue() { return ffi::exec_ExpeditionContinue(m_session); }
+FightOutcome Session::fightOutcome(const std::string& name, bool useMushroom)
+{
+    fight(name, useMushroom);
+    ffi::CFightOutcome outcome;
+    if (!ffi::session_fight_outcome(m_session, &outcome))
+        throw ParsingError("The response did not contain the fight");
+    return FightOutcome::fromFfi(outcome);
+}
+
+OtherPlayer Session::viewPlayerOutcome(const std::string& ident)
+{
+    viewPlayer(ident);
+    const ffi::OtherPlayer* player = ffi::session_player_outcome(m_session);
+    if (!player)
+        throw ParsingError("The response did not contain the player");
+    return OtherPlayer::fromFfi(player);
+}
+
+GambleResult Session::gambleSilverOutcome(unsigned long amount)
+{
+    gambleSilver(amount);
+    return gambleOutcome();
+}
+
+GambleResult Session::gambleMushroomsOutcome(unsigned long amount)
+{
+    gambleMushrooms(amount);
+    return gambleOutcome();
+}
+
+GambleResult Session::gambleOutcome()
+{
+    ffi::CGambleResult result;
+    if (!ffi::session_gamble_outcome(m_session, &result))
+        throw ParsingError("The response did not contain the gamble result");
+    return {result.mushrooms, result.change};
+}
+
+std::vector<HallOfFamePlayer> Session::hallOfFamePageOutcome(size_t page)
+{
+    hallOfFamePage(page);
+    std::vector<HallOfFamePlayer> res;
+    size_t len = ffi::session_hall_of_fame_outcome_len(m_session);
+    res.reserve(len);
+    for (size_t i = 0; i < len; ++i)
+        res.push_back(HallOfFamePlayer::fromFfi(ffi::session_hall_of_fame_outcome_player(m_session, i)));
+    return res;
+}
+
+CommandResponse Session::update() { return checked(ffi::exec_Update(m_session)); }
+CommandResponse Session::buyBeer() { return checked(ffi::exec_BuyBeer(m_session)); }
+CommandResponse Session::cancelQuest() { return checked(ffi::exec_CancelQuest(m_session)); }
+CommandResponse Session::finishWork() { return checked(ffi::exec_FinishWork(m_session)); }
+CommandResponse Session::checkArena() { return checked(ffi::exec_CheckArena(m_session)); }
+CommandResponse Session::collectCalendar() { return checked(ffi::exec_CollectCalendar(m_session)); }
+CommandResponse Session::toiletFlush() { return checked(ffi::exec_ToiletFlush(m_session)); }
+CommandResponse Session::toiletOpen() { return checked(ffi::exec_ToiletOpen(m_session)); }
+CommandResponse Session::cancelWork() { return checked(ffi::exec_CancelWork(m_session)); }
+CommandResponse Session::guildLoadMushrooms() { return checked(ffi::exec_GuildLoadMushrooms(m_session)); }
+CommandResponse Session::guildJoinAttack() { return checked(ffi::exec_GuildJoinAttack(m_session)); }
+CommandResponse Session::guildJoinDefense() { return checked(ffi::exec_GuildJoinDefense(m_session)); }
+CommandResponse Session::guildRaid() { return checked(ffi::exec_GuildRaid(m_session)); }
+CommandResponse Session::guildPortalBattle() { return checked(ffi::exec_GuildPortalBattle(m_session)); }
+CommandResponse Session::guildGetFightableTargets() { return checked(ffi::exec_GuildGetFightableTargets(m_session)); }
+CommandResponse Session::viewScrapbook() { return checked(ffi::exec_ViewScrapbook(m_session)); }
+CommandResponse Session::fightPortal() { return checked(ffi::exec_FightPortal(m_session)); }
+CommandResponse Session::swapManequin() { return checked(ffi::exec_SwapManequin(m_session)); }
+CommandResponse Session::idleSacrifice() { return checked(ffi::exec_IdleSacrifice(m_session)); }
+CommandResponse Session::hellevatorEnter() { return checked(ffi::exec_HellevatorEnter(m_session)); }
+CommandResponse Session::hellevatorViewGuildRanking() { return checked(ffi::exec_HellevatorViewGuildRanking(m_session)); }
+CommandResponse Session::hellevatorRefreshShop() { return checked(ffi::exec_HellevatorRefreshShop(m_session)); }
+CommandResponse Session::hellevatorClaimDaily() { return checked(ffi::exec_HellevatorClaimDaily(m_session)); }
+CommandResponse Session::hellevatorClaimDailyYesterday() { return checked(ffi::exec_HellevatorClaimDailyYesterday(m_session)); }
+CommandResponse Session::hellevatorClaimFinal() { return checked(ffi::exec_HellevatorClaimFinal(m_session)); }
+CommandResponse Session::hellevatorPreviewRewards() { return checked(ffi::exec_HellevatorPreviewRewards(m_session)); }
+CommandResponse Session::buyGoldFrame() { return checked(ffi::exec_BuyGoldFrame(m_session)); }
+CommandResponse Session::fortressGemStoneSearch() { return checked(ffi::exec_FortressGemStoneSearch(m_session)); }
+CommandResponse Session::fortressGemStoneSearchCancel() { return checked(ffi::exec_FortressGemStoneSearchCancel(m_session)); }
+CommandResponse Session::fortressUpgradeHallOfKnights() { return checked(ffi::exec_FortressUpgradeHallOfKnights(m_session)); }
+CommandResponse Session::expeditionContinue() { return checked(ffi::exec_ExpeditionContinue(m_session)); }
 
 
 // ###################  Commands with trivial arguments  ################### //
 
-CommandResponse Session::hallOfFamePage(size_t page) { return ffi::exec_HallOfFamePage(m_session, page); }
-CommandResponse Session::hallOfFameFortressPage(size_t page) { return ffi::exec_HallOfFameFortressPage(m_session, page); }
-CommandResponse Session::viewPlayer(const std::string& ident) { return ffi::exec_ViewPlayer(m_session, to_ffi_str(ident)); }
-CommandResponse Session::startQuest(size_t questPos, bool overwriteInv) { return ffi::exec_StartQuest(m_session, questPos, overwriteInv); }
-CommandResponse Session::removePotion(size_t pos) { return ffi::exec_RemovePotion(m_session, pos); }
-CommandResponse Session::fight(const std::string& name, bool useMushroom) { return ffi::exec_Fight(m_session, to_ffi_str(name), useMushroom); }
-CommandResponse Session::guildAttack(const std::string& guild) { return ffi::exec_GuildAttack(m_session, to_ffi_str(guild)); }
-CommandResponse Session::finishQuest(bool skip) { return ffi::exec_FinishQuest(m_session, skip); }
-CommandResponse Session::checkNameAvailable(const std::string& name) { return ffi::exec_CheckNameAvailable(m_session, to_ffi_str(name)); }
-CommandResponse Session::viewGuild(const std::string& guildIdent) { return ffi::exec_ViewGuild(m_session, to_ffi_str(guildIdent)); }
-CommandResponse Session::guildFound(const std::string& name) { return ffi::exec_GuildFound(m_session, to_ffi_str(name)); }
-CommandResponse Session::guildInvitePlayer(const std::string& name) { return ffi::exec_GuildInvitePlayer(m_session, to_ffi_str(name)); }
-CommandResponse Session::guildKickPlayer(const std::string& name) { return ffi::exec_GuildKickPlayer(m_session, to_ffi_str(name)); }
-CommandResponse Session::guildSetLeader(const std::string& name) { return ffi::exec_GuildSetLeader(m_session, to_ffi_str(name)); }
-CommandResponse Session::guildToggleOfficer(const std::string& name) { return ffi::exec_GuildToggleOfficer(m_session, to_ffi_str(name)); }
-CommandResponse Session::messageOpen(int pos) { return ffi::exec_MessageOpen(m_session, pos); }
-CommandResponse Session::messageDelete(int pos) { return ffi::exec_MessageDelete(m_session, pos); }
-CommandResponse Session::viewPet(unsigned short petId) { return ffi::exec_ViewPet(m_session, petId); }
-CommandResponse Session::gambleSilver(unsigned long amount) { return ffi::exec_GambleSilver(m_session, amount); }
-CommandResponse Session::gambleMushrooms(unsigned long amount) { return ffi::exec_GambleMushrooms(m_session, amount); }
-CommandResponse Session::sendMessage(const std::string& to, const std::string& msg) { return ffi::exec_SendMessage(m_session, to_ffi_str(to), to_ffi_str(msg)); }
-CommandResponse Session::whisper(const std::string& playerName, const std::string& message) { return ffi::exec_Whisper(m_session, to_ffi_str(playerName), to_ffi_str(message)); }
-CommandResponse Session::setLanguage(const std::string& language) { return ffi::exec_SetLanguage(m_session, to_ffi_str(language)); }
-CommandResponse Session::fortressNewEnemy(bool useMushroom) { return ffi::exec_FortressNewEnemy(m_session, useMushroom); }
-CommandResponse Session::fortressSetCAEnemy(unsigned int msgId) { return ffi::exec_FortressSetCAEnemy(m_session, msgId); }
-CommandResponse Session::guildPetBattle(bool useMushroom) { return ffi::exec_GuildPetBattle(m_session, useMushroom); }
-CommandResponse Session::hallOfFameGroupPage(unsigned int page) { return ffi::exec_HallOfFameGroupPage(m_session, page); }
-CommandResponse Session::hallOfFameUnderworldPage(unsigned int page) { return ffi::exec_HallOfFameUnderworldPage(m_session, page); }
-CommandResponse Session::hallOfFamePetsPage(unsigned int page) { return ffi::exec_HallOfFamePetsPage(m_session, page); }
-CommandResponse Session::blockGuildInvites(bool blockInvites) { return ffi::exec_BlockGuildInvites(m_session, blockInvites); }
-CommandResponse Session::showTips(bool showTips) { return ffi::exec_ShowTips(m_session, showTips); }
-CommandResponse Session::unlockFeature(long mainIdent, long subIdent) { return ffi::exec_UnlockFeature(m_session, mainIdent, subIdent); }
-CommandResponse Session::expeditionStart(size_t pos) { return ffi::exec_ExpeditionStart(m_session, pos); }
-CommandResponse Session::expeditionPickEncounter(size_t pos) { return ffi::exec_ExpeditionPickEncounter(m_session, pos); }
-CommandResponse Session::expeditionPickReward(size_t pos) { return ffi::exec_ExpeditionPickReward(m_session, pos); }
-CommandResponse Session::hallOfFameHellevatorPage(size_t page) { return ffi::exec_HallOfFameHellevatorPage(m_session, page); }
-CommandResponse Session::claimablePreview(long int msgId) { return ffi::exec_ClaimablePreview(m_session, msgId); }
-CommandResponse Session::claimableClaim(long int msgId) { return ffi::exec_ClaimableClaim(m_session, msgId); }
-CommandResponse Session::hellevatorJoinHellAttack(bool useMushroom, size_t plain) { return ffi::exec_HellevatorJoinHellAttack(m_session, useMushroom, plain); }
-CommandResponse Session::hellevatorFight(bool useMushroom) { return ffi::exec_HellevatorFight(m_session, useMushroom); }
-CommandResponse Session::fortressGemStoneSearchFinish(unsigned int mushrooms) { return ffi::exec_FortressGemStoneSearchFinish(m_session, mushrooms); }
-CommandResponse Session::fortressAttack(unsigned int soldiers) { return ffi::exec_FortressAttack(m_session, soldiers); }
-CommandResponse Session::fortressGatherSecretStorage(size_t stone, size_t wood) { return ffi::exec_FortressGatherSecretStorage(m_session, stone, wood); }
+CommandResponse Session::hallOfFamePage(size_t page) { return checked(ffi::exec_HallOfFamePage(m_session, page)); }
+CommandResponse Session::hallOfFameFortressPage(size_t page) { return checked(ffi::exec_HallOfFameFortressPage(m_session, page)); }
+CommandResponse Session::viewPlayer(const std::string& ident) { return checked(ffi::exec_ViewPlayer(m_session, to_ffi_str(ident))); }
+CommandResponse Session::startQuest(size_t questPos, bool overwriteInv) { return checked(ffi::exec_StartQuest(m_session, questPos, overwriteInv)); }
+CommandResponse Session::removePotion(size_t pos) { return checked(ffi::exec_RemovePotion(m_session, pos)); }
+CommandResponse Session::fight(const std::string& name, bool useMushroom) { return checked(ffi::exec_Fight(m_session, to_ffi_str(name), useMushroom)); }
+CommandResponse Session::guildAttack(const std::string& guild) { return checked(ffi::exec_GuildAttack(m_session, to_ffi_str(guild))); }
+CommandResponse Session::finishQuest(bool skip) { return checked(ffi::exec_FinishQuest(m_session, skip)); }
+CommandResponse Session::checkNameAvailable(const std::string& name) { return checked(ffi::exec_CheckNameAvailable(m_session, to_ffi_str(name))); }
+CommandResponse Session::viewGuild(const std::string& guildIdent) { return checked(ffi::exec_ViewGuild(m_session, to_ffi_str(guildIdent))); }
+CommandResponse Session::guildFound(const std::string& name) { return checked(ffi::exec_GuildFound(m_session, to_ffi_str(name))); }
+CommandResponse Session::guildInvitePlayer(const std::string& name) { return checked(ffi::exec_GuildInvitePlayer(m_session, to_ffi_str(name))); }
+CommandResponse Session::guildKickPlayer(const std::string& name) { return checked(ffi::exec_GuildKickPlayer(m_session, to_ffi_str(name))); }
+CommandResponse Session::guildSetLeader(const std::string& name) { return checked(ffi::exec_GuildSetLeader(m_session, to_ffi_str(name))); }
+CommandResponse Session::guildToggleOfficer(const std::string& name) { return checked(ffi::exec_GuildToggleOfficer(m_session, to_ffi_str(name))); }
+CommandResponse Session::messageOpen(int pos) { return checked(ffi::exec_MessageOpen(m_session, pos)); }
+CommandResponse Session::messageDelete(int pos) { return checked(ffi::exec_MessageDelete(m_session, pos)); }
+CommandResponse Session::viewPet(unsigned short petId) { return checked(ffi::exec_ViewPet(m_session, petId)); }
+CommandResponse Session::gambleSilver(unsigned long amount) { return checked(ffi::exec_GambleSilver(m_session, amount)); }
+CommandResponse Session::gambleMushrooms(unsigned long amount) { return checked(ffi::exec_GambleMushrooms(m_session, amount)); }
+CommandResponse Session::sendMessage(const std::string& to, const std::string& msg) { return checked(ffi::exec_SendMessage(m_session, to_ffi_str(to), to_ffi_str(msg))); }
+CommandResponse Session::whisper(const std::string& playerName, const std::string& message) { return checked(ffi::exec_Whisper(m_session, to_ffi_str(playerName), to_ffi_str(message))); }
+CommandResponse Session::setLanguage(const std::string& language) { return checked(ffi::exec_SetLanguage(m_session, to_ffi_str(language))); }
+CommandResponse Session::fortressNewEnemy(bool useMushroom) { return checked(ffi::exec_FortressNewEnemy(m_session, useMushroom)); }
+CommandResponse Session::fortressSetCAEnemy(unsigned int msgId) { return checked(ffi::exec_FortressSetCAEnemy(m_session, msgId)); }
+CommandResponse Session::guildPetBattle(bool useMushroom) { return checked(ffi::exec_GuildPetBattle(m_session, useMushroom)); }
+CommandResponse Session::hallOfFameGroupPage(unsigned int page) { return checked(ffi::exec_HallOfFameGroupPage(m_session, page)); }
+CommandResponse Session::hallOfFameUnderworldPage(unsigned int page) { return checked(ffi::exec_HallOfFameUnderworldPage(m_session, page)); }
+CommandResponse Session::hallOfFamePetsPage(unsigned int page) { return checked(ffi::exec_HallOfFamePetsPage(m_session, page)); }
+CommandResponse Session::blockGuildInvites(bool blockInvites) { return checked(ffi::exec_BlockGuildInvites(m_session, blockInvites)); }
+CommandResponse Session::showTips(bool showTips) { return checked(ffi::exec_ShowTips(m_session, showTips)); }
+CommandResponse Session::unlockFeature(long mainIdent, long subIdent) { return checked(ffi::exec_UnlockFeature(m_session, mainIdent, subIdent)); }
+CommandResponse Session::expeditionStart(size_t pos) { return checked(ffi::exec_ExpeditionStart(m_session, pos)); }
+CommandResponse Session::expeditionPickEncounter(size_t pos) { return checked(ffi::exec_ExpeditionPickEncounter(m_session, pos)); }
+CommandResponse Session::expeditionPickReward(size_t pos) { return checked(ffi::exec_ExpeditionPickReward(m_session, pos)); }
+CommandResponse Session::hallOfFameHellevatorPage(size_t page) { return checked(ffi::exec_HallOfFameHellevatorPage(m_session, page)); }
+CommandResponse Session::claimablePreview(long int msgId) { return checked(ffi::exec_ClaimablePreview(m_session, msgId)); }
+CommandResponse Session::claimableClaim(long int msgId) { return checked(ffi::exec_ClaimableClaim(m_session, msgId)); }
+CommandResponse Session::hellevatorJoinHellAttack(bool useMushroom, size_t plain) { return checked(ffi::exec_HellevatorJoinHellAttack(m_session, useMushroom, plain)); }
+CommandResponse Session::hellevatorFight(bool useMushroom) { return checked(ffi::exec_HellevatorFight(m_session, useMushroom)); }
+CommandResponse Session::fortressGemStoneSearchFinish(unsigned int mushrooms) { return checked(ffi::exec_FortressGemStoneSearchFinish(m_session, mushrooms)); }
+CommandResponse Session::fortressAttack(unsigned int soldiers) { return checked(ffi::exec_FortressAttack(m_session, soldiers)); }
+CommandResponse Session::fortressGatherSecretStorage(size_t stone, size_t wood) { return checked(ffi::exec_FortressGatherSecretStorage(m_session, stone, wood)); }
+CommandResponse Session::login(const std::string& username, const std::string& pwHash, unsigned int loginCount) { return checked(ffi::exec_Login(m_session, to_ffi_str(username), to_ffi_str(pwHash), loginCount)); }
+CommandResponse Session::ssoLogin(const std::string& uuid, const std::string& characterId, const std::string& bearerToken) { return checked(ffi::exec_SSOLogin(m_session, to_ffi_str(uuid), to_ffi_str(characterId), to_ffi_str(bearerToken))); }
+CommandResponse Session::changePassword(const std::string& username, const std::string& oldPassword, const std::string& newPassword) { return checked(ffi::exec_ChangePassword(m_session, to_ffi_str(username), to_ffi_str(oldPassword), to_ffi_str(newPassword))); }
+CommandResponse Session::changeMailAddress(const std::string& oldMail, const std::string& newMail, const std::string& password, const std::string& username) { return checked(ffi::exec_ChangeMailAddress(m_session, to_ffi_str(oldMail), to_ffi_str(newMail), to_ffi_str(password), to_ffi_str(username))); }
+CommandResponse Session::collectDailyQuestReward(size_t pos) { return checked(ffi::exec_CollectDailyQuestReward(m_session, pos)); }
+CommandResponse Session::collectEventTaskReward(size_t pos) { return checked(ffi::exec_CollectEventTaskReward(m_session, pos)); }
+CommandResponse Session::fightTower(uint8_t currentLevel, bool useMushroom) { return checked(ffi::exec_FightTower(m_session, currentLevel, useMushroom)); }
+CommandResponse Session::petFeed(unsigned int petId, unsigned int fruitIdx) { return checked(ffi::exec_PetFeed(m_session, petId, fruitIdx)); }
+CommandResponse Session::underworldAttack(uint32_t playerId) { return checked(ffi::exec_UnderworldAttack(m_session, playerId)); }
+CommandResponse Session::setPortraitFrame(long int portraitId) { return checked(ffi::exec_SetPortraitFrame(m_session, portraitId)); }
+CommandResponse Session::guildSendChat(const std::string& message) { return checked(ffi::exec_GuildSendChat(m_session, to_ffi_str(message))); }
+CommandResponse Session::setDescription(const std::string& description) { return checked(ffi::exec_SetDescription(m_session, to_ffi_str(description))); }
+CommandResponse Session::guildSetInfo(const std::string& description, const std::string& emblem) { return checked(ffi::exec_GuildSetInfo(m_session, to_ffi_str(description), to_ffi_str(emblem))); }
+CommandResponse Session::updateFlag(const std::string& countryCode) { return checked(ffi::exec_UpdateFlag(m_session, to_ffi_str(countryCode))); }
+
+CommandResponse Session::custom(const std::string& cmdName, const std::vector<std::string>& arguments)
+{
+    std::vector<const ffi_char_t*> args;
+    args.reserve(arguments.size());
+    for (const std::string& arg : arguments)
+        args.push_back(to_ffi_str(arg));
+
+    return checked(ffi::exec_Custom(m_session, to_ffi_str(cmdName), args.data(), args.size()));
+}
 
 CommandResponse Session::startWork(unsigned short hours) {
     static uint8_t MAX_VAL = std::numeric_limits<uint8_t>::max();
     if (hours > MAX_VAL)
         throw std::runtime_error("Exceeded maximum hours (" + std::to_string(MAX_VAL) + ")");
 
-    return ffi::exec_StartWork(m_session, (uint8_t) hours);
+    return checked(ffi::exec_StartWork(m_session, (uint8_t) hours));
 }
 
 
 // ###################  Commands with custom enum types  ################### //
 
-CommandResponse Session::fortressBuild(FortressBuildingType building) { return ffi::exec_FortressBuild(m_session, static_cast<uint8_t>(building)); }
-CommandResponse Session::fortressBuildCancel(FortressBuildingType building) { return ffi::exec_FortressBuildCancel(m_session, static_cast<uint8_t>(building)); }
-CommandResponse Session::fortressBuildFinish(FortressBuildingType building, unsigned int mushrooms) { return ffi::exec_FortressBuildFinish(m_session, static_cast<uint8_t>(building), mushrooms); }
-CommandResponse Session::fortressBuildUnit(FortressUnitType unit, unsigned int count) { return ffi::exec_FortressBuildUnit(m_session, static_cast<uint8_t>(unit), count); }
-CommandResponse Session::fortressGather(FortressResourceType resource) { return ffi::exec_FortressGather(m_session, static_cast<uint8_t>(resource)); }
-CommandResponse Session::underworldCollect(UnderworldResourceType resource) { return ffi::exec_UnderworldCollect(m_session, static_cast<uint8_t>(resource)); }
-CommandResponse Session::underworldUnitUpgrade(UnderworldUnitType unit) { return ffi::exec_UnderworldUnitUpgrade(m_session, static_cast<uint8_t>(unit)); }
-CommandResponse Session::underworldUpgradeCancel(UnderworldUnitType unit) { return ffi::exec_UnderworldUpgradeCancel(m_session, static_cast<uint8_t>(unit)); }
-CommandResponse Session::underworldUpgradeFinish(UnderworldBuildingType building, unsigned int mushrooms) { return ffi::exec_UnderworldUpgradeFinish(m_session, static_cast<uint8_t>(building), mushrooms); }
-CommandResponse Session::spinWheelOfFortune(FortunePaymentType payment) { return ffi::exec_SpinWheelOfFortune(m_session, static_cast<uint8_t>(payment)); }
-CommandResponse Session::buyMount(MountType mount) { return ffi::exec_BuyMount(m_session, static_cast<uint8_t>(mount)); }
-CommandResponse Session::increaseAttribute(AttributeType attribute, unsigned int increaseTo) { return ffi::exec_IncreaseAttribute(m_session, static_cast<uint8_t>(attribute), increaseTo); }
-CommandResponse Session::guildIncreaseSkill(GuildSkillType skill, unsigned short current) { return ffi::exec_GuildIncreaseSkill(m_session, static_cast<uint8_t>(skill), current); }
-CommandResponse Session::toiletDrop(PlayerItemPlaceType inventory, size_t pos) { return ffi::exec_ToiletDrop(m_session, static_cast<uint8_t>(inventory), pos); }
-CommandResponse Session::expeditionSkipWait(TimeSkipType type) { return ffi::exec_ExpeditionSkipWait(m_session, static_cast<uint8_t>(type)); }
-CommandResponse Session::hellevatorBuy(size_t position, HellevatorTreatType treat, unsigned int price, bool useMushroom) { return ffi::exec_HellevatorBuy(m_session, position, static_cast<uint8_t>(treat), price, useMushroom); }
+CommandResponse Session::fortressBuild(FortressBuildingType building) { return checked(ffi::exec_FortressBuild(m_session, static_cast<uint8_t>(building))); }
+CommandResponse Session::fortressBuildCancel(FortressBuildingType building) { return checked(ffi::exec_FortressBuildCancel(m_session, static_cast<uint8_t>(building))); }
+CommandResponse Session::fortressBuildFinish(FortressBuildingType building, unsigned int mushrooms) { return checked(ffi::exec_FortressBuildFinish(m_session, static_cast<uint8_t>(building), mushrooms)); }
+CommandResponse Session::fortressBuildUnit(FortressUnitType unit, unsigned int count) { return checked(ffi::exec_FortressBuildUnit(m_session, static_cast<uint8_t>(unit), count)); }
+CommandResponse Session::fortressGather(FortressResourceType resource) { return checked(ffi::exec_FortressGather(m_session, static_cast<uint8_t>(resource))); }
+CommandResponse Session::underworldCollect(UnderworldResourceType resource) { return checked(ffi::exec_UnderworldCollect(m_session, static_cast<uint8_t>(resource))); }
+CommandResponse Session::underworldUnitUpgrade(UnderworldUnitType unit) { return checked(ffi::exec_UnderworldUnitUpgrade(m_session, static_cast<uint8_t>(unit))); }
+CommandResponse Session::underworldUpgradeCancel(UnderworldUnitType unit) { return checked(ffi::exec_UnderworldUpgradeCancel(m_session, static_cast<uint8_t>(unit))); }
+CommandResponse Session::underworldUpgradeFinish(UnderworldBuildingType building, unsigned int mushrooms) { return checked(ffi::exec_UnderworldUpgradeFinish(m_session, static_cast<uint8_t>(building), mushrooms)); }
+CommandResponse Session::spinWheelOfFortune(FortunePaymentType payment) { return checked(ffi::exec_SpinWheelOfFortune(m_session, static_cast<uint8_t>(payment))); }
+CommandResponse Session::buyMount(MountType mount) { return checked(ffi::exec_BuyMount(m_session, static_cast<uint8_t>(mount))); }
+CommandResponse Session::increaseAttribute(AttributeType attribute, unsigned int increaseTo) { return checked(ffi::exec_IncreaseAttribute(m_session, static_cast<uint8_t>(attribute), increaseTo)); }
+CommandResponse Session::guildIncreaseSkill(GuildSkillType skill, unsigned short current) { return checked(ffi::exec_GuildIncreaseSkill(m_session, static_cast<uint8_t>(skill), current)); }
+CommandResponse Session::toiletDrop(PlayerItemPlaceType inventory, size_t pos) { return checked(ffi::exec_ToiletDrop(m_session, static_cast<uint8_t>(inventory), pos)); }
+CommandResponse Session::expeditionSkipWait(TimeSkipType type) { return checked(ffi::exec_ExpeditionSkipWait(m_session, static_cast<uint8_t>(type))); }
+CommandResponse Session::hellevatorBuy(size_t position, HellevatorTreatType treat, unsigned int price, bool useMushroom) { return checked(ffi::exec_HellevatorBuy(m_session, position, static_cast<uint8_t>(treat), price, useMushroom)); }
+CommandResponse Session::registerCharacter(const std::string& username, const std::string& password, GenderType gender, RaceType race, ClassType classType) { return checked(ffi::exec_Register(m_session, to_ffi_str(username), to_ffi_str(password), static_cast<uint8_t>(gender), static_cast<uint8_t>(race), static_cast<uint8_t>(classType))); }
+CommandResponse Session::upgradeSkill(AttributeType attribute, unsigned int nextAttribute) { return checked(ffi::exec_UpgradeSkill(m_session, static_cast<uint8_t>(attribute), nextAttribute)); }
+CommandResponse Session::underworldUpgradeStart(UnderworldBuildingType building, unsigned int mushrooms) { return checked(ffi::exec_UnderworldUpgradeStart(m_session, static_cast<uint8_t>(building), mushrooms)); }
+CommandResponse Session::setQuestsInsteadOfExpeditions(ExpeditionSettingType value) { return checked(ffi::exec_SetQuestsInsteadOfExpeditions(m_session, static_cast<uint8_t>(value))); }
+CommandResponse Session::setPlayerRelation(uint32_t playerId, RelationshipType relation) { return checked(ffi::exec_SetPlayerRelation(m_session, playerId, static_cast<int8_t>(relation))); }
+CommandResponse Session::idleUpgrade(IdleBuildingType building, uint64_t amount) { return checked(ffi::exec_IdleUpgrade(m_session, static_cast<uint8_t>(building), amount)); }
+CommandResponse Session::fightPetOpponent(HabitatType habitat, uint32_t opponentId) { return checked(ffi::exec_FightPetOpponent(m_session, static_cast<uint8_t>(habitat), opponentId)); }
+CommandResponse Session::fightPetDungeon(bool useMushroom, HabitatType habitat, unsigned int enemyPos, unsigned int playerPetId) { return checked(ffi::exec_FightPetDungeon(m_session, useMushroom, static_cast<uint8_t>(habitat), enemyPos, playerPetId)); }
+CommandResponse Session::equipCompanion(InventoryType fromInventory, uint8_t fromPos, CompanionClassType toCompanion, EquipmentSlotType toSlot) { return checked(ffi::exec_EquipCompanion(m_session, static_cast<uint8_t>(fromInventory), fromPos, static_cast<uint8_t>(toCompanion), static_cast<uint8_t>(toSlot))); }
+CommandResponse Session::itemMove(ItemPlaceType from, size_t fromPos, ItemPlaceType to, size_t toPos) { return checked(ffi::exec_ItemMove(m_session, static_cast<uint8_t>(from), fromPos, static_cast<uint8_t>(to), toPos)); }
+CommandResponse Session::swapRunes(ItemPlaceType from, size_t fromPos, ItemPlaceType to, size_t toPos) { return checked(ffi::exec_SwapRunes(m_session, static_cast<uint8_t>(from), fromPos, static_cast<uint8_t>(to), toPos)); }
+CommandResponse Session::usePotion(ItemPlaceType from, size_t fromPos) { return checked(ffi::exec_UsePotion(m_session, static_cast<uint8_t>(from), fromPos)); }
+CommandResponse Session::changeItemLook(ItemPlaceType inventory, size_t pos, uint16_t rawModelId) { return checked(ffi::exec_ChangeItemLook(m_session, static_cast<uint8_t>(inventory), pos, rawModelId)); }
+CommandResponse Session::witchEnchant(EnchantmentType enchantment) { return checked(ffi::exec_WitchEnchant(m_session, static_cast<uint8_t>(enchantment))); }
+CommandResponse Session::inventoryMove(PlayerItemPlaceType from, size_t fromPos, PlayerItemPlaceType to, size_t toPos) { return checked(ffi::exec_InventoryMove(m_session, static_cast<uint8_t>(from), fromPos, static_cast<uint8_t>(to), toPos)); }
+CommandResponse Session::buyShop(ShopType shop, size_t shopPos, PlayerItemPlaceType inventory, size_t inventoryPos) { return checked(ffi::exec_BuyShop(m_session, static_cast<uint8_t>(shop), shopPos, static_cast<uint8_t>(inventory), inventoryPos)); }
+CommandResponse Session::sellShop(PlayerItemPlaceType inventory, size_t inventoryPos) { return checked(ffi::exec_SellShop(m_session, static_cast<uint8_t>(inventory), inventoryPos)); }
+CommandResponse Session::refreshShop(ShopType shop) { return checked(ffi::exec_RefreshShop(m_session, static_cast<uint8_t>(shop))); }
+CommandResponse Session::witchDropCauldron(PlayerItemPlaceType inventory, size_t pos) { return checked(ffi::exec_WitchDropCauldron(m_session, static_cast<uint8_t>(inventory), pos)); }
+CommandResponse Session::blacksmith(PlayerItemPlaceType inventory, uint8_t pos, BlacksmithActionType action) { return checked(ffi::exec_Blacksmith(m_session, static_cast<uint8_t>(inventory), pos, static_cast<uint8_t>(action))); }
+CommandResponse Session::fightDungeon(DungeonType type, uint8_t dungeonId, bool useMushroom) { return checked(ffi::exec_FightDungeon(m_session, { static_cast<uint8_t>(type), dungeonId }, useMushroom)); }
+
+CommandResponse Session::rollDice(RollDicePriceType payment, const std::array<DiceType, 5>& dices)
+{
+    std::array<uint8_t, 5> rawDices;
+    for (size_t i = 0; i < dices.size(); ++i)
+        rawDices[i] = static_cast<uint8_t>(dices[i]);
+
+    return checked(ffi::exec_RollDice(m_session, static_cast<uint8_t>(payment), rawDices.data()));
+}
 
 
 CommandResponse::CommandResponse(ffi::Response* response)
@@ -189,4 +750,15 @@ std::string CommandResponse::getValue(const std::string& key) const
     return val;
 }
 
+std::string CommandResponse::toJson() const
+{
+    ffi_char_t* json = ffi::response_to_json(m_response);
+    if (!json)
+        throwLastError();
+
+    std::string res = to_str(json);
+    ffi::destr_string(json);
+    return res;
+}
+
 } // namespace sf
\ No newline at end of file
@@ -1,27 +1,237 @@
 #pragma once
+#include <array>
+#include <chrono>
+#include <functional>
+#include <memory>
+#include <optional>
 #include <string>
 #include <unordered_set>
+#include <vector>
 #include "sf_enums.h"
+#include "sf_error.h"
+#include "sf_gamestate.h"
 
 
 namespace ffi
 {
-    class Session;
+    class GameSession;
     class Response;
+    class ServerConnection;
+    struct CommandTicket;
 }
 
 namespace sf
 {
 
 class CommandResponse;
+class PendingCommand;
 
 
+// How much the different parts of a quest are worth when rating them. XP and silver are relative to the best quest
+struct QuestWeights
+{
+    double experience = 1.0;
+    double silver = 1.0;
+    double newMonster = 1.0;
+    double newItem = 1.0;
+};
+
+
+struct QuestRating
+{
+    size_t questPos;
+    double experiencePerSec;
+    double silverPerSec;
+    bool newMonster;
+    bool newItem;
+    bool startable;
+    double score;
+};
+
+
+// How many fights Session::estimateFight() simulates
+struct EstimateOptions
+{
+    // The maximum, if targetMargin is set
+    uint32_t samples = 10000;
+    // Stops once the win rate is known this precisely, like 0.02 for ±2%. 0 disables this
+    double targetMargin = 0.0;
+    // 0 uses all available cores
+    size_t threads = 0;
+    // The same seed always gives the same estimate
+    uint64_t seed = 0;
+};
+
+
+// The total damage one side has dealt per fight
+struct DamageDistribution
+{
+    int64_t min;
+    int64_t max;
+    double mean;
+    double stdDev;
+};
+
+
+// Simulated fights of the own character against another player
+struct FightEstimate
+{
+    uint32_t samples;
+    uint32_t wins;
+    double winRate;
+    // The 95% confidence interval of winRate
+    double lower;
+    double upper;
+    double averageRounds;
+    DamageDistribution ownDamage;
+    DamageDistribution otherDamage;
+
+    // Half the width of the confidence interval
+    double margin() const;
+    // Like "73% ±2%"
+    std::string toString() const;
+};
+
+
+// How a session recovers from lost connections and expired sessions. Other errors are thrown right away.
+// After a lost connection only commands, that just look at things, like update(), are retried, because the others might
+// have already reached the server. The same goes for expired sessions, because their error messages are not confirmed yet
+struct RecoveryPolicy
+{
+    // 0 disables the recovery
+    uint32_t maxRetries = 3;
+    // Doubled with every retry, up to maxBackoff
+    std::chrono::milliseconds backoff{1000};
+    std::chrono::milliseconds maxBackoff{30000};
+    // Log in again, if the server says the session has expired
+    bool relogin = true;
+    // Renew the credentials of SSO characters, if logging in again fails
+    bool renewSso = true;
+};
+
+
+enum class RecoveryEventKind
+{
+    Retry,
+    Relogin,
+    SsoRenewed,
+    ReloginFailed,
+};
+
+
+struct RecoveryEvent
+{
+    RecoveryEventKind kind;
+    // Starts at 1. 0 for logins before the first attempt to send the command
+    uint32_t attempt;
+    // The error, that caused this. None for Relogin
+    ErrorKind error;
+};
+
+
+// The amount of requests, that can be sent in some time frame. 0 requests disables the budget
+struct RateBudget
+{
+    uint32_t requests = 0;
+    std::chrono::milliseconds interval{0};
+    // The amount of requests, that can be sent right after each other, after nothing has been sent for a while
+    uint32_t burst = 1;
+};
+
+
+// How fast the sessions of a ServerConnection send their requests. Nothing is limited by default
+struct RateLimit
+{
+    RateBudget perSession;
+    // Shared by all sessions of the same ServerConnection
+    RateBudget perServer;
+    // A random delay between 0 and this is added before every request
+    std::chrono::milliseconds jitter{0};
+
+    // Limits, that should look like a human clicking through the game. Use these for crawling the Hall of Fame
+    static RateLimit conservative();
+};
+
+
+// All the commands throw one of the exceptions in sf_error.h, if they fail
 class Session
 {
 public:
     Session(const std::string& username, const std::string& password, const std::string& serverUrl);
+    // Takes ownership of a session, that has not been logged in yet, like the ones from SsoCharacterList, and logs it in
+    explicit Session(ffi::GameSession* session);
     ~Session();
 
+    Session(const Session&) = delete;
+    Session& operator=(const Session&) = delete;
+
+    // The state of the game after the last command. Empty, if the session is not logged in
+    std::optional<GameState> gameState() const;
+    // The full game state as JSON, or only one field of it, like "character", "fortress", "guild", "hall_of_fames"
+    // or "lookup". Empty, if the session is not logged in
+    std::optional<std::string> gameStateJson(const std::string& field = {}) const;
+
+    // The items a player, that has been looked at with viewPlayer(), has equipped and are missing from the own scrapbook
+    std::vector<EquipmentIdent> missingScrapbookItems(const std::string& playerName) const;
+    std::vector<EquipmentIdent> missingScrapbookItems(uint32_t playerId) const;
+
+    // The share of `iterations` simulated fights, that the own character wins against a player, that has been looked at
+    std::optional<double> winProbability(const std::string& playerName, uint32_t iterations) const;
+    std::optional<double> winProbability(uint32_t playerId, uint32_t iterations) const;
+    // Simulates the fights on multiple threads and also gives the confidence interval of the win rate
+    std::optional<FightEstimate> estimateFight(const std::string& playerName, const EstimateOptions& options = {}) const;
+    std::optional<FightEstimate> estimateFight(uint32_t playerId, const EstimateOptions& options = {}) const;
+    // Simulates one fight and returns everything, that happened in it, as JSON. The same seed always gives the same fight
+    std::optional<std::string> simulateFightJson(const std::string& playerName, uint64_t seed) const;
+    std::optional<std::string> simulateFightJson(uint32_t playerId, uint64_t seed) const;
+
+    // Records the scrapbook into the history in `dir` after every update() and viewScrapbook(). See ScrapbookHistory
+    void setScrapbookHistory(const std::string& dir);
+    // The server name, that the scrapbook history uses for this session
+    std::string serverHost() const;
+
+    // Gets new credentials from the SSO account of this character. Only works for sessions from an SsoAccount
+    void renewSsoCredentials();
+
+    // Logs in and writes every command, including the login, and the response of the server into the cassette file
+    static std::unique_ptr<Session> recording(const std::string& username, const std::string& password,
+        const std::string& serverUrl, const std::string& cassettePath);
+    // Logs in using a cassette file, that has been recorded with recording(), and answers all commands from it
+    // instead of talking to the server
+    static std::unique_ptr<Session> replaying(const std::string& username, const std::string& password,
+        const std::string& serverUrl, const std::string& cassettePath);
+
+    // Writes every further command and the response of the server into the cassette file at `path`
+    void recordCassette(const std::string& path);
+    // Answers all further commands with the responses from a cassette file instead of talking to the server
+    void replayCassette(const std::string& path);
+
+    RecoveryPolicy recoveryPolicy() const;
+    void setRecoveryPolicy(const RecoveryPolicy& policy);
+    // Called with everything the recovery does, like logging in again. For background commands this is called from
+    // a background thread
+    void onRecovery(std::function<void(const RecoveryEvent&)> callback);
+
+    // The tavern quests rated by xp/silver per second and new scrapbook entries. The best quest is at the front
+    std::vector<QuestRating> rateQuests(const QuestWeights& weights = {}) const;
+    // The quest, that startRecommendedQuest() would start. Empty, if the character is busy or can not start any quest
+    std::optional<size_t> recommendedQuest(const QuestWeights& weights = {}) const;
+    // Starts the best quest. The response is invalid, if there is no quest to start
+    CommandResponse startRecommendedQuest(const QuestWeights& weights = {});
+
+    // Starts a command in the background. `command` has to call exactly one command on the session it is given, like
+    // [](Session& s) { return s.update(); }. `onFinished` is called from a background thread, once the command is done.
+    // The session must not be used until then
+    PendingCommand runAsync(const std::function<CommandResponse(Session&)>& command, std::function<void()> onFinished = {});
+
+    /* Commands, that return their direct result. Throw a ParsingError, if the response did not contain it */
+    FightOutcome fightOutcome(const std::string& name, bool useMushroom);
+    OtherPlayer viewPlayerOutcome(const std::string& ident);
+    GambleResult gambleSilverOutcome(unsigned long amount);
+    GambleResult gambleMushroomsOutcome(unsigned long amount);
+    std::vector<HallOfFamePlayer> hallOfFamePageOutcome(size_t page);
+
+
     /* No-argument commands */
     CommandResponse update();
     CommandResponse buyBeer();
@@ -101,6 +311,22 @@ public:
     CommandResponse fortressGemStoneSearchFinish(unsigned int mushrooms);
     CommandResponse fortressAttack(unsigned int soldiers);
     CommandResponse fortressGatherSecretStorage(size_t stone, size_t wood);
+    CommandResponse login(const std::string& username, const std::string& pwHash, unsigned int loginCount);
+    CommandResponse ssoLogin(const std::string& uuid, const std::string& characterId, const std::string& bearerToken);
+    CommandResponse changePassword(const std::string& username, const std::string& oldPassword, const std::string& newPassword);
+    CommandResponse changeMailAddress(const std::string& oldMail, const std::string& newMail, const std::string& password, const std::string& username);
+    CommandResponse custom(const std::string& cmdName, const std::vector<std::string>& arguments);
+    CommandResponse collectDailyQuestReward(size_t pos);
+    CommandResponse collectEventTaskReward(size_t pos);
+    CommandResponse fightTower(uint8_t currentLevel, bool useMushroom);
+    CommandResponse petFeed(unsigned int petId, unsigned int fruitIdx);
+    CommandResponse underworldAttack(uint32_t playerId);
+    CommandResponse setPortraitFrame(long int portraitId);
+    CommandResponse guildSendChat(const std::string& message);
+    CommandResponse setDescription(const std::string& description);
+    CommandResponse guildSetInfo(const std::string& description, const std::string& emblem);
+    // An empty country code removes the flag
+    CommandResponse updateFlag(const std::string& countryCode);
 
 
     /* Commands with custom enum types */
@@ -120,10 +346,78 @@ public:
     CommandResponse toiletDrop(PlayerItemPlaceType inventory, size_t pos);
     CommandResponse expeditionSkipWait(TimeSkipType type);
     CommandResponse hellevatorBuy(size_t position, HellevatorTreatType treat, unsigned int price, bool useMushroom);
+    CommandResponse registerCharacter(const std::string& username, const std::string& password, GenderType gender, RaceType race, ClassType classType);
+    CommandResponse upgradeSkill(AttributeType attribute, unsigned int nextAttribute);
+    CommandResponse underworldUpgradeStart(UnderworldBuildingType building, unsigned int mushrooms);
+    CommandResponse setQuestsInsteadOfExpeditions(ExpeditionSettingType value);
+    CommandResponse setPlayerRelation(uint32_t playerId, RelationshipType relation);
+    CommandResponse idleUpgrade(IdleBuildingType building, uint64_t amount);
+    CommandResponse fightPetOpponent(HabitatType habitat, uint32_t opponentId);
+    CommandResponse fightPetDungeon(bool useMushroom, HabitatType habitat, unsigned int enemyPos, unsigned int playerPetId);
+    CommandResponse equipCompanion(InventoryType fromInventory, uint8_t fromPos, CompanionClassType toCompanion, EquipmentSlotType toSlot);
+    CommandResponse itemMove(ItemPlaceType from, size_t fromPos, ItemPlaceType to, size_t toPos);
+    CommandResponse swapRunes(ItemPlaceType from, size_t fromPos, ItemPlaceType to, size_t toPos);
+    CommandResponse usePotion(ItemPlaceType from, size_t fromPos);
+    CommandResponse changeItemLook(ItemPlaceType inventory, size_t pos, uint16_t rawModelId);
+    // Only works, if the enchantment has been unlocked at the witch
+    CommandResponse witchEnchant(EnchantmentType enchantment);
+    CommandResponse inventoryMove(PlayerItemPlaceType from, size_t fromPos, PlayerItemPlaceType to, size_t toPos);
+    CommandResponse buyShop(ShopType shop, size_t shopPos, PlayerItemPlaceType inventory, size_t inventoryPos);
+    CommandResponse sellShop(PlayerItemPlaceType inventory, size_t inventoryPos);
+    CommandResponse refreshShop(ShopType shop);
+    CommandResponse witchDropCauldron(PlayerItemPlaceType inventory, size_t pos);
+    CommandResponse blacksmith(PlayerItemPlaceType inventory, uint8_t pos, BlacksmithActionType action);
+    CommandResponse fightDungeon(DungeonType type, uint8_t dungeonId, bool useMushroom);
+    CommandResponse rollDice(RollDicePriceType payment, const std::array<DiceType, 5>& dices);
+
+
+private:
+    GambleResult gambleOutcome();
+
+    ffi::GameSession* m_session;
+    std::function<void(const RecoveryEvent&)> m_onRecovery;
+};
+
+
+// A server, that multiple sessions share their rate limit on
+class ServerConnection
+{
+public:
+    explicit ServerConnection(const std::string& serverUrl, const RateLimit& limit = {});
+    ~ServerConnection();
+
+    ServerConnection(const ServerConnection&) = delete;
+    ServerConnection& operator=(const ServerConnection&) = delete;
+
+    // Logs a character in on this server. The session stays valid, even if the connection is destroyed
+    std::unique_ptr<Session> login(const std::string& username, const std::string& password) const;
+
+private:
+    ffi::ServerConnection* m_connection;
+};
+
+
+// A command, that is running in the background. See Session::runAsync()
+class PendingCommand
+{
+public:
+    explicit PendingCommand(ffi::CommandTicket* ticket);
+    ~PendingCommand();
+
+    PendingCommand(PendingCommand&& other) noexcept;
+    PendingCommand& operator=(PendingCommand&& other) noexcept;
+    PendingCommand(const PendingCommand&) = delete;
+    PendingCommand& operator=(const PendingCommand&) = delete;
 
+    // Checks if the command has finished without blocking
+    bool isReady() const;
+    // Blocks, until the command has finished, or the timeout has passed. Returns true, if it has finished
+    bool waitFor(std::chrono::milliseconds timeout) const;
+    // Blocks, until the command has finished. Throws just like the blocking command would have. Can only be called once
+    CommandResponse wait();
 
 private:
-    ffi::Session* m_session;
+    ffi::CommandTicket* m_ticket;
 };
 
 
@@ -137,6 +431,8 @@ public:
 
     std::unordered_set<std::string> getKeys() const;
     std::string getValue(const std::string& key) const;
+    // Save this after a login to build the game state again later with GameState::fromResponseJson()
+    std::string toJson() const;
 
 private:
     ffi::Response* m_response;
@@ -0,0 +1,130 @@
+#include "sf_sso.h"
+
+#include "sf_api.hpp"
+#include "sf_error.h"
+
+namespace
+{
+
+using ffi_char_t = int8_t;
+
+const ffi_char_t* to_ffi_str(const std::string& str)
+{
+    return reinterpret_cast<const ffi_char_t*>(str.c_str());
+}
+
+std::optional<std::string> take_ffi_str(ffi_char_t* ffiStr)
+{
+    if (!ffiStr)
+        return std::nullopt;
+
+    std::string res = reinterpret_cast<const char*>(ffiStr);
+    ffi::destr_string(ffiStr);
+    return res;
+}
+
+} // namespace
+
+
+namespace sf
+{
+
+ServerLookup::ServerLookup(const std::string& path)
+    : m_lookup(ffi::server_lookup_load(to_ffi_str(path)))
+{
+    if (!m_lookup)
+    {
+        throwLastError();
+        throw std::runtime_error("Failed to load the server list");
+    }
+}
+
+ServerLookup::ServerLookup(ffi::ServerLookup* lookup)
+    : m_lookup(lookup) { }
+
+ServerLookup::~ServerLookup()
+{
+    if (m_lookup)
+        ffi::destr_server_lookup(m_lookup);
+}
+
+std::unique_ptr<ServerLookup> ServerLookup::fetch()
+{
+    ffi::ServerLookup* lookup = ffi::server_lookup_fetch();
+    if (!lookup)
+    {
+        throwLastError();
+        throw std::runtime_error("Failed to fetch the server list");
+    }
+    return std::unique_ptr<ServerLookup>(new ServerLookup(lookup));
+}
+
+
+SsoCharacterList::SsoCharacterList(ffi::SSOCharacters* characters)
+    : m_characters(characters) { }
+
+SsoCharacterList::~SsoCharacterList()
+{
+    if (m_characters)
+        ffi::destr_sso_characters(m_characters);
+}
+
+size_t SsoCharacterList::size() const
+{
+    return ffi::sso_characters_len(m_characters);
+}
+
+std::optional<std::string> SsoCharacterList::name(size_t pos) const
+{
+    return take_ffi_str(ffi::sso_characters_name(m_characters, pos));
+}
+
+std::optional<std::string> SsoCharacterList::serverUrl(size_t pos) const
+{
+    return take_ffi_str(ffi::sso_characters_server_url(m_characters, pos));
+}
+
+std::unique_ptr<Session> SsoCharacterList::login(size_t pos)
+{
+    ffi::GameSession* session = ffi::sso_characters_take_session(m_characters, pos);
+    if (!session)
+    {
+        throwLastError();
+        throw std::runtime_error("The character can not be logged in");
+    }
+    return std::make_unique<Session>(session);
+}
+
+
+SsoAccount::SsoAccount(const std::string& username, const std::string& password)
+    : m_account(ffi::sso_account_login(to_ffi_str(username), to_ffi_str(password)))
+{
+    if (!m_account)
+    {
+        throwLastError();
+        throw std::runtime_error("Failed to login the SSO account");
+    }
+}
+
+SsoAccount::~SsoAccount()
+{
+    if (m_account)
+        ffi::destr_sso_account(m_account);
+}
+
+std::unique_ptr<SsoCharacterList> SsoAccount::characters(const ServerLookup* lookup)
+{
+    if (!m_account)
+        throw InvalidArgumentError("The characters of this account have already been fetched");
+
+    ffi::SSOCharacters* characters = ffi::sso_account_characters(m_account, lookup ? lookup->m_lookup : nullptr);
+    m_account = nullptr;
+    if (!characters)
+    {
+        throwLastError();
+        throw std::runtime_error("Failed to fetch the characters");
+    }
+    return std::make_unique<SsoCharacterList>(characters);
+}
+
+} // namespace sf
@@ -0,0 +1,83 @@
+#pragma once
+#include <cstddef>
+#include <memory>
+#include <optional>
+#include <string>
+#include "sf_session.h"
+
+
+namespace ffi
+{
+    class SFAccount;
+    class ServerLookup;
+    struct SSOCharacters;
+}
+
+namespace sf
+{
+
+// The mapping of server ids to server urls, that SSO characters need
+class ServerLookup
+{
+public:
+    // Reads a server list in the format of https://sfgame.net/config.json from disk
+    explicit ServerLookup(const std::string& path);
+    ~ServerLookup();
+
+    ServerLookup(const ServerLookup&) = delete;
+    ServerLookup& operator=(const ServerLookup&) = delete;
+
+    // Downloads the current server list
+    static std::unique_ptr<ServerLookup> fetch();
+
+private:
+    explicit ServerLookup(ffi::ServerLookup* lookup);
+
+    ffi::ServerLookup* m_lookup;
+
+    friend class SsoAccount;
+};
+
+
+// All the characters of an SSO account
+class SsoCharacterList
+{
+public:
+    explicit SsoCharacterList(ffi::SSOCharacters* characters);
+    ~SsoCharacterList();
+
+    SsoCharacterList(const SsoCharacterList&) = delete;
+    SsoCharacterList& operator=(const SsoCharacterList&) = delete;
+
+    size_t size() const;
+
+    // Empty, if the server of the character is unknown, or the character has already been logged in
+    std::optional<std::string> name(size_t pos) const;
+    std::optional<std::string> serverUrl(size_t pos) const;
+
+    // Logs the character in. Every character can only be logged in once. All sessions share the account to renew their credentials
+    std::unique_ptr<Session> login(size_t pos);
+
+private:
+    ffi::SSOCharacters* m_characters;
+};
+
+
+// An S&F SSO account, that can have characters on multiple servers
+class SsoAccount
+{
+public:
+    SsoAccount(const std::string& username, const std::string& password);
+    ~SsoAccount();
+
+    SsoAccount(const SsoAccount&) = delete;
+    SsoAccount& operator=(const SsoAccount&) = delete;
+
+    // Fetches all characters of the account. This consumes the account, so it can not be used afterwards. If no lookup is given, the current server list is downloaded
+    std::unique_ptr<SsoCharacterList> characters(const ServerLookup* lookup = nullptr);
+
+private:
+    ffi::SFAccount* m_account;
+};
+
+} // namespace sf
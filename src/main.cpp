@@ -22,6 +22,9 @@ int main() {
     sf::Session session{ username, password, serverUrl };
     session.update();
 
+    if (std::optional<sf::GameState> gameState = session.gameState())
+        std::cout << "Logged in as " << gameState->character.name << " (level " << gameState->character.level << ")\n";
+
     sf::CommandResponse hallOfFame = session.hallOfFamePage(/*page*/ 0);
     printResponse(hallOfFame);
 